@@ -20,6 +20,10 @@ pub struct Config {
     pub mysql_port: Option<String>,
     pub mysql_db: Option<String>,
     pub migrate: Option<bool>,
+    pub weather_provider: Option<String>,
+    pub weather_url: Option<String>,
+    pub weather_fixture: Option<String>,
+    pub settle_interval: Option<String>,
 }
 // TODO refactor
 impl Config {
@@ -36,6 +40,10 @@ impl Config {
     pub const MYSQL_PORT: &str = "MYSQL_PORT";
     pub const MYSQL_DB: &str = "MYSQL_DB";
     pub const MIGRATE: &str = "MIGRATE";
+    pub const WEATHER_PROVIDER: &str = "WEATHER_PROVIDER";
+    pub const WEATHER_URL: &str = "WEATHER_URL";
+    pub const WEATHER_FIXTURE: &str = "WEATHER_FIXTURE";
+    pub const SETTLE_INTERVAL: &str = "SETTLE_INTERVAL";
 
     pub fn environ() -> Self {
         Self::default()
@@ -55,6 +63,10 @@ impl Config {
             mysql_port: Some("3306".into()),
             mysql_db: Some("db".into()),
             migrate: Some(false),
+            weather_provider: Some("open_meteo".into()),
+            weather_url: Some("https://api.open-meteo.com/v1/forecast".into()),
+            weather_fixture: None,
+            settle_interval: Some("1h".into()),
         }
     }
 
@@ -153,4 +165,33 @@ impl Config {
                 .unwrap_or(migrate.expect("last_resort")),
         )
     }
+
+    pub fn weather_provider(&self) -> String {
+        let Self { weather_provider, .. } = Self::last_resort();
+        self.weather_provider.clone().unwrap_or(
+            std::env::var(Self::WEATHER_PROVIDER).unwrap_or(weather_provider.expect("last_resort")),
+        )
+    }
+
+    pub fn weather_url(&self) -> String {
+        let Self { weather_url, .. } = Self::last_resort();
+        self.weather_url.clone().unwrap_or(
+            std::env::var(Self::WEATHER_URL).unwrap_or(weather_url.expect("last_resort")),
+        )
+    }
+
+    /// path of the forecasts served by the fixture provider, builtin ones if not set
+    pub fn weather_fixture(&self) -> Option<String> {
+        self.weather_fixture.clone().or(std::env::var(Self::WEATHER_FIXTURE).ok())
+    }
+
+    /// how often the outfits planned for the past days are settled
+    pub fn settle_interval(&self) -> Duration {
+        let Self { settle_interval, .. } = Self::last_resort();
+        let interval =
+            std::env::var(Self::SETTLE_INTERVAL).unwrap_or(settle_interval.expect("last_resort"));
+        let std_duration = duration_str::parse(&self.settle_interval.clone().unwrap_or(interval))
+            .unwrap_or_else(|e| panic!("{:?}", e));
+        chrono::Duration::from_std(std_duration).unwrap_or_else(|e| panic!("{}", e))
+    }
 }
@@ -0,0 +1,438 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Router,
+};
+use chrono::Days;
+use entity::{
+    class::{
+        category::Category,
+        color::Color,
+        id::Id,
+        money::{Currency, Money},
+        season::Season,
+        tag::Tag,
+    },
+    error::FieldErrors,
+    model::{closet, item, item_color, item_tag, item_wear},
+};
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::analytics::{self, Concentration, MonthlyCount},
+    AppState,
+};
+
+pub fn analytics_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/added", axum::routing::get(added))
+        .route("/spend", axum::routing::get(spend))
+        .route("/colors", axum::routing::get(colors))
+        .route("/utilisation", axum::routing::get(utilisation))
+        .route("/seasons", axum::routing::get(seasons))
+        .route("/brands", axum::routing::get(brands))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AnalyticsQuery {
+    /// only items of this closet, otherwise of every closet the user is a member of
+    pub closet: Option<Id<closet::Model>>,
+    /// length of the time series, 1 to `MAX_MONTHS`, defaults to `DEFAULT_MONTHS`
+    pub months: Option<u32>,
+    /// window of the utilisation, 1 to `MAX_DAYS`, defaults to `DEFAULT_DAYS`
+    pub days: Option<i64>,
+}
+impl AnalyticsQuery {
+    pub const DEFAULT_MONTHS: u32 = 12;
+    pub const MAX_MONTHS: u32 = 120;
+    pub const DEFAULT_DAYS: i64 = 90;
+    pub const MAX_DAYS: i64 = 3650;
+
+    fn months(&self) -> Result<u32, FieldErrors> {
+        let months = self.months.unwrap_or(Self::DEFAULT_MONTHS);
+        if (1..=Self::MAX_MONTHS).contains(&months) {
+            Ok(months)
+        } else {
+            Err(FieldErrors::of(
+                "months",
+                format!("{} is not between 1 and {}", months, Self::MAX_MONTHS),
+            ))
+        }
+    }
+    fn days(&self) -> Result<i64, FieldErrors> {
+        let days = self.days.unwrap_or(Self::DEFAULT_DAYS);
+        if (1..=Self::MAX_DAYS).contains(&days) {
+            Ok(days)
+        } else {
+            Err(FieldErrors::of(
+                "days",
+                format!("{} is not between 1 and {}", days, Self::MAX_DAYS),
+            ))
+        }
+    }
+
+    /// items of the closets in scope, excluding retired ones unless `retired`
+    fn items(&self, user: &AuthUser, retired: bool) -> Select<item::Entity> {
+        let mut select = item::Entity::find().filter(
+            item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)),
+        );
+        if let Some(closet) = &self.closet {
+            select = select.filter(item::Column::ClosetId.eq(closet.clone()));
+        }
+        if !retired {
+            select = select.filter(item::Column::RetiredOn.is_null());
+        }
+        select
+    }
+}
+
+/// `COUNT(DISTINCT column)`, with the `?` placeholder of MySQL and SQLite
+pub(crate) fn count_distinct<C: ColumnTrait>(column: C) -> SimpleExpr {
+    Expr::cust_with_expr("COUNT(DISTINCT ?)", Expr::col(column))
+}
+
+/// `SUM(column)` of an integer column as an integer, which MySQL would sum into a decimal
+fn sum_integer<C: ColumnTrait>(column: C) -> SimpleExpr {
+    Expr::cust_with_expr("CAST(SUM(?) AS SIGNED)", Expr::col(column))
+}
+
+/// Items added per month in the time zone of the user, including the ones retired since.
+pub async fn added(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Vec<MonthlyCount>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let months = query.months()?;
+    let today = user.user.today();
+    let first_days = analytics::first_days(today, months);
+    let month = analytics::month(item::Column::CreatedAt, &first_days, &user.user.time_zone);
+    let counts: Vec<(Option<String>, i64)> = query
+        .items(&user, true)
+        .select_only()
+        .column_as(month.clone(), "month")
+        .column_as(item::Column::Id.count(), "count")
+        .group_by(month)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    // the items added before the first month have no month
+    let counts: Vec<_> = counts
+        .into_iter()
+        .filter_map(|(month, count)| Some(MonthlyCount { month: month?, count }))
+        .collect();
+    Ok(ApiResponse::Success(analytics::monthly(&counts, today, months)))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CategorySpend {
+    pub category: Option<Category>,
+    /// for each currency
+    pub total: Vec<Money>,
+    pub purchases: i64,
+}
+/// Purchase prices summed per category, including retired items, the most purchases first.
+pub async fn spend(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Vec<CategorySpend>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let sums: Vec<(Option<Category>, Currency, i64, i64)> = query
+        .items(&user, true)
+        .select_only()
+        .column(item::Column::Category)
+        .column(item::Column::PurchasePriceCurrency)
+        .column_as(sum_integer(item::Column::PurchasePriceAmount), "amount")
+        .column_as(item::Column::Id.count(), "count")
+        .filter(item::Column::PurchasePriceAmount.is_not_null())
+        .filter(item::Column::PurchasePriceCurrency.is_not_null())
+        .group_by(item::Column::Category)
+        .group_by(item::Column::PurchasePriceCurrency)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    let mut categories: Vec<_> = sums.iter().map(|(category, _, _, _)| *category).collect();
+    categories.sort_by_key(|c| c.map(|c| c.to_string()));
+    categories.dedup();
+    let mut spends = Vec::new();
+    for category in categories {
+        let summed: Vec<_> = sums.iter().filter(|(c, _, _, _)| c == &category).collect();
+        let mut total: Vec<_> =
+            summed.iter().map(|(_, currency, amount, _)| Money::new(*amount, *currency)).collect();
+        total.sort_by_key(|m| m.currency());
+        let purchases = summed.iter().map(|(_, _, _, count)| count).sum();
+        spends.push(CategorySpend { category, total, purchases });
+    }
+    spends.sort_by_key(|s| std::cmp::Reverse(s.purchases));
+    Ok(ApiResponse::Success(spends))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ColorCount {
+    pub color: Color,
+    /// items with the color anywhere in their palette
+    pub items: i64,
+}
+pub async fn colors(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Vec<ColorCount>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let items = query.items(&user, false).select_only().column(item::Column::Id).into_query();
+    let counts: Vec<(Color, i64)> = item_color::Entity::find()
+        .select_only()
+        .column(item_color::Column::Color)
+        .column_as(count_distinct(item_color::Column::ItemId), "items")
+        .filter(item_color::Column::ItemId.in_subquery(items))
+        .group_by(item_color::Column::Color)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    let mut counts: Vec<_> =
+        counts.into_iter().map(|(color, items)| ColorCount { color, items }).collect();
+    counts.sort_by(|x, y| {
+        y.items.cmp(&x.items).then_with(|| x.color.to_string().cmp(&y.color.to_string()))
+    });
+    Ok(ApiResponse::Success(counts))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Utilisation {
+    pub days: i64,
+    /// items not retired
+    pub items: u64,
+    /// of them, worn at least once in the last `days` days
+    pub worn: i64,
+    /// 0 to 1
+    pub rate: f64,
+}
+pub async fn utilisation(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Utilisation> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let days = query.days()?;
+    let since = user.user.today() - Days::new(days as u64);
+    let items = query.items(&user, false).count(&state.db).await?;
+    let in_scope = query.items(&user, false).select_only().column(item::Column::Id).into_query();
+    let worn: Option<i64> = item_wear::Entity::find()
+        .select_only()
+        .column_as(count_distinct(item_wear::Column::ItemId), "worn")
+        .filter(item_wear::Column::ItemId.in_subquery(in_scope))
+        .filter(item_wear::Column::WornOn.gte(since))
+        .into_tuple()
+        .one(&state.db)
+        .await?;
+    let worn = worn.unwrap_or_default();
+    let rate = if items == 0 { 0.0 } else { worn as f64 / items as f64 };
+    Ok(ApiResponse::Success(Utilisation { days, items, worn, rate }))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SeasonCount {
+    /// `None` for items tagged with no season, which suit all seasons
+    pub season: Option<Season>,
+    pub items: i64,
+}
+/// Items tagged with each season, in the order of [`Season::ALL`].
+pub async fn seasons(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Vec<SeasonCount>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let seasonal = || {
+        item_tag::Entity::find()
+            .filter(item_tag::Column::Tag.is_in(Season::ALL.map(|s| s.to_string())))
+            .filter(item_tag::Column::ItemId.in_subquery(
+                query.items(&user, false).select_only().column(item::Column::Id).into_query(),
+            ))
+    };
+    let counts: Vec<(Tag, i64)> = seasonal()
+        .select_only()
+        .column(item_tag::Column::Tag)
+        .column_as(count_distinct(item_tag::Column::ItemId), "items")
+        .group_by(item_tag::Column::Tag)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    let all_seasons = query
+        .items(&user, false)
+        .filter(item::Column::Id.not_in_subquery(
+            seasonal().select_only().column(item_tag::Column::ItemId).into_query(),
+        ))
+        .count(&state.db)
+        .await?;
+    let mut balance: Vec<_> = Season::ALL
+        .into_iter()
+        .map(|season| SeasonCount {
+            season: Some(season),
+            items: counts
+                .iter()
+                .filter(|(tag, _)| tag.as_str() == season.to_string())
+                .map(|(_, items)| items)
+                .sum(),
+        })
+        .collect();
+    balance.push(SeasonCount { season: None, items: all_seasons as i64 });
+    Ok(ApiResponse::Success(balance))
+}
+
+/// Items per brand, ignoring surrounding whitespace, and how concentrated they are.
+pub async fn brands(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<Concentration> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let counts: Vec<(String, i64)> = query
+        .items(&user, false)
+        .select_only()
+        .column(item::Column::Brand)
+        .column_as(item::Column::Id.count(), "count")
+        .filter(item::Column::Brand.is_not_null())
+        .group_by(item::Column::Brand)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    let counts = counts
+        .into_iter()
+        .map(|(brand, count)| (brand.trim().to_string(), count))
+        .filter(|(brand, _)| !brand.is_empty());
+    Ok(ApiResponse::Success(Concentration::of(counts)))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_analytics_scenario() {
+        use hyper::{Body, Method, StatusCode};
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use super::*;
+        use crate::{
+            call, call_json, configuration::Configuration, handler::item::ItemDetail, request,
+            service::analytics::Concentration, signup, with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let mut ids = Vec::new();
+        for create in [
+            serde_json::json!({ "name": "shirt", "category": "top", "brand": "Acme",
+                "purchase_price": "10.00 USD", "tags": ["summer"] }),
+            serde_json::json!({ "name": "tee", "category": "top", "brand": " Acme",
+                "purchase_price": "10.00 USD", "tags": ["summer", "winter"] }),
+            serde_json::json!({ "name": "boots", "category": "shoes", "brand": "Zed",
+                "purchase_price": "120 EUR" }),
+        ] {
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+        let wear = serde_json::json!({});
+        call_json::<ItemDetail>(
+            &api,
+            Method::POST,
+            &format!("/items/{}/wear", ids[0]),
+            token,
+            Some(wear),
+        )
+        .await;
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, Rgba([30, 40, 70, 255])))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let upload = request(Method::POST, &format!("/items/{}/images", ids[2]), token)
+            .body(Body::from(png));
+        call(&api, upload.unwrap()).await;
+
+        let (status, added) = call_json::<Vec<MonthlyCount>>(
+            &api,
+            Method::GET,
+            "/analytics/added?months=3",
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let added: Vec<_> = added.result().unwrap().iter().map(|m| m.count).collect();
+        assert_eq!(added, vec![0, 0, 3]);
+
+        let (_, spend) =
+            call_json::<Vec<CategorySpend>>(&api, Method::GET, "/analytics/spend", token, None)
+                .await;
+        let spend = spend.result().unwrap();
+        assert_eq!(spend[0].category, Some(Category::Top));
+        assert_eq!(spend[0].total, vec![Money::parse("20.00 USD").unwrap()]);
+        assert_eq!((spend[1].category, spend[1].purchases), (Some(Category::Shoes), 1));
+
+        let (_, colors) =
+            call_json::<Vec<ColorCount>>(&api, Method::GET, "/analytics/colors", token, None).await;
+        assert_eq!(colors.result().unwrap(), &vec![ColorCount { color: Color::Navy, items: 1 }]);
+
+        let (_, utilisation) =
+            call_json::<Utilisation>(&api, Method::GET, "/analytics/utilisation", token, None)
+                .await;
+        let utilisation = utilisation.result().unwrap();
+        assert_eq!((utilisation.items, utilisation.worn), (3, 1));
+        assert_eq!(utilisation.rate, 1.0 / 3.0);
+        for uri in [
+            "/analytics/utilisation?days=0",
+            "/analytics/utilisation?days=100000",
+            "/analytics/added?months=0",
+            "/analytics/added?months=4000000000",
+        ] {
+            let (status, failure) =
+                call_json::<serde_json::Value>(&api, Method::GET, uri, token, None).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", uri);
+            assert!(matches!(failure.result(), Err(ApiError::ValidationError { .. })), "{}", uri);
+        }
+
+        let (_, seasons) =
+            call_json::<Vec<SeasonCount>>(&api, Method::GET, "/analytics/seasons", token, None)
+                .await;
+        let seasons: Vec<_> =
+            seasons.result().unwrap().iter().map(|s| (s.season, s.items)).collect();
+        assert_eq!(
+            seasons,
+            vec![
+                (Some(Season::Spring), 0),
+                (Some(Season::Summer), 2),
+                (Some(Season::Autumn), 0),
+                (Some(Season::Winter), 1),
+                (None, 1),
+            ]
+        );
+
+        let (_, brands) =
+            call_json::<Concentration>(&api, Method::GET, "/analytics/brands", token, None).await;
+        let brands = brands.result().unwrap();
+        assert_eq!((brands.shares[0].value.as_str(), brands.shares[0].count), ("Acme", 2));
+        assert_eq!(brands.top, 2.0 / 3.0);
+
+        let other = signup(&api, "other").await;
+        let (_, none) = call_json::<Concentration>(
+            &api,
+            Method::GET,
+            "/analytics/brands",
+            other.token.as_deref(),
+            None,
+        )
+        .await;
+        assert!(none.result().unwrap().shares.is_empty());
+    }
+}
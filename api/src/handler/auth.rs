@@ -1,15 +1,18 @@
-use axum::{extract::Json, extract::State, Extension, Router};
+use axum::{extract::State, Extension, Router};
 use chrono::Utc;
 use entity::{
     class::{password::Password, username::Username},
-    error::EntityError,
+    error::{EntityError, FieldErrors},
     model::user::{self, InsertUser},
 };
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
+use super::json::Json;
 use crate::{
     middleware::authorization::AuthUser,
     response::{error::ApiError, result::ApiResponse, ApiResult},
@@ -28,7 +31,7 @@ pub fn auth_router() -> Router<AppState> {
         .route("/deactivate", axum::routing::post(deactivate))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct UserCreate {
     pub email: String,
     pub username: String,
@@ -40,11 +43,17 @@ impl TryFrom<UserCreate> for InsertUser {
     fn try_from(
         UserCreate { email, username, password, display_name }: UserCreate,
     ) -> Result<Self, Self::Error> {
-        let (email, username, display_name) =
-            (email.try_into()?, username.try_into()?, display_name);
-        let password = Password::hash(password.as_bytes())?;
-        let is_active = true;
-        Ok(InsertUser { email, username, password, display_name, is_active })
+        let mut errors = FieldErrors::default();
+        let email = errors.check("email", email.try_into());
+        let username = errors.check("username", Username::try_from(username));
+        let password = errors.check("password", Password::hash(password.as_bytes()));
+        match (email, username, password) {
+            (Some(email), Some(username), Some(password)) => {
+                let is_active = true;
+                Ok(InsertUser { email, username, password, display_name, is_active })
+            }
+            _ => Err(errors.into()),
+        }
     }
 }
 pub async fn create(
@@ -56,7 +65,7 @@ pub async fn create(
     Ok(ApiResponse::Success(AuthUser::new(None, created.await?)))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct UserLogin {
     pub username: String,
     pub password: String,
@@ -77,11 +86,22 @@ pub async fn login(
     } else if !user.is_active {
         Err(ApiError::InactiveUserError)?
     }
-
     let (encoding_key, expired) =
         (state.configuration.encoding_key(), state.configuration.jwt_expired());
-    let login = AuthUser::authenticate(user, &state.db, &encoding_key, &expired);
-    Ok(ApiResponse::Success(login.await?))
+    let login = AuthUser::authenticate(user.clone(), &state.db, &encoding_key, &expired).await?;
+
+    // the days planned before are over for good, even if no request settled them yet;
+    // if they cannot be settled now, the next settling does it rather than failing the login
+    let settled = async {
+        let txn = state.db.begin().await?;
+        super::calendar::settle(&txn, &user).await?;
+        txn.commit().await?;
+        Ok::<_, ApiError>(())
+    };
+    if let Err(e) = settled.await {
+        tracing::error!("cannot settle planned outfits of {}: {}", user.username, e);
+    }
+    Ok(ApiResponse::Success(login))
 }
 
 pub async fn whoami(Extension(user): Extension<Option<AuthUser>>) -> ApiResult<Option<AuthUser>> {
@@ -108,3 +128,42 @@ pub async fn deactivate(
     let deactivated = active.update(&state.db);
     Ok(ApiResponse::Success(AuthUser::new(None, deactivated.await?)))
 }
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_create_validation() {
+        use hyper::{Method, StatusCode};
+
+        use crate::{
+            call_json, configuration::Configuration, response::error::ApiError, with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let invalid = serde_json::json!({
+            "email": "not an email",
+            "username": "no spaces allowed",
+            "password": "password",
+            "display_name": "invalid",
+        });
+        let (status, created) =
+            call_json::<()>(&api, Method::POST, "/auth/create", None, Some(invalid)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let ApiError::ValidationError { fields, .. } = created.result().unwrap_err() else {
+            panic!("{:?}", created)
+        };
+        assert_eq!(fields.0.keys().collect::<Vec<_>>(), ["email", "username"]);
+        assert_eq!(fields.0["email"], ["email not an email is invalid"]);
+
+        let missing = serde_json::json!({ "email": "a@closet.test", "username": "a" });
+        let (status, created) =
+            call_json::<()>(&api, Method::POST, "/auth/create", None, Some(missing)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let ApiError::ValidationError { fields, .. } = created.result().unwrap_err() else {
+            panic!("{:?}", created)
+        };
+        assert_eq!(fields.0["password"], ["missing field `password`"]);
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::{
+    body::{Bytes, StreamBody},
+    extract::{Query, State},
+    response::IntoResponse,
+    BoxError, Extension,
+};
+use entity::{
+    class::{availability::Availability, category::Category, color::Color, id::Id, tag::Tag},
+    error::EntityError,
+    model::{
+        closet,
+        item::{self, InsertItem},
+        user,
+    },
+};
+use hyper::{header, StatusCode};
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::{replace_tags, ItemDetail},
+    json::Json,
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::bulk::{self, ImportField, ImportedItem},
+    AppState,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ImportData {
+    /// CSV text with a header row
+    Csv(String),
+    Json(Vec<serde_json::Map<String, serde_json::Value>>),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ItemImport {
+    pub data: ImportData,
+    /// column to field, columns not given here are mapped by their header
+    #[serde(default)]
+    pub mapping: HashMap<String, ImportField>,
+    /// only validate, nothing is created
+    #[serde(default)]
+    pub dry_run: bool,
+    /// defaults to the personal closet
+    pub closet_id: Option<Id<closet::Model>>,
+}
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RowReport {
+    /// 1-based, not counting the header
+    pub row: usize,
+    pub item: Option<ImportedItem>,
+    pub errors: Vec<EntityError>,
+}
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ImportReport {
+    pub columns: Vec<String>,
+    pub mapping: BTreeMap<String, ImportField>,
+    /// ignored columns, which may need an explicit mapping
+    pub unmapped: Vec<String>,
+    pub rows: Vec<RowReport>,
+    /// whether the items were created, which happens only if every row is valid
+    pub committed: bool,
+    pub created: Vec<Id<item::Model>>,
+}
+/// Validate rows of a spreadsheet and create them as items, all or nothing.
+pub async fn import(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<ItemImport>,
+) -> ApiResult<ImportReport> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let (columns, records) = match schema.data {
+        ImportData::Csv(data) => {
+            bulk::csv_records(&data).map_err(|e| (StatusCode::BAD_REQUEST, anyhow::anyhow!(e)))?
+        }
+        ImportData::Json(rows) => bulk::json_records(rows),
+    };
+    let mapping = bulk::mapping(&columns, &schema.mapping);
+    let unmapped = columns.iter().filter(|c| !mapping.contains_key(*c)).cloned().collect();
+    let rows: Vec<_> = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| match bulk::parse(record, &mapping) {
+            Ok(item) => RowReport { row: i + 1, item: Some(item), errors: Vec::new() },
+            Err(errors) => RowReport { row: i + 1, item: None, errors },
+        })
+        .collect();
+
+    let valid = rows.iter().all(|r| r.errors.is_empty());
+    let mut created = Vec::new();
+    if valid && !schema.dry_run {
+        let txn = state.db.begin().await?;
+        let closet_id = super::closet::writable_closet(&txn, &user, schema.closet_id).await?;
+        for item in rows.iter().filter_map(|r| r.item.clone()) {
+            let ImportedItem { name, category, brand, notes, warmth, waterproof, tags } = item;
+            let (owner_id, closet_id) = (user.user.id.clone(), closet_id.clone());
+            let insert_item = InsertItem {
+                owner_id,
+                closet_id,
+                name,
+                category,
+                brand,
+                notes,
+                warmth,
+                waterproof,
+            };
+            let inserted = insert_item.into_active_model().insert(&txn).await?;
+            replace_tags(&txn, &inserted, tags).await?;
+            created.push(inserted.id);
+        }
+        txn.commit().await?;
+    }
+    let committed = valid && !schema.dry_run;
+    Ok(ApiResponse::Success(ImportReport { columns, mapping, unmapped, rows, committed, created }))
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFormat {
+    #[default]
+    Json,
+    Csv,
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ItemExportQuery {
+    #[serde(default)]
+    pub format: DataFormat,
+    pub closet: Option<Id<closet::Model>>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExportedItem {
+    pub id: Id<item::Model>,
+    pub name: String,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub notes: Option<String>,
+    pub warmth: i32,
+    pub waterproof: bool,
+    pub availability: Availability,
+    pub tags: Vec<Tag>,
+    pub colors: Vec<Color>,
+    /// paths of the photos
+    pub images: Vec<String>,
+}
+impl ExportedItem {
+    /// importable by the same header
+    pub const HEADER: [&str; 11] = [
+        "id",
+        "name",
+        "category",
+        "brand",
+        "notes",
+        "warmth",
+        "waterproof",
+        "availability",
+        "tags",
+        "colors",
+        "images",
+    ];
+
+    pub fn new(detail: ItemDetail, base_url: &str) -> Self {
+        let ItemDetail { item, colors, tags, images } = detail;
+        let images = images
+            .iter()
+            .map(|image| {
+                format!("{}/items/{}/images/{}", base_url.trim_end_matches('/'), item.id, image)
+            })
+            .collect();
+        Self {
+            id: item.id,
+            name: item.name,
+            category: item.category,
+            brand: item.brand,
+            notes: item.notes,
+            warmth: item.warmth,
+            waterproof: item.waterproof,
+            availability: item.availability,
+            tags,
+            colors: colors.into_iter().map(|c| c.color).collect(),
+            images,
+        }
+    }
+    pub fn record(&self) -> [String; 11] {
+        let join = |values: Vec<String>| values.join(";");
+        [
+            self.id.to_string(),
+            self.name.clone(),
+            self.category.map(|c| c.to_string()).unwrap_or_default(),
+            self.brand.clone().unwrap_or_default(),
+            self.notes.clone().unwrap_or_default(),
+            self.warmth.to_string(),
+            self.waterproof.to_string(),
+            self.availability.to_string(),
+            join(self.tags.iter().map(|t| t.to_string()).collect()),
+            join(self.colors.iter().map(|c| c.to_string()).collect()),
+            join(self.images.clone()),
+        ]
+    }
+}
+
+struct ExportCursor {
+    db: DatabaseConnection,
+    user_id: Id<user::Model>,
+    closet: Option<Id<closet::Model>>,
+    base_url: String,
+    format: DataFormat,
+    /// last exported item, as the pages are cut by id rather than by offset so that the items
+    /// added or deleted during the export do not shift the pages
+    after: Option<Id<item::Model>>,
+    finished: bool,
+}
+impl ExportCursor {
+    const PAGE_SIZE: u64 = 100;
+
+    /// next chunk of the export, or `None` after the last one
+    async fn next(mut self) -> Result<Option<(Bytes, Self)>, BoxError> {
+        if self.finished {
+            return Ok(None);
+        }
+        let mut select = item::Entity::find().filter(
+            item::Column::ClosetId.in_subquery(super::closet::member_closets(&self.user_id)),
+        );
+        if let Some(closet) = &self.closet {
+            select = select.filter(item::Column::ClosetId.eq(closet.clone()));
+        }
+        let first = self.after.is_none();
+        if let Some(after) = &self.after {
+            select = select.filter(item::Column::Id.gt(after.clone()));
+        }
+        let items =
+            select.order_by_asc(item::Column::Id).limit(Self::PAGE_SIZE).all(&self.db).await?;
+        self.finished = (items.len() as u64) < Self::PAGE_SIZE;
+        self.after = items.last().map(|i| i.id.clone()).or(self.after);
+        let exported: Vec<_> = ItemDetail::load(&self.db, items)
+            .await?
+            .into_iter()
+            .map(|d| ExportedItem::new(d, &self.base_url))
+            .collect();
+
+        let mut chunk = Vec::new();
+        match self.format {
+            DataFormat::Json => {
+                for (i, item) in exported.iter().enumerate() {
+                    chunk.push(if first && i == 0 { b'[' } else { b',' });
+                    serde_json::to_writer(&mut chunk, item)?;
+                }
+                if first && exported.is_empty() {
+                    chunk.push(b'[');
+                }
+                if self.finished {
+                    chunk.push(b']');
+                }
+            }
+            DataFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(&mut chunk);
+                if first {
+                    writer.write_record(ExportedItem::HEADER)?;
+                }
+                for item in &exported {
+                    writer.write_record(item.record())?;
+                }
+                writer.flush()?;
+            }
+        }
+        Ok(Some((Bytes::from(chunk), self)))
+    }
+}
+/// Items of every closet the user is a member of, or of one, streamed page by page.
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<ItemExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let cursor = ExportCursor {
+        db: state.db.clone(),
+        user_id: user.user.id,
+        closet: query.closet,
+        base_url: state.configuration.base_url(),
+        format: query.format,
+        after: None,
+        finished: false,
+    };
+    let (content_type, extension) = match query.format {
+        DataFormat::Json => ("application/json", "json"),
+        DataFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+    };
+    let disposition = format!("attachment; filename=\"closet.{}\"", extension);
+    let body = StreamBody::new(futures::stream::try_unfold(cursor, ExportCursor::next));
+    let headers = [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+    ];
+    Ok((headers, body))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_import_export_scenario() {
+        use hyper::Method;
+
+        use super::*;
+        use crate::{
+            call, call_json, configuration::Configuration, request, response::page::Page, signup,
+            with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let csv = "Item,Type,Cozy,Tags,Price\n\
+                   shirt,top,1,casual;summer,20\n\
+                   ,hat,hot,,10\n\
+                   jeans,bottom,2,casual,50\n";
+        let import = serde_json::json!({
+            "data": csv, "mapping": { "Cozy": "warmth" }, "dry_run": true
+        });
+        let uri = "/items/import";
+        let (status, report) =
+            call_json::<ImportReport>(&api, Method::POST, uri, token, Some(import)).await;
+        assert_eq!(status, StatusCode::OK);
+        let report = report.result().unwrap();
+        assert_eq!(report.unmapped, vec!["Price"]);
+        assert_eq!(report.mapping["Item"], ImportField::Name);
+        assert_eq!(report.rows[1].errors.len(), 3);
+        assert!(report.rows[0].errors.is_empty() && report.rows[2].errors.is_empty());
+        assert!(!report.committed);
+
+        // not committed at all because of the invalid row
+        let import = serde_json::json!({ "data": csv, "mapping": { "Cozy": "warmth" } });
+        let (_, report) =
+            call_json::<ImportReport>(&api, Method::POST, uri, token, Some(import)).await;
+        assert!(!report.result().unwrap().committed);
+        let (_, items) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items", token, None).await;
+        assert!(items.result().unwrap().items.is_empty());
+
+        let json = serde_json::json!([
+            { "name": "shirt", "category": "top", "tags": ["casual", "summer"] },
+            { "name": "parka, long", "category": "outerwear", "warmth": 5, "waterproof": true }
+        ]);
+        let import = serde_json::json!({ "data": json });
+        let (_, report) =
+            call_json::<ImportReport>(&api, Method::POST, uri, token, Some(import)).await;
+        let report = report.result().unwrap();
+        assert!(report.committed);
+        assert_eq!(report.created.len(), 2);
+        let uri = format!("/items/{}", report.created[0]);
+        let (_, shirt) = call_json::<ItemDetail>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(shirt.result().unwrap().tags.len(), 2);
+
+        let export = |format: &'static str| {
+            let uri = format!("/items/export?format={}", format);
+            request(Method::GET, &uri, token).body(hyper::Body::empty()).unwrap()
+        };
+        let (status, json) = call(&api, export("json")).await;
+        assert_eq!(status, StatusCode::OK);
+        let exported: Vec<ExportedItem> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(exported.len(), 2);
+        let (_, csv) = call(&api, export("csv")).await;
+        let csv = String::from_utf8(csv.to_vec()).unwrap();
+        assert!(csv.starts_with("id,name,category,brand,notes,warmth,waterproof,availability,"));
+        assert!(csv.contains(",\"parka, long\",outerwear,,,5,true,clean,,,\n"));
+
+        // exported csv can be imported again
+        let import = serde_json::json!({ "data": csv, "dry_run": true });
+        let (_, report) =
+            call_json::<ImportReport>(&api, Method::POST, "/items/import", token, Some(import))
+                .await;
+        let report = report.result().unwrap();
+        assert!(report.rows.iter().all(|r| r.errors.is_empty()));
+        assert_eq!(report.unmapped, vec!["id", "availability", "colors", "images"]);
+
+        // more items than a page are exported each once
+        let socks: Vec<_> = (0..ExportCursor::PAGE_SIZE)
+            .map(|i| serde_json::json!({ "name": format!("sock {}", i) }))
+            .collect();
+        let import = serde_json::json!({ "data": socks });
+        let (_, report) =
+            call_json::<ImportReport>(&api, Method::POST, "/items/import", token, Some(import))
+                .await;
+        assert!(report.result().unwrap().committed);
+        let (_, json) = call(&api, export("json")).await;
+        let exported: Vec<ExportedItem> = serde_json::from_slice(&json).unwrap();
+        let ids: std::collections::HashSet<_> = exported.iter().map(|i| i.id.to_string()).collect();
+        assert_eq!(ids.len() as u64, ExportCursor::PAGE_SIZE + 2);
+    }
+}
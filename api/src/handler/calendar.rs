@@ -0,0 +1,492 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Router,
+};
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use entity::{
+    class::{id::Id, role::Role, week_start::WeekStart},
+    model::{calendar_token, item, outfit, outfit_item, planned_outfit, user},
+};
+use hyper::{header, StatusCode};
+use rand::distributions::{Alphanumeric, DistString};
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, IntoActiveModel, LoaderTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, TransactionTrait, TryIntoModel,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{feedback::settle_outfit_wear, json::Json, outfit::OutfitDetail};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::icalendar::{self, Event},
+    AppState,
+};
+
+pub fn calendar_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(month))
+        .route("/token", axum::routing::post(rotate_token))
+        .route("/settle", axum::routing::post(settle_past))
+        .route("/:date", axum::routing::put(plan).delete(unplan))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "PlannedOutfitDetail")]
+pub struct PlannedOutfit {
+    pub plan: planned_outfit::Model,
+    pub outfit: OutfitDetail,
+}
+impl PlannedOutfit {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        plans: Vec<planned_outfit::Model>,
+    ) -> Result<Vec<Self>, DbErr> {
+        if plans.is_empty() {
+            return Ok(Vec::new());
+        }
+        let outfits = plans.load_one(outfit::Entity, db).await?;
+        let details = OutfitDetail::load(db, outfits.into_iter().flatten().collect()).await?;
+        Ok(plans
+            .into_iter()
+            .filter_map(|plan| {
+                let outfit = details.iter().find(|d| d.outfit.id == plan.outfit_id)?.clone();
+                Some(Self { plan, outfit })
+            })
+            .collect())
+    }
+}
+
+/// Record the items of outfits planned before the owner's today in the wear log, once per plan.
+/// Done by the writes of the calendar, on login, on request and periodically by
+/// [`settle_periodically`], never by reads.
+pub(crate) async fn settle<C: ConnectionTrait>(
+    db: &C,
+    owner: &user::Model,
+) -> Result<Vec<planned_outfit::Model>, ApiError> {
+    let plans = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(owner.id.clone()))
+        .filter(planned_outfit::Column::Logged.eq(false))
+        .filter(planned_outfit::Column::PlannedOn.lt(owner.today()))
+        .order_by_asc(planned_outfit::Column::PlannedOn)
+        .all(db)
+        .await?;
+    let outfits = plans.load_one(outfit::Entity, db).await?;
+    let mut settled = Vec::new();
+    for (plan, outfit) in plans.into_iter().zip(outfits) {
+        if let Some(outfit) = outfit {
+            settle_outfit_wear(db, owner, &outfit, plan.planned_on).await?;
+        }
+        let mut active = plan.into_active_model();
+        active.logged = ActiveValue::Set(true);
+        settled.push(active.update(db).await?);
+    }
+    Ok(settled)
+}
+
+/// Items of the outfits planned from `since` to before `today` which are not settled yet, with the
+/// day they were planned for, so that reads can take them as worn without settling.
+pub(crate) async fn unsettled_wears<C: ConnectionTrait>(
+    db: &C,
+    owner_id: &Id<user::Model>,
+    since: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<(Id<item::Model>, NaiveDate)>, DbErr> {
+    let plans = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(owner_id.clone()))
+        .filter(planned_outfit::Column::Logged.eq(false))
+        .filter(planned_outfit::Column::PlannedOn.gte(since))
+        .filter(planned_outfit::Column::PlannedOn.lt(today))
+        .all(db)
+        .await?;
+    let outfit_items = outfit_item::Entity::find()
+        .filter(outfit_item::Column::OutfitId.is_in(plans.iter().map(|p| p.outfit_id.clone())))
+        .all(db)
+        .await?;
+    Ok(plans
+        .iter()
+        .flat_map(|plan| {
+            let items = outfit_items.iter().filter(|i| i.outfit_id == plan.outfit_id);
+            items.map(|i| (i.item_id.clone(), plan.planned_on))
+        })
+        .collect())
+}
+
+/// Settle the outfits planned for the past days, see [`settle`].
+pub async fn settle_past(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<Vec<planned_outfit::Model>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let settled = settle(&txn, &user.user).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(settled))
+}
+
+/// Settle the past plans of every user, each up to their own today, returning how many settled.
+pub async fn settle_everyone(db: &DatabaseConnection) -> Result<usize, ApiError> {
+    let unsettled = planned_outfit::Entity::find()
+        .select_only()
+        .column(planned_outfit::Column::OwnerId)
+        .filter(planned_outfit::Column::Logged.eq(false))
+        .into_query();
+    let owners =
+        user::Entity::find().filter(user::Column::Id.in_subquery(unsettled)).all(db).await?;
+    let mut settled = 0;
+    for owner in owners {
+        let txn = db.begin().await?;
+        settled += settle(&txn, &owner).await?.len();
+        txn.commit().await?;
+    }
+    Ok(settled)
+}
+
+/// Settle every `period`, so that the days which are over turn into wears without anyone asking.
+pub async fn settle_periodically(db: DatabaseConnection, period: std::time::Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+        match settle_everyone(&db).await {
+            Ok(settled) => tracing::info!("settled {} planned outfits", settled),
+            Err(e) => tracing::error!("cannot settle planned outfits: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MonthQuery {
+    /// `YYYY-MM`, defaults to the current month
+    pub month: Option<String>,
+    /// widen the month to whole weeks, starting on the week start of the user
+    #[serde(default)]
+    pub weeks: bool,
+}
+impl MonthQuery {
+    /// first day of the month and of the next month
+    pub fn range(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), ApiError> {
+        let first = match &self.month {
+            Some(month) => NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+                .map_err(|_| {
+                    let error = anyhow::anyhow!("month {} is not formatted as YYYY-MM", month);
+                    (StatusCode::BAD_REQUEST, error)
+                })?,
+            None => today.with_day(1).expect("every month has a first day"),
+        };
+        let next = match first.month() {
+            12 => NaiveDate::from_ymd_opt(first.year() + 1, 1, 1),
+            month => NaiveDate::from_ymd_opt(first.year(), month + 1, 1),
+        };
+        Ok((first, next.ok_or_else(|| anyhow::anyhow!("month out of range"))?))
+    }
+    /// [`MonthQuery::range`], widened to whole weeks if asked
+    pub fn span(
+        &self,
+        today: NaiveDate,
+        week_start: WeekStart,
+    ) -> Result<(NaiveDate, NaiveDate), ApiError> {
+        let (first, next) = self.range(today)?;
+        if !self.weeks {
+            return Ok((first, next));
+        }
+        let last_week = week_start.first_of_week(next - Days::new(1));
+        let next = last_week.checked_add_days(Days::new(7));
+        Ok((
+            week_start.first_of_week(first),
+            next.ok_or_else(|| anyhow::anyhow!("week out of range"))?,
+        ))
+    }
+}
+pub async fn month(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<MonthQuery>,
+) -> ApiResult<Vec<PlannedOutfit>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let today = user.user.today();
+    let (first, next) = query.span(today, user.user.week_start)?;
+    let plans = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(user.user.id))
+        .filter(planned_outfit::Column::PlannedOn.gte(first))
+        .filter(planned_outfit::Column::PlannedOn.lt(next))
+        .order_by_asc(planned_outfit::Column::PlannedOn)
+        .all(&state.db)
+        .await?;
+    Ok(ApiResponse::Success(PlannedOutfit::load(&state.db, plans).await?))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct OutfitPlan {
+    pub outfit_id: Id<outfit::Model>,
+    pub note: Option<String>,
+}
+/// Plan the outfit for the day, replacing the former plan.
+/// Planning wears the items once the day is over, so the outfit must be editable.
+pub async fn plan(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(date): Path<NaiveDate>,
+    Json(schema): Json<OutfitPlan>,
+) -> ApiResult<PlannedOutfit> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    settle(&txn, &user.user).await?;
+    let outfit =
+        super::outfit::find_accessible(&txn, &user, schema.outfit_id, Role::Editor).await?;
+    let planned = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(user.user.id.clone()))
+        .filter(planned_outfit::Column::PlannedOn.eq(date))
+        .one(&txn)
+        .await?;
+    let mut active = match planned {
+        Some(planned) if planned.logged => {
+            let error = anyhow::anyhow!("outfit of {} is already in the wear log", date);
+            Err((StatusCode::CONFLICT, error))?
+        }
+        Some(planned) => planned.into_active_model(),
+        None => planned_outfit::ActiveModel {
+            owner_id: ActiveValue::Set(user.user.id),
+            planned_on: ActiveValue::Set(date),
+            ..Default::default()
+        },
+    };
+    active.outfit_id = ActiveValue::Set(outfit.id);
+    active.note = ActiveValue::Set(schema.note);
+    let saved = active.save(&txn).await?.try_into_model()?;
+    let mut planned = PlannedOutfit::load(&txn, vec![saved]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(planned.remove(0)))
+}
+
+pub async fn unplan(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(date): Path<NaiveDate>,
+) -> ApiResult<planned_outfit::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    settle(&txn, &user.user).await?;
+    let planned = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(user.user.id))
+        .filter(planned_outfit::Column::PlannedOn.eq(date))
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    planned.clone().delete(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(planned))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarFeed {
+    pub token: String,
+    /// subscribable by calendar apps without further authentication
+    pub path: String,
+}
+/// Issue a new secret for the calendar feed, the former one stops working.
+pub async fn rotate_token(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<CalendarFeed> {
+    const TOKEN_LENGTH: usize = 32;
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+    let txn = state.db.begin().await?;
+    calendar_token::Entity::delete_many()
+        .filter(calendar_token::Column::UserId.eq(user.user.id.clone()))
+        .exec(&txn)
+        .await?;
+    calendar_token::ActiveModel {
+        user_id: ActiveValue::Set(user.user.id),
+        token: ActiveValue::Set(token.clone()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+    let base_url = state.configuration.base_url();
+    let path = format!("{}/calendar.ics?token={}", base_url.trim_end_matches('/'), token);
+    Ok(ApiResponse::Success(CalendarFeed { token, path }))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedQuery {
+    pub token: String,
+}
+/// Planned outfits as an iCalendar feed, authenticated by the token in the url.
+pub async fn feed(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = calendar_token::Entity::find()
+        .filter(calendar_token::Column::Token.eq(query.token))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    let plans = planned_outfit::Entity::find()
+        .filter(planned_outfit::Column::OwnerId.eq(token.user_id))
+        .order_by_asc(planned_outfit::Column::PlannedOn)
+        .all(&state.db)
+        .await?;
+    let planned = PlannedOutfit::load(&state.db, plans).await?;
+
+    let events: Vec<_> = planned
+        .into_iter()
+        .map(|PlannedOutfit { plan, outfit }| {
+            let items: Vec<_> = outfit.items.iter().map(|i| i.name.as_str()).collect();
+            let description = match &plan.note {
+                Some(note) => format!("{}\n{}", items.join(", "), note),
+                None => items.join(", "),
+            };
+            Event {
+                uid: format!("{}@closet", plan.id),
+                stamp: plan.updated_at.with_timezone(&Utc),
+                date: plan.planned_on,
+                summary: outfit.outfit.name,
+                description: Some(description),
+            }
+        })
+        .collect();
+    let body = icalendar::calendar("closet outfits", &events);
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_range() {
+        let today = NaiveDate::from_ymd_opt(2023, 12, 24).unwrap();
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let current = MonthQuery { month: None, ..Default::default() }.range(today).unwrap();
+        assert_eq!(current, (ymd(2023, 12, 1), ymd(2024, 1, 1)));
+        let february = MonthQuery { month: Some("2024-02".into()), weeks: false };
+        assert_eq!(february.range(today).unwrap(), (ymd(2024, 2, 1), ymd(2024, 3, 1)));
+        assert_eq!(
+            february.span(today, WeekStart::Monday).unwrap(),
+            february.range(today).unwrap()
+        );
+        let february = MonthQuery { weeks: true, ..february };
+        // 2024-02-01 is a Thursday and 2024-02-29 too
+        assert_eq!(
+            february.span(today, WeekStart::Monday).unwrap(),
+            (ymd(2024, 1, 29), ymd(2024, 3, 4))
+        );
+        assert_eq!(
+            february.span(today, WeekStart::Sunday).unwrap(),
+            (ymd(2024, 1, 28), ymd(2024, 3, 3))
+        );
+        let april = MonthQuery { month: Some("2024-04".into()), weeks: true };
+        // 2024-04-01 is a Monday and 2024-04-30 a Tuesday
+        assert_eq!(
+            april.span(today, WeekStart::Monday).unwrap(),
+            (ymd(2024, 4, 1), ymd(2024, 5, 6))
+        );
+        assert!(MonthQuery { month: Some("2024-13".into()), weeks: false }.range(today).is_err());
+        assert!(MonthQuery { month: Some("2024-02-01".into()), weeks: false }
+            .range(today)
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_calendar_scenario() {
+        use hyper::Method;
+
+        use super::super::item::ItemDetail;
+        use crate::response::page::Page;
+        use crate::{call, call_json, configuration::Configuration, request, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut ids = Vec::new();
+        for name in ["shirt", "jeans"] {
+            let create = serde_json::json!({ "name": name });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+        let create = serde_json::json!({ "name": "office, casual", "items": ids });
+        let (_, outfit) =
+            call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(create)).await;
+        let outfit_id = outfit.result().unwrap().outfit.id.clone();
+
+        let today = owner.user.today();
+        let (yesterday, tomorrow) = (today.pred_opt().unwrap(), today.succ_opt().unwrap());
+        for (date, note) in [(tomorrow, Some("meeting")), (yesterday, None)] {
+            let plan = serde_json::json!({ "outfit_id": outfit_id, "note": note });
+            let uri = format!("/calendar/{}", date);
+            let (status, planned) =
+                call_json::<PlannedOutfit>(&api, Method::PUT, &uri, token, Some(plan)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(planned.result().unwrap().outfit.items.len(), 2);
+        }
+
+        let uri = format!("/calendar?month={}", tomorrow.format("%Y-%m"));
+        let (status, month) =
+            call_json::<Vec<PlannedOutfit>>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let planned = month.result().unwrap().iter().find(|p| p.plan.planned_on == tomorrow);
+        assert_eq!(planned.unwrap().plan.note.as_deref(), Some("meeting"));
+
+        let worn = || async {
+            let uri = format!("/items/{}/wears", ids[0]);
+            let (_, wears) = call_json::<Page<entity::model::item_wear::Model>>(
+                &api,
+                Method::GET,
+                &uri,
+                token,
+                None,
+            )
+            .await;
+            wears.result().unwrap().items.iter().map(|w| w.worn_on).collect::<Vec<_>>()
+        };
+        assert_eq!(worn().await, Vec::new(), "reads do not settle");
+        // yesterday is over, so its outfit has been worn by the next login
+        signup(&api, "owner").await;
+        assert_eq!(worn().await, vec![yesterday]);
+        let (status, settled) = call_json::<Vec<planned_outfit::Model>>(
+            &api,
+            Method::POST,
+            "/calendar/settle",
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(settled.result().unwrap().is_empty(), "settled once");
+        let plan = serde_json::json!({ "outfit_id": outfit_id });
+        let uri = format!("/calendar/{}", yesterday);
+        let (status, _) =
+            call_json::<PlannedOutfit>(&api, Method::PUT, &uri, token, Some(plan)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, feed) =
+            call_json::<CalendarFeed>(&api, Method::POST, "/calendar/token", token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let feed = feed.result().unwrap();
+        let (status, ics) =
+            call(&api, request(Method::GET, &feed.path, None).body(hyper::Body::empty()).unwrap())
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        let ics = String::from_utf8(ics.to_vec()).unwrap();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("SUMMARY:office\\, casual\r\n"));
+        assert!(ics.contains(&format!("DTSTART;VALUE=DATE:{}", tomorrow.format("%Y%m%d"))));
+        assert!(ics.contains("DESCRIPTION:shirt\\, jeans\\nmeeting\r\n"));
+
+        let (_, rotated) =
+            call_json::<CalendarFeed>(&api, Method::POST, "/calendar/token", token, None).await;
+        assert_ne!(rotated.result().unwrap(), feed);
+        let (status, _) =
+            call(&api, request(Method::GET, &feed.path, None).body(hyper::Body::empty()).unwrap())
+                .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "former token is revoked");
+    }
+}
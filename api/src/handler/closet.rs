@@ -0,0 +1,683 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Router,
+};
+use entity::{
+    class::{email::Email, id::Id, role::Role, username::Username},
+    error::{FieldErrors, ValidateError},
+    model::{closet, closet_invitation, closet_member, user},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::SelectStatement, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    EntityTrait, IntoActiveModel, JoinType, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, RelationTrait, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    AppState,
+};
+
+pub fn closet_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/invitations", axum::routing::get(invitations))
+        .route("/invitations/:invitation_id/accept", axum::routing::post(accept))
+        .route("/invitations/:invitation_id/decline", axum::routing::post(decline))
+        .route("/:id", axum::routing::get(detail).put(rename).delete(delete))
+        .route("/:id/invitations", axum::routing::post(invite))
+        .route("/:id/members/:user_id", axum::routing::put(change_role).delete(remove_member))
+}
+
+/// ids of the closets the user is a member of, as a subquery
+pub(crate) fn member_closets(user_id: &Id<user::Model>) -> SelectStatement {
+    closet_member::Entity::find()
+        .select_only()
+        .column(closet_member::Column::ClosetId)
+        .filter(closet_member::Column::UserId.eq(user_id.clone()))
+        .into_query()
+}
+
+/// Role of the user in the closet, which must satisfy `required`.
+/// Closets the user is not a member of are treated as nonexistent.
+pub(crate) async fn authorize<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    closet_id: &Id<closet::Model>,
+    required: Role,
+) -> Result<Role, ApiError> {
+    let member = closet_member::Entity::find()
+        .filter(closet_member::Column::ClosetId.eq(closet_id.clone()))
+        .filter(closet_member::Column::UserId.eq(user.user.id.clone()))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    if member.role.satisfies(&required) {
+        Ok(member.role)
+    } else {
+        let error = anyhow::anyhow!("{} role is required in the closet", required);
+        Err((StatusCode::FORBIDDEN, error))?
+    }
+}
+
+/// The user who created the closet, its first member. Personal closets stay theirs: nobody else
+/// may own or delete them.
+async fn creator<C: ConnectionTrait>(
+    db: &C,
+    closet_id: &Id<closet::Model>,
+) -> Result<Option<Id<user::Model>>, DbErr> {
+    let first = closet_member::Entity::find()
+        .filter(closet_member::Column::ClosetId.eq(closet_id.clone()))
+        .order_by_asc(closet_member::Column::CreatedAt)
+        .one(db)
+        .await?;
+    Ok(first.map(|member| member.user_id))
+}
+
+/// Personal closets are owned by their creator alone.
+fn ensure_ownable(closet: &closet::Model, role: Role) -> Result<(), FieldErrors> {
+    if closet.personal && role == Role::Owner {
+        Err(FieldErrors::of("role", format!("{} has no other owner", closet.name)))?
+    }
+    Ok(())
+}
+
+/// The closet new items and outfits go to by default, created on first use.
+pub(crate) async fn personal_closet<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+) -> Result<closet::Model, ApiError> {
+    let owned = closet::Entity::find()
+        .join(JoinType::InnerJoin, closet::Relation::Member.def())
+        .filter(closet_member::Column::UserId.eq(user.user.id.clone()))
+        .filter(closet_member::Column::Role.eq(Role::Owner))
+        .filter(closet::Column::Personal.eq(true))
+        .order_by_asc(closet::Column::CreatedAt)
+        .all(db)
+        .await?;
+    for closet in owned {
+        if creator(db, &closet.id).await?.as_ref() == Some(&user.user.id) {
+            return Ok(closet);
+        }
+    }
+    let name = format!("{}'s closet", user.user.display_name);
+    Ok(create_closet(db, user, name, true).await?)
+}
+
+async fn find_closet<C: ConnectionTrait>(
+    db: &C,
+    id: &Id<closet::Model>,
+) -> Result<closet::Model, ApiError> {
+    closet::Entity::find_by_id(id.clone()).one(db).await?.ok_or(ApiError::RecordNotFound)
+}
+
+/// `closet_id` if the user can edit it, or the personal closet.
+pub(crate) async fn writable_closet<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    closet_id: Option<Id<closet::Model>>,
+) -> Result<Id<closet::Model>, ApiError> {
+    match closet_id {
+        Some(closet_id) => {
+            authorize(db, user, &closet_id, Role::Editor).await?;
+            Ok(closet_id)
+        }
+        None => Ok(personal_closet(db, user).await?.id),
+    }
+}
+
+async fn create_closet<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    name: String,
+    personal: bool,
+) -> Result<closet::Model, DbErr> {
+    let created = closet::ActiveModel {
+        name: ActiveValue::Set(name),
+        personal: ActiveValue::Set(personal),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    closet_member::ActiveModel {
+        closet_id: ActiveValue::Set(created.id.clone()),
+        user_id: ActiveValue::Set(user.user.id.clone()),
+        role: ActiveValue::Set(Role::Owner),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(created)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Member {
+    pub user_id: Id<user::Model>,
+    pub username: Username,
+    pub display_name: String,
+    pub role: Role,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ClosetDetail {
+    pub closet: closet::Model,
+    /// role of the requesting user
+    pub role: Role,
+    pub members: Vec<Member>,
+}
+impl ClosetDetail {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        user: &AuthUser,
+        closet: closet::Model,
+    ) -> Result<Self, DbErr> {
+        let members: Vec<_> = closet
+            .find_related(closet_member::Entity)
+            .find_also_related(user::Entity)
+            .order_by_asc(closet_member::Column::CreatedAt)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(member, user)| {
+                let user = user?;
+                let (username, display_name) = (user.username, user.display_name);
+                Some(Member { user_id: member.user_id, username, display_name, role: member.role })
+            })
+            .collect();
+        let role = members
+            .iter()
+            .find(|m| m.user_id == user.user.id)
+            .map(|m| m.role)
+            .ok_or_else(|| DbErr::RecordNotFound("closet member".into()))?;
+        Ok(Self { closet, role, members })
+    }
+}
+
+async fn find_member<C: ConnectionTrait>(
+    db: &C,
+    closet_id: &Id<closet::Model>,
+    user_id: &Id<user::Model>,
+) -> Result<closet_member::Model, ApiError> {
+    closet_member::Entity::find()
+        .filter(closet_member::Column::ClosetId.eq(closet_id.clone()))
+        .filter(closet_member::Column::UserId.eq(user_id.clone()))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+/// A closet must always be managed by someone.
+async fn ensure_other_owner<C: ConnectionTrait>(
+    db: &C,
+    member: &closet_member::Model,
+) -> Result<(), ApiError> {
+    let owners = closet_member::Entity::find()
+        .filter(closet_member::Column::ClosetId.eq(member.closet_id.clone()))
+        .filter(closet_member::Column::Role.eq(Role::Owner))
+        .filter(closet_member::Column::Id.ne(member.id.clone()))
+        .count(db)
+        .await?;
+    if member.role == Role::Owner && owners == 0 {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("closet needs another owner")))?
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ClosetCreate {
+    pub name: String,
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<ClosetCreate>,
+) -> ApiResult<ClosetDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let created = create_closet(&txn, &user, schema.name, false).await?;
+    let detail = ClosetDetail::load(&txn, &user, created).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(detail))
+}
+
+impl Listed for closet::Entity {
+    type Entity = Self;
+    const ID: closet::Column = closet::Column::Id;
+    const SORTABLE: &'static [closet::Column] = &[closet::Column::CreatedAt, closet::Column::Name];
+    const FILTERABLE: &'static [closet::Column] = &[closet::Column::Personal];
+    const DEFAULT_SORT: &'static str = "created_at";
+}
+/// Closets the user is a member of, the oldest first by default.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    listing: Listing<closet::Entity>,
+) -> ApiPage<ClosetDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let select = closet::Entity::find()
+        .filter(closet::Column::Id.in_subquery(member_closets(&user.user.id)));
+    let page = listing.fetch(&state.db, select).await?;
+    let mut details = Vec::new();
+    for closet in page.items.clone() {
+        details.push(ClosetDetail::load(&state.db, &user, closet).await?);
+    }
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<closet::Model>>,
+) -> ApiResult<ClosetDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    authorize(&state.db, &user, &id, Role::Viewer).await?;
+    let closet = closet::Entity::find_by_id(id).one(&state.db).await?;
+    let closet = closet.ok_or(ApiError::RecordNotFound)?;
+    Ok(ApiResponse::Success(ClosetDetail::load(&state.db, &user, closet).await?))
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<closet::Model>>,
+    Json(schema): Json<ClosetCreate>,
+) -> ApiResult<ClosetDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    authorize(&state.db, &user, &id, Role::Owner).await?;
+    let closet = closet::Entity::find_by_id(id).one(&state.db).await?;
+    let mut active = closet.ok_or(ApiError::RecordNotFound)?.into_active_model();
+    active.name = ActiveValue::Set(schema.name);
+    let updated = active.update(&state.db).await?;
+    Ok(ApiResponse::Success(ClosetDetail::load(&state.db, &user, updated).await?))
+}
+
+/// Delete the closet together with its items and outfits.
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<closet::Model>>,
+) -> ApiResult<closet::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    authorize(&txn, &user, &id, Role::Owner).await?;
+    let closet = find_closet(&txn, &id).await?;
+    if closet.personal && creator(&txn, &id).await?.as_ref() != Some(&user.user.id) {
+        let error = anyhow::anyhow!("only its creator can delete {}", closet.name);
+        Err((StatusCode::FORBIDDEN, error))?
+    }
+    // its items and outfits go with it by their foreign keys
+    closet.clone().delete(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(closet))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ClosetInvite {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub role: Role,
+}
+pub async fn invite(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<closet::Model>>,
+    Json(schema): Json<ClosetInvite>,
+) -> ApiResult<closet_invitation::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    authorize(&txn, &user, &id, Role::Owner).await?;
+    ensure_ownable(&find_closet(&txn, &id).await?, schema.role)?;
+    let invitee = match (schema.username, schema.email) {
+        (Some(username), _) => {
+            user::Entity::find()
+                .filter(
+                    user::Column::Username
+                        .eq(Username::parse(&username)
+                            .map_err(|e| FieldErrors::of("username", e))?),
+                )
+                .one(&txn)
+                .await?
+        }
+        (None, Some(email)) => {
+            user::Entity::find()
+                .filter(
+                    user::Column::Email
+                        .eq(Email::parse(&email).map_err(|e| FieldErrors::of("email", e))?),
+                )
+                .one(&txn)
+                .await?
+        }
+        (None, None) => {
+            let missing = ValidateError::MissingField { missing_field: "username or email".into() };
+            Err(FieldErrors::of("username", missing))?
+        }
+    };
+    let invitee = invitee.filter(|u| u.is_active).ok_or(ApiError::RecordNotFound)?;
+    if find_member(&txn, &id, &invitee.id).await.is_ok() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is already a member", invitee.username)))?
+    }
+    closet_invitation::Entity::delete_many()
+        .filter(closet_invitation::Column::ClosetId.eq(id.clone()))
+        .filter(closet_invitation::Column::InviteeId.eq(invitee.id.clone()))
+        .exec(&txn)
+        .await?;
+    let invitation = closet_invitation::ActiveModel {
+        closet_id: ActiveValue::Set(id),
+        inviter_id: ActiveValue::Set(user.user.id),
+        invitee_id: ActiveValue::Set(invitee.id),
+        role: ActiveValue::Set(schema.role),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(invitation))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InvitationDetail {
+    pub invitation: closet_invitation::Model,
+    pub closet: closet::Model,
+}
+/// Pending invitations to the requesting user.
+pub async fn invitations(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<Vec<InvitationDetail>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let invitations = closet_invitation::Entity::find()
+        .filter(closet_invitation::Column::InviteeId.eq(user.user.id))
+        .find_also_related(closet::Entity)
+        .order_by_asc(closet_invitation::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+    let details = invitations
+        .into_iter()
+        .filter_map(|(invitation, closet)| Some(InvitationDetail { invitation, closet: closet? }))
+        .collect();
+    Ok(ApiResponse::Success(details))
+}
+
+async fn find_invitation<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<closet_invitation::Model>,
+) -> Result<closet_invitation::Model, ApiError> {
+    closet_invitation::Entity::find_by_id(id)
+        .filter(closet_invitation::Column::InviteeId.eq(user.user.id.clone()))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+pub async fn accept(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(invitation_id): Path<Id<closet_invitation::Model>>,
+) -> ApiResult<ClosetDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let invitation = find_invitation(&txn, &user, invitation_id).await?;
+    let closet = find_closet(&txn, &invitation.closet_id).await?;
+    ensure_ownable(&closet, invitation.role)?;
+    closet_member::ActiveModel {
+        closet_id: ActiveValue::Set(invitation.closet_id.clone()),
+        user_id: ActiveValue::Set(user.user.id.clone()),
+        role: ActiveValue::Set(invitation.role),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    invitation.delete(&txn).await?;
+    let detail = ClosetDetail::load(&txn, &user, closet).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(detail))
+}
+
+pub async fn decline(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(invitation_id): Path<Id<closet_invitation::Model>>,
+) -> ApiResult<closet_invitation::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let invitation = find_invitation(&state.db, &user, invitation_id).await?;
+    invitation.clone().delete(&state.db).await?;
+    Ok(ApiResponse::Success(invitation))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct RoleChange {
+    pub role: Role,
+}
+pub async fn change_role(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, user_id)): Path<(Id<closet::Model>, Id<user::Model>)>,
+    Json(schema): Json<RoleChange>,
+) -> ApiResult<closet_member::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    authorize(&txn, &user, &id, Role::Owner).await?;
+    let member = find_member(&txn, &id, &user_id).await?;
+    if member.role != Role::Owner {
+        ensure_ownable(&find_closet(&txn, &id).await?, schema.role)?;
+    }
+    if schema.role != Role::Owner {
+        ensure_other_owner(&txn, &member).await?;
+    }
+    let mut active = member.into_active_model();
+    active.role = ActiveValue::Set(schema.role);
+    let updated = active.update(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(updated))
+}
+
+/// Remove a member, by an owner or by the member leaving.
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, user_id)): Path<(Id<closet::Model>, Id<user::Model>)>,
+) -> ApiResult<closet_member::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let required = if user_id == user.user.id { Role::Viewer } else { Role::Owner };
+    authorize(&txn, &user, &id, required).await?;
+    let member = find_member(&txn, &id, &user_id).await?;
+    ensure_other_owner(&txn, &member).await?;
+    member.clone().delete(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(member))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_shared_closet_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::handler::item::ItemDetail;
+        use crate::response::page::Page;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+        use entity::model::item;
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let (owner, partner) = (signup(&api, "owner").await, signup(&api, "partner").await);
+        let (owner_token, partner_token) = (owner.token.as_deref(), partner.token.as_deref());
+
+        let create = serde_json::json!({ "name": "household" });
+        let (_, household) =
+            call_json::<ClosetDetail>(&api, Method::POST, "/closets", owner_token, Some(create))
+                .await;
+        let household = household.result().unwrap().clone();
+        assert_eq!(household.role, Role::Owner);
+        let create = serde_json::json!({ "name": "towel", "closet_id": household.closet.id });
+        let (_, towel) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", owner_token, Some(create)).await;
+        let towel = towel.result().unwrap().item.clone();
+        // items without a closet go to the personal one
+        let create = serde_json::json!({ "name": "shirt" });
+        let (_, shirt) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", owner_token, Some(create)).await;
+        assert_ne!(shirt.result().unwrap().item.closet_id, household.closet.id);
+
+        let personal = shirt.result().unwrap().item.closet_id.clone();
+        let uri = format!("/closets/{}/invitations", personal);
+        let co_owner = serde_json::json!({ "username": "partner", "role": "owner" });
+        let (_, failure) = call_json::<closet_invitation::Model>(
+            &api,
+            Method::POST,
+            &uri,
+            owner_token,
+            Some(co_owner),
+        )
+        .await;
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+        assert!(fields.0.contains_key("role"), "a personal closet has a single owner");
+        let editor = serde_json::json!({ "username": "partner", "role": "editor" });
+        let (_, invitation) = call_json::<closet_invitation::Model>(
+            &api,
+            Method::POST,
+            &uri,
+            owner_token,
+            Some(editor),
+        )
+        .await;
+        let uri = format!("/closets/invitations/{}/accept", invitation.result().unwrap().id);
+        call_json::<ClosetDetail>(&api, Method::POST, &uri, partner_token, None).await;
+        let uri = format!("/closets/{}/members/{}", personal, partner.user.id);
+        let co_owner = serde_json::json!({ "role": "owner" });
+        let (status, _) =
+            call_json::<closet_member::Model>(&api, Method::PUT, &uri, owner_token, Some(co_owner))
+                .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, _) =
+            call_json::<closet_member::Model>(&api, Method::DELETE, &uri, owner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let towel_uri = format!("/items/{}", towel.id);
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::GET, &towel_uri, partner_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "not a member yet");
+
+        let uri = format!("/closets/{}/invitations", household.closet.id);
+        let nobody = serde_json::json!({ "username": "nobody", "role": "viewer" });
+        let (status, _) = call_json::<closet_invitation::Model>(
+            &api,
+            Method::POST,
+            &uri,
+            owner_token,
+            Some(nobody),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let invite = serde_json::json!({ "email": "partner@closet.test", "role": "viewer" });
+        let (status, _) = call_json::<closet_invitation::Model>(
+            &api,
+            Method::POST,
+            &uri,
+            partner_token,
+            Some(invite.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "only members can invite");
+        let (status, _) = call_json::<closet_invitation::Model>(
+            &api,
+            Method::POST,
+            &uri,
+            owner_token,
+            Some(invite),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, pending) = call_json::<Vec<InvitationDetail>>(
+            &api,
+            Method::GET,
+            "/closets/invitations",
+            partner_token,
+            None,
+        )
+        .await;
+        let pending = pending.result().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].closet.name, "household");
+        let uri = format!("/closets/invitations/{}/accept", pending[0].invitation.id);
+        let (_, joined) =
+            call_json::<ClosetDetail>(&api, Method::POST, &uri, partner_token, None).await;
+        assert_eq!(joined.result().unwrap().members.len(), 2);
+
+        // viewers can see but not change
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::GET, &towel_uri, partner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, items) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items", partner_token, None).await;
+        assert_eq!(items.result().unwrap().items.len(), 1, "the personal closet is not shared");
+        let (status, _) =
+            call_json::<item::Model>(&api, Method::DELETE, &towel_uri, partner_token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let create = serde_json::json!({
+            "name": "bath", "items": [towel.id], "closet_id": household.closet.id
+        });
+        let (_, bath) = call_json::<crate::handler::outfit::OutfitDetail>(
+            &api,
+            Method::POST,
+            "/outfits",
+            owner_token,
+            Some(create),
+        )
+        .await;
+        let plan = serde_json::json!({ "outfit_id": bath.result().unwrap().outfit.id });
+        let uri = format!("/calendar/{}", partner.user.today());
+        let (status, _) =
+            call_json::<serde_json::Value>(&api, Method::PUT, &uri, partner_token, Some(plan))
+                .await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "planning wears the items");
+
+        let uri = format!("/closets/{}/members/{}", household.closet.id, partner.user.id);
+        let editor = serde_json::json!({ "role": "editor" });
+        let (status, _) = call_json::<closet_member::Model>(
+            &api,
+            Method::PUT,
+            &uri,
+            partner_token,
+            Some(editor.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "viewers cannot promote themselves");
+        let (status, _) =
+            call_json::<closet_member::Model>(&api, Method::PUT, &uri, owner_token, Some(editor))
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) =
+            call_json::<item::Model>(&api, Method::DELETE, &towel_uri, partner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let uri = format!("/closets/{}/members/{}", household.closet.id, owner.user.id);
+        let (status, _) =
+            call_json::<closet_member::Model>(&api, Method::DELETE, &uri, owner_token, None).await;
+        assert_eq!(status, StatusCode::CONFLICT, "the last owner cannot leave");
+        let uri = format!("/closets/{}", household.closet.id);
+        let (status, _) =
+            call_json::<closet::Model>(&api, Method::DELETE, &uri, partner_token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) =
+            call_json::<closet::Model>(&api, Method::DELETE, &uri, owner_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, closets) =
+            call_json::<Page<ClosetDetail>>(&api, Method::GET, "/closets", partner_token, None)
+                .await;
+        assert!(closets.result().unwrap().items.iter().all(|c| c.closet.name != "household"));
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+};
+use entity::{
+    class::{availability::Availability, id::Id, role::Role},
+    error::FieldErrors,
+    model::{
+        closet, favorite, item, item_color, item_image, item_tag, item_transition, item_wear, loan,
+        outfit_item, packing_item, wish,
+    },
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    EntityTrait, IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter, QuerySelect,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::{lock_accessible, ItemDetail},
+    json::Json,
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::duplicate::{self, Garment},
+    AppState,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DuplicateQuery {
+    pub closet: Option<Id<closet::Model>>,
+    /// 0 to 1000, defaults to likely duplicates only
+    pub threshold: Option<i32>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Duplicate {
+    pub item: ItemDetail,
+    pub other: ItemDetail,
+    pub score: i32,
+    pub reasons: Vec<String>,
+}
+/// Pairs of active items of the same closet that may be the same garment, the most similar
+/// first.
+pub async fn duplicates(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<DuplicateQuery>,
+) -> ApiResult<Vec<Duplicate>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(item::Column::RetiredOn.is_null());
+    if let Some(closet) = query.closet {
+        select = select.filter(item::Column::ClosetId.eq(closet));
+    }
+    let details = ItemDetail::load(&state.db, select.all(&state.db).await?).await?;
+    let hashes: Vec<(Id<item::Model>, i64)> = item_image::Entity::find()
+        .select_only()
+        .column(item_image::Column::ItemId)
+        .column(item_image::Column::PerceptualHash)
+        .filter(item_image::Column::ItemId.is_in(details.iter().map(|d| d.item.id.clone())))
+        .filter(item_image::Column::PerceptualHash.is_not_null())
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+    let mut photos: HashMap<_, Vec<u64>> = HashMap::new();
+    for (item_id, hash) in hashes {
+        photos.entry(item_id.identifier()).or_default().push(hash as u64);
+    }
+
+    let garments: Vec<_> = details
+        .iter()
+        .map(|d| Garment {
+            id: d.item.id.clone(),
+            category: d.item.category,
+            brand: d.item.brand.clone(),
+            colors: d.colors.iter().map(|c| c.color).collect(),
+            hashes: photos.remove(&d.item.id.identifier()).unwrap_or_default(),
+        })
+        .collect();
+    let detail = |id: &Id<item::Model>| details.iter().find(|d| &d.item.id == id);
+    let threshold = query.threshold.unwrap_or(duplicate::THRESHOLD);
+    let duplicates = duplicate::detect(&garments, threshold)
+        .into_iter()
+        .filter_map(|s| {
+            let (item, other) = (detail(&s.id)?.clone(), detail(&s.other)?.clone());
+            let (score, reasons) = (s.score, s.reasons);
+            (item.item.closet_id == other.item.closet_id).then_some(Duplicate {
+                item,
+                other,
+                score,
+                reasons,
+            })
+        })
+        .collect();
+    Ok(ApiResponse::Success(duplicates))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ItemMerge {
+    /// the item merged into this one and then deleted
+    pub from: Id<item::Model>,
+}
+/// Merge an item entered twice into this one: wears, transitions, loans, outfits, packing
+/// lists, favorites, tags and photos move over, and details this item lacks are taken from the other.
+pub async fn merge(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    Json(schema): Json<ItemMerge>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    if schema.from == id {
+        Err(FieldErrors::of("from", "cannot merge an item into itself"))?
+    }
+    let txn = state.db.begin().await?;
+    // locked in the order of their ids, so that two merges of the pair cannot deadlock
+    let (target, source) = if id.to_string() < schema.from.to_string() {
+        let target = lock_accessible(&txn, &user, id, Role::Editor).await?;
+        (target, lock_accessible(&txn, &user, schema.from, Role::Editor).await?)
+    } else {
+        let source = lock_accessible(&txn, &user, schema.from, Role::Editor).await?;
+        (lock_accessible(&txn, &user, id, Role::Editor).await?, source)
+    };
+    if target.closet_id != source.closet_id {
+        let error = anyhow::anyhow!("{} and {} are in different closets", target.name, source.name);
+        Err((StatusCode::CONFLICT, error))?
+    }
+    if let Some(retired) = [&target, &source].into_iter().find(|i| i.is_retired()) {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", retired.name)))?
+    }
+    if source.availability == Availability::LentOut {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is lent out", source.name)))?
+    }
+    move_history(&txn, &source.id, &target.id).await?;
+
+    let mut active = target.clone().into_active_model();
+    active.set_purchase_price(target.purchase_price().or(source.purchase_price()));
+    active.storage_id = ActiveValue::Set(target.storage_id.or(source.storage_id.clone()));
+    active.category = ActiveValue::Set(target.category.or(source.category));
+    active.brand = ActiveValue::Set(target.brand.or(source.brand.clone()));
+    active.notes = ActiveValue::Set(target.notes.or(source.notes.clone()));
+    active.care = ActiveValue::Set(target.care.or(source.care));
+    active.size = ActiveValue::Set(target.size.or(source.size));
+    active.fit = ActiveValue::Set(target.fit.or(source.fit));
+    active.fit_note = ActiveValue::Set(target.fit_note.or(source.fit_note.clone()));
+    active.purchased_on = ActiveValue::Set(target.purchased_on.or(source.purchased_on));
+    let merged = active.update(&txn).await?;
+    source.delete(&txn).await?;
+    let mut details = ItemDetail::load(&txn, vec![merged]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+/// repoint everything recorded about `source` to `target`; rows `target` already has an
+/// equivalent of are left to be deleted with `source`
+async fn move_history<C: ConnectionTrait>(
+    db: &C,
+    source: &Id<item::Model>,
+    target: &Id<item::Model>,
+) -> Result<(), DbErr> {
+    let to = || Expr::value(target.clone());
+    macro_rules! repoint {
+        ($entity: ident) => {
+            $entity::Entity::update_many()
+                .col_expr($entity::Column::ItemId, to())
+                .filter($entity::Column::ItemId.eq(source.clone()))
+                .exec(db)
+                .await?;
+        };
+    }
+    repoint!(item_wear);
+    repoint!(item_transition);
+    repoint!(item_image);
+    repoint!(loan);
+    repoint!(wish);
+
+    let colored = item_color::Entity::find()
+        .filter(item_color::Column::ItemId.eq(target.clone()))
+        .count(db)
+        .await?;
+    if colored == 0 {
+        repoint!(item_color);
+    }
+    let tags: Vec<_> = item_tag::Entity::find()
+        .filter(item_tag::Column::ItemId.eq(target.clone()))
+        .all(db)
+        .await?;
+    item_tag::Entity::update_many()
+        .col_expr(item_tag::Column::ItemId, to())
+        .filter(item_tag::Column::ItemId.eq(source.clone()))
+        .filter(item_tag::Column::Tag.is_not_in(tags.into_iter().map(|t| t.tag)))
+        .exec(db)
+        .await?;
+    let outfits: Vec<_> = outfit_item::Entity::find()
+        .filter(outfit_item::Column::ItemId.eq(target.clone()))
+        .all(db)
+        .await?;
+    outfit_item::Entity::update_many()
+        .col_expr(outfit_item::Column::ItemId, to())
+        .filter(outfit_item::Column::ItemId.eq(source.clone()))
+        .filter(outfit_item::Column::OutfitId.is_not_in(outfits.into_iter().map(|o| o.outfit_id)))
+        .exec(db)
+        .await?;
+    let trips: Vec<_> = packing_item::Entity::find()
+        .filter(packing_item::Column::ItemId.eq(target.clone()))
+        .all(db)
+        .await?;
+    packing_item::Entity::update_many()
+        .col_expr(packing_item::Column::ItemId, to())
+        .filter(packing_item::Column::ItemId.eq(source.clone()))
+        .filter(packing_item::Column::TripId.is_not_in(trips.into_iter().map(|p| p.trip_id)))
+        .exec(db)
+        .await?;
+    let favorites: Vec<_> = favorite::Entity::find()
+        .filter(favorite::Column::ItemId.eq(target.clone()))
+        .all(db)
+        .await?;
+    favorite::Entity::update_many()
+        .col_expr(favorite::Column::ItemId, to())
+        .filter(favorite::Column::ItemId.eq(source.clone()))
+        .filter(favorite::Column::UserId.is_not_in(favorites.into_iter().map(|f| f.user_id)))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_duplicate_scenario() {
+        use hyper::{Body, Method, StatusCode};
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use super::*;
+        use crate::handler::outfit::OutfitDetail;
+        use crate::response::page::Page;
+        use crate::{call, call_json, configuration::Configuration, request, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut png = Vec::new();
+        let photo = RgbaImage::from_fn(40, 40, |x, y| {
+            if x + y < 50 {
+                Rgba([30, 40, 70, 255])
+            } else {
+                Rgba([245, 245, 245, 255])
+            }
+        });
+        DynamicImage::ImageRgba8(photo)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let mut ids = Vec::new();
+        for (name, category, brand, tags) in [
+            ("navy tee", "top", "Acme", vec!["basic"]),
+            ("navy t-shirt", "top", "acme", vec!["basic", "summer"]),
+            ("jeans", "bottom", "Acme", vec![]),
+        ] {
+            let create = serde_json::json!({
+                "name": name, "category": category, "brand": brand, "tags": tags
+            });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            let id = created.result().unwrap().item.id.clone();
+            let upload = request(Method::POST, &format!("/items/{}/images", id), token)
+                .body(Body::from(png.clone()))
+                .unwrap();
+            assert_eq!(call(&api, upload).await.0, StatusCode::OK);
+            ids.push(id);
+        }
+        let wear = serde_json::json!({ "worn_on": "2023-06-01" });
+        let uri = format!("/items/{}/wear", ids[1]);
+        call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(wear)).await;
+        let outfit = serde_json::json!({ "name": "weekend", "items": [ids[1], ids[2]] });
+        let (_, outfit) =
+            call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(outfit)).await;
+        let outfit = outfit.result().unwrap().outfit.clone();
+        let uri = format!("/items/{}/favorite", ids[1]);
+        call_json::<favorite::Model>(&api, Method::PUT, &uri, token, None).await;
+
+        let (_, duplicates) =
+            call_json::<Vec<Duplicate>>(&api, Method::GET, "/items/duplicates", token, None).await;
+        let duplicates = duplicates.result().unwrap();
+        assert_eq!(duplicates.len(), 1, "jeans are another category");
+        let pair = [&duplicates[0].item.item.id, &duplicates[0].other.item.id];
+        assert!(pair.contains(&&ids[0]) && pair.contains(&&ids[1]));
+        assert_eq!(duplicates[0].score, 1000);
+        assert_eq!(duplicates[0].reasons.len(), 4);
+
+        let uri = format!("/items/{}/merge", ids[0]);
+        let itself = serde_json::json!({ "from": ids[0] });
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(itself)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let other = signup(&api, "other").await;
+        let from = serde_json::json!({ "from": ids[1] });
+        let (status, _) = call_json::<ItemDetail>(
+            &api,
+            Method::POST,
+            &uri,
+            other.token.as_deref(),
+            Some(from.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, merged) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(from)).await;
+        assert_eq!(status, StatusCode::OK);
+        let merged = merged.result().unwrap();
+        assert_eq!(merged.item.name, "navy tee");
+        assert_eq!(merged.tags.iter().map(|t| t.as_str()).collect::<Vec<_>>(), ["basic", "summer"]);
+        assert_eq!(merged.images.len(), 2);
+
+        let uri = format!("/items/{}/wears", ids[0]);
+        let (_, wears) =
+            call_json::<Page<item_wear::Model>>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(wears.result().unwrap().items.len(), 1);
+        let uri = format!("/outfits/{}", outfit.id);
+        let (_, outfit) = call_json::<OutfitDetail>(&api, Method::GET, &uri, token, None).await;
+        assert!(outfit.result().unwrap().items.iter().any(|i| i.id == ids[0]));
+        let (_, favorites) = call_json::<crate::handler::feedback::Favorites>(
+            &api,
+            Method::GET,
+            "/favorites",
+            token,
+            None,
+        )
+        .await;
+        let favorites = favorites.result().unwrap();
+        assert_eq!(favorites.items.iter().map(|i| &i.item.id).collect::<Vec<_>>(), [&ids[0]]);
+        let uri = format!("/items/{}", ids[1]);
+        let (status, _) = call_json::<ItemDetail>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, duplicates) =
+            call_json::<Vec<Duplicate>>(&api, Method::GET, "/items/duplicates", token, None).await;
+        assert!(duplicates.result().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{id::Id, role::Role, tag::Tag},
+    model::{favorite, item, item_wear, outfit, outfit_item, outfit_wear, outfit_wear_tag, user},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::{Expr, Func, Query, SelectStatement, SimpleExpr, SubQueryStatement},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, IntoActiveModel, LoaderTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
+    Select, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::ItemDetail,
+    json::Json,
+    listing::{Computed, Listed, Listing},
+    outfit::OutfitDetail,
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    service::loved::{Love, NEUTRAL_RATING},
+    AppState,
+};
+
+/// Sort of items and outfits by how much the user loves them, in the order of [`Love::keys`].
+pub(crate) struct Loved(pub Id<user::Model>);
+impl Loved {
+    pub const SORT: &'static str = "loved";
+
+    /// 0 for favorites of the user, 1 for the rest
+    fn favorite(&self, column: favorite::Column, id: SimpleExpr) -> SimpleExpr {
+        let favorites = Query::select()
+            .column(column)
+            .from(favorite::Entity)
+            .and_where(favorite::Column::UserId.eq(self.0.clone()))
+            .to_owned();
+        SimpleExpr::Case(Box::new(Expr::case(Expr::expr(id).in_subquery(favorites), 0).finally(1)))
+    }
+    /// the outfit wears of the user that are rated
+    fn rated(&self) -> SelectStatement {
+        Query::select()
+            .from(outfit_wear::Entity)
+            .and_where(outfit_wear::Column::UserId.eq(self.0.clone()))
+            .and_where(outfit_wear::Column::Rating.is_not_null())
+            .to_owned()
+    }
+}
+/// minus the average of the `ratings` in thousandths rounded down, or of the neutral rating
+fn rating(mut ratings: SelectStatement) -> SimpleExpr {
+    let rating = || Expr::col((outfit_wear::Entity, outfit_wear::Column::Rating));
+    let thousandths = || Expr::expr(Func::sum(rating())).mul(1000);
+    let count = || Func::count(rating());
+    // divided exactly, as mysql divides integers into decimals
+    let rounded = thousandths().sub(Expr::expr(thousandths()).modulo(count()));
+    ratings.expr(rounded.div(count()));
+    Expr::val(0).sub(Func::if_null(subquery(ratings), (NEUTRAL_RATING * 1000.0) as i64))
+}
+/// minus the number of `rows`
+fn count(mut rows: SelectStatement) -> SimpleExpr {
+    rows.expr(Func::count(Expr::asterisk()));
+    Expr::val(0).sub(subquery(rows))
+}
+fn subquery(select: SelectStatement) -> SimpleExpr {
+    SimpleExpr::SubQuery(None, Box::new(SubQueryStatement::SelectStatement(select)))
+}
+#[async_trait::async_trait]
+impl Computed<item::Entity> for Loved {
+    fn name(&self) -> &'static str {
+        Self::SORT
+    }
+    fn keys(&self) -> Vec<SimpleExpr> {
+        let id = || (item::Entity, item::Column::Id);
+        let worn_with = Expr::col((outfit_item::Entity, outfit_item::Column::OutfitId))
+            .equals((outfit_wear::Entity, outfit_wear::Column::OutfitId));
+        let ratings = self
+            .rated()
+            .inner_join(outfit_item::Entity, worn_with)
+            .and_where(Expr::col((outfit_item::Entity, outfit_item::Column::ItemId)).equals(id()))
+            .to_owned();
+        let wears = Query::select()
+            .from(item_wear::Entity)
+            .and_where(Expr::col((item_wear::Entity, item_wear::Column::ItemId)).equals(id()))
+            .to_owned();
+        vec![
+            self.favorite(favorite::Column::ItemId, Expr::col(id()).into()),
+            rating(ratings),
+            count(wears),
+        ]
+    }
+    async fn values(
+        &self,
+        db: &DatabaseConnection,
+        models: &[item::Model],
+    ) -> Result<Vec<Vec<i64>>, DbErr> {
+        let loves = item_loves(db, &self.0, models).await?;
+        Ok(loves.iter().map(Love::keys).collect())
+    }
+}
+#[async_trait::async_trait]
+impl Computed<outfit::Entity> for Loved {
+    fn name(&self) -> &'static str {
+        Self::SORT
+    }
+    fn keys(&self) -> Vec<SimpleExpr> {
+        let id = || (outfit::Entity, outfit::Column::Id);
+        let worn = || Expr::col((outfit_wear::Entity, outfit_wear::Column::OutfitId)).equals(id());
+        let ratings = self.rated().and_where(worn()).to_owned();
+        let wears = Query::select()
+            .from(outfit_wear::Entity)
+            .and_where(outfit_wear::Column::UserId.eq(self.0.clone()))
+            .and_where(worn())
+            .to_owned();
+        vec![
+            self.favorite(favorite::Column::OutfitId, Expr::col(id()).into()),
+            rating(ratings),
+            count(wears),
+        ]
+    }
+    async fn values(
+        &self,
+        db: &DatabaseConnection,
+        models: &[outfit::Model],
+    ) -> Result<Vec<Vec<i64>>, DbErr> {
+        let loves = outfit_loves(db, &self.0, models).await?;
+        Ok(loves.iter().map(Love::keys).collect())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "OutfitWearDetail")]
+pub struct OutfitWear {
+    pub wear: outfit_wear::Model,
+    pub feedback: Vec<Tag>,
+}
+impl OutfitWear {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        wears: Vec<outfit_wear::Model>,
+    ) -> Result<Vec<Self>, DbErr> {
+        if wears.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tags = wears
+            .load_many(
+                outfit_wear_tag::Entity::find().order_by_asc(outfit_wear_tag::Column::Tag),
+                db,
+            )
+            .await?;
+        Ok(wears
+            .into_iter()
+            .zip(tags)
+            .map(|(wear, tags)| Self { wear, feedback: tags.into_iter().map(|t| t.tag).collect() })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WearFeedback {
+    /// 1 to 5
+    pub rating: Option<i32>,
+    /// e.g. `too-warm`, `compliments`
+    #[serde(default)]
+    pub feedback: Vec<Tag>,
+    pub note: Option<String>,
+}
+async fn save_feedback<C: ConnectionTrait>(
+    db: &C,
+    mut active: outfit_wear::ActiveModel,
+    feedback: WearFeedback,
+) -> Result<OutfitWear, ApiError> {
+    let rating = feedback.rating.map(outfit_wear::Model::validate_rating).transpose()?;
+    active.rating = ActiveValue::Set(rating);
+    active.note = ActiveValue::Set(feedback.note);
+    let wear = active.save(db).await?;
+    let wear = outfit_wear::Entity::find_by_id(wear.id.unwrap())
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    outfit_wear_tag::Entity::delete_many()
+        .filter(outfit_wear_tag::Column::OutfitWearId.eq(wear.id.clone()))
+        .exec(db)
+        .await?;
+    let mut tags = feedback.feedback;
+    tags.sort();
+    tags.dedup();
+    for tag in tags {
+        outfit_wear_tag::ActiveModel {
+            outfit_wear_id: ActiveValue::Set(wear.id.clone()),
+            tag: ActiveValue::Set(tag),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(OutfitWear::load(db, vec![wear]).await?.remove(0))
+}
+
+/// Items of the outfit, locked as their availability is read to be written back.
+fn locked_items(outfit: &outfit::Model) -> Select<item::Entity> {
+    outfit.find_related(item::Entity).order_by_asc(item::Column::Id).lock_exclusive()
+}
+async fn log_outfit_wear<C: ConnectionTrait>(
+    db: &C,
+    user: &user::Model,
+    outfit: &outfit::Model,
+    items: Vec<item::Model>,
+    worn_on: NaiveDate,
+    feedback: WearFeedback,
+) -> Result<OutfitWear, ApiError> {
+    for item in items {
+        super::item::record_wear(db, item, worn_on, &user.time_zone).await?;
+    }
+    let active = outfit_wear::ActiveModel {
+        outfit_id: ActiveValue::Set(outfit.id.clone()),
+        user_id: ActiveValue::Set(user.id.clone()),
+        worn_on: ActiveValue::Set(worn_on),
+        ..Default::default()
+    };
+    save_feedback(db, active, feedback).await
+}
+
+/// Record that the user wore the outfit, and each of its items, on the day.
+pub(crate) async fn record_outfit_wear<C: ConnectionTrait>(
+    db: &C,
+    user: &user::Model,
+    outfit: &outfit::Model,
+    worn_on: NaiveDate,
+    feedback: WearFeedback,
+) -> Result<OutfitWear, ApiError> {
+    let items = locked_items(outfit).all(db).await?;
+    if let Some(retired) = items.iter().find(|i| i.is_retired()) {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", retired.name)))?
+    }
+    log_outfit_wear(db, user, outfit, items, worn_on, feedback).await
+}
+
+/// Record the outfit planned for the day as worn, leaving out its items retired since the plan.
+pub(crate) async fn settle_outfit_wear<C: ConnectionTrait>(
+    db: &C,
+    user: &user::Model,
+    outfit: &outfit::Model,
+    worn_on: NaiveDate,
+) -> Result<OutfitWear, ApiError> {
+    let items = locked_items(outfit).filter(item::Column::RetiredOn.is_null()).all(db).await?;
+    log_outfit_wear(db, user, outfit, items, worn_on, Default::default()).await
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct OutfitWearCreate {
+    /// defaults to today
+    pub worn_on: Option<NaiveDate>,
+    #[serde(flatten)]
+    pub feedback: WearFeedback,
+}
+pub async fn wear(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+    Json(schema): Json<OutfitWearCreate>,
+) -> ApiResult<OutfitWear> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let worn_on = schema.worn_on.unwrap_or_else(|| user.user.today());
+    let txn = state.db.begin().await?;
+    let outfit = super::outfit::find_accessible(&txn, &user, id, Role::Editor).await?;
+    let wear = record_outfit_wear(&txn, &user.user, &outfit, worn_on, schema.feedback).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(wear))
+}
+
+impl Listed for outfit_wear::Entity {
+    type Entity = Self;
+    const ID: outfit_wear::Column = outfit_wear::Column::Id;
+    const SORTABLE: &'static [outfit_wear::Column] =
+        &[outfit_wear::Column::WornOn, outfit_wear::Column::CreatedAt];
+    const FILTERABLE: &'static [outfit_wear::Column] =
+        &[outfit_wear::Column::UserId, outfit_wear::Column::WornOn, outfit_wear::Column::Rating];
+    const DEFAULT_SORT: &'static str = "-worn_on";
+}
+/// Wears of the outfit by every member of its closet, the most recent first by default.
+pub async fn wears(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+    listing: Listing<outfit_wear::Entity>,
+) -> ApiPage<OutfitWear> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let outfit = super::outfit::find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let select = outfit_wear::Entity::find().filter(outfit_wear::Column::OutfitId.eq(outfit.id));
+    let page = listing.fetch(&state.db, select).await?;
+    let wears = OutfitWear::load(&state.db, page.items.clone()).await?;
+    Ok(ApiResponse::Success(page.with_items(wears)))
+}
+
+/// Rate a wear of the user after the fact, replacing the former feedback.
+pub async fn rate(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, wear_id)): Path<(Id<outfit::Model>, Id<outfit_wear::Model>)>,
+    Json(schema): Json<WearFeedback>,
+) -> ApiResult<OutfitWear> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let wear = outfit_wear::Entity::find_by_id(wear_id)
+        .filter(outfit_wear::Column::OutfitId.eq(id))
+        .filter(outfit_wear::Column::UserId.eq(user.user.id))
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    let wear = save_feedback(&txn, wear.into_active_model(), schema).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(wear))
+}
+
+async fn find_favorite<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    item_id: Option<Id<item::Model>>,
+    outfit_id: Option<Id<outfit::Model>>,
+) -> Result<Option<favorite::Model>, DbErr> {
+    let select = favorite::Entity::find().filter(favorite::Column::UserId.eq(user.user.id.clone()));
+    let select = match (item_id, outfit_id) {
+        (Some(item_id), _) => select.filter(favorite::Column::ItemId.eq(item_id)),
+        (None, Some(outfit_id)) => select.filter(favorite::Column::OutfitId.eq(outfit_id)),
+        (None, None) => return Ok(None),
+    };
+    select.one(db).await
+}
+async fn mark<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    item_id: Option<Id<item::Model>>,
+    outfit_id: Option<Id<outfit::Model>>,
+) -> Result<favorite::Model, ApiError> {
+    if let Some(favorite) = find_favorite(db, user, item_id.clone(), outfit_id.clone()).await? {
+        return Ok(favorite);
+    }
+    let favorite = favorite::ActiveModel {
+        user_id: ActiveValue::Set(user.user.id.clone()),
+        item_id: ActiveValue::Set(item_id),
+        outfit_id: ActiveValue::Set(outfit_id),
+        ..Default::default()
+    };
+    Ok(favorite.insert(db).await?)
+}
+async fn unmark<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    item_id: Option<Id<item::Model>>,
+    outfit_id: Option<Id<outfit::Model>>,
+) -> Result<favorite::Model, ApiError> {
+    let favorite =
+        find_favorite(db, user, item_id, outfit_id).await?.ok_or(ApiError::RecordNotFound)?;
+    favorite.clone().delete(db).await?;
+    Ok(favorite)
+}
+
+pub async fn favorite_item(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+) -> ApiResult<favorite::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = super::item::find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    Ok(ApiResponse::Success(mark(&state.db, &user, Some(item.id), None).await?))
+}
+pub async fn unfavorite_item(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+) -> ApiResult<favorite::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    Ok(ApiResponse::Success(unmark(&state.db, &user, Some(id), None).await?))
+}
+pub async fn favorite_outfit(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+) -> ApiResult<favorite::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let outfit = super::outfit::find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    Ok(ApiResponse::Success(mark(&state.db, &user, None, Some(outfit.id)).await?))
+}
+pub async fn unfavorite_outfit(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+) -> ApiResult<favorite::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    Ok(ApiResponse::Success(unmark(&state.db, &user, None, Some(id)).await?))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Favorites {
+    pub items: Vec<ItemDetail>,
+    pub outfits: Vec<OutfitDetail>,
+}
+/// Favorites of the user that are still in a closet they are a member of.
+pub async fn favorites(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<Favorites> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let favorites = favorite::Entity::find()
+        .filter(favorite::Column::UserId.eq(user.user.id.clone()))
+        .order_by_desc(favorite::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+    let members = || super::closet::member_closets(&user.user.id);
+    let items = item::Entity::find()
+        .filter(item::Column::Id.is_in(favorites.iter().filter_map(|f| f.item_id.clone())))
+        .filter(item::Column::ClosetId.in_subquery(members()))
+        .order_by_asc(item::Column::Name)
+        .all(&state.db)
+        .await?;
+    let outfits = outfit::Entity::find()
+        .filter(outfit::Column::Id.is_in(favorites.iter().filter_map(|f| f.outfit_id.clone())))
+        .filter(outfit::Column::ClosetId.in_subquery(members()))
+        .order_by_asc(outfit::Column::Name)
+        .all(&state.db)
+        .await?;
+    let items = ItemDetail::load(&state.db, items).await?;
+    let outfits = OutfitDetail::load(&state.db, outfits).await?;
+    Ok(ApiResponse::Success(Favorites { items, outfits }))
+}
+
+/// how much the user loves each of the outfits
+pub(crate) async fn outfit_loves<C: ConnectionTrait>(
+    db: &C,
+    user_id: &Id<user::Model>,
+    outfits: &[outfit::Model],
+) -> Result<Vec<Love>, DbErr> {
+    let ids = || outfits.iter().map(|o| o.id.clone());
+    let favorites: Vec<_> = favorite::Entity::find()
+        .filter(favorite::Column::UserId.eq(user_id.clone()))
+        .filter(favorite::Column::OutfitId.is_in(ids()))
+        .all(db)
+        .await?;
+    let wears = outfit_wear::Entity::find()
+        .filter(outfit_wear::Column::UserId.eq(user_id.clone()))
+        .filter(outfit_wear::Column::OutfitId.is_in(ids()))
+        .all(db)
+        .await?;
+    Ok(outfits
+        .iter()
+        .map(|outfit| {
+            let worn: Vec<_> = wears.iter().filter(|w| w.outfit_id == outfit.id).collect();
+            Love {
+                favorite: favorites.iter().any(|f| f.outfit_id.as_ref() == Some(&outfit.id)),
+                ratings: worn.iter().filter_map(|w| w.rating).collect(),
+                wears: worn.len(),
+            }
+        })
+        .collect())
+}
+
+/// how much the user loves each of the items, rated through the outfits worn with them
+pub(crate) async fn item_loves<C: ConnectionTrait>(
+    db: &C,
+    user_id: &Id<user::Model>,
+    items: &[item::Model],
+) -> Result<Vec<Love>, DbErr> {
+    let ids = || items.iter().map(|i| i.id.clone());
+    let favorites: Vec<_> = favorite::Entity::find()
+        .filter(favorite::Column::UserId.eq(user_id.clone()))
+        .filter(favorite::Column::ItemId.is_in(ids()))
+        .all(db)
+        .await?;
+    let rated = outfit_wear::Entity::find()
+        .filter(outfit_wear::Column::UserId.eq(user_id.clone()))
+        .filter(outfit_wear::Column::Rating.is_not_null())
+        .all(db)
+        .await?;
+    let worn_with = outfit_item::Entity::find()
+        .filter(outfit_item::Column::OutfitId.is_in(rated.iter().map(|w| w.outfit_id.clone())))
+        .filter(outfit_item::Column::ItemId.is_in(ids()))
+        .all(db)
+        .await?;
+    let mut ratings: HashMap<_, Vec<i32>> = HashMap::new();
+    for wear in &rated {
+        for outfit_item in worn_with.iter().filter(|o| o.outfit_id == wear.outfit_id) {
+            let rating = wear.rating.into_iter();
+            ratings.entry(outfit_item.item_id.identifier()).or_default().extend(rating);
+        }
+    }
+    let mut wears: HashMap<_, usize> = HashMap::new();
+    for wear in
+        item_wear::Entity::find().filter(item_wear::Column::ItemId.is_in(ids())).all(db).await?
+    {
+        *wears.entry(wear.item_id.identifier()).or_default() += 1;
+    }
+    Ok(items
+        .iter()
+        .map(|item| Love {
+            favorite: favorites.iter().any(|f| f.item_id.as_ref() == Some(&item.id)),
+            ratings: ratings.remove(&item.id.identifier()).unwrap_or_default(),
+            wears: wears.get(&item.id.identifier()).copied().unwrap_or_default(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_outfit_feedback_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::response::page::Page;
+        use crate::{call_json, configuration::Configuration, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let mut ids = Vec::new();
+        for (name, category) in [("shirt", "top"), ("chinos", "bottom"), ("jeans", "bottom")] {
+            let create = serde_json::json!({ "name": name, "category": category });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+        let mut outfits = Vec::new();
+        for (name, items) in
+            [("office", &ids[..2]), ("weekend", &[ids[0].clone(), ids[2].clone()][..])]
+        {
+            let create = serde_json::json!({ "name": name, "items": items });
+            let (_, created) =
+                call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(create))
+                    .await;
+            outfits.push(created.result().unwrap().outfit.id.clone());
+        }
+
+        let uri = format!("/outfits/{}/wear", outfits[1]);
+        let wear = serde_json::json!({ "worn_on": "2023-06-01", "rating": 6 });
+        let (status, _) =
+            call_json::<OutfitWear>(&api, Method::POST, &uri, token, Some(wear)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let wear = serde_json::json!({
+            "worn_on": "2023-06-01", "rating": 5, "feedback": ["compliments", "comfortable"]
+        });
+        let (status, worn) =
+            call_json::<OutfitWear>(&api, Method::POST, &uri, token, Some(wear)).await;
+        assert_eq!(status, StatusCode::OK);
+        let worn = worn.result().unwrap();
+        assert_eq!(worn.wear.rating, Some(5));
+        let feedback: Vec<_> = worn.feedback.iter().map(|t| t.to_string()).collect();
+        assert_eq!(feedback, vec!["comfortable", "compliments"]);
+        let (_, shirt) = call_json::<Page<item_wear::Model>>(
+            &api,
+            Method::GET,
+            &format!("/items/{}/wears", ids[0]),
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(shirt.result().unwrap().items.len(), 1);
+
+        let other = signup(&api, "other").await;
+        let rate = format!("/outfits/{}/wears/{}", outfits[1], worn.wear.id);
+        let feedback = serde_json::json!({ "rating": 1, "feedback": ["too-warm"] });
+        let (status, _) = call_json::<OutfitWear>(
+            &api,
+            Method::PUT,
+            &rate,
+            other.token.as_deref(),
+            Some(feedback.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, rated) =
+            call_json::<OutfitWear>(&api, Method::PUT, &rate, token, Some(feedback)).await;
+        assert_eq!(rated.result().unwrap().wear.rating, Some(1));
+        let (_, wears) = call_json::<Page<OutfitWear>>(
+            &api,
+            Method::GET,
+            &format!("/outfits/{}/wears", outfits[1]),
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(wears.result().unwrap().items, vec![rated.result().unwrap().clone()]);
+
+        // the office outfit is rated well, the weekend one badly
+        let uri = format!("/outfits/{}/wear", outfits[0]);
+        let wear = serde_json::json!({ "worn_on": "2023-06-02", "rating": 4 });
+        call_json::<OutfitWear>(&api, Method::POST, &uri, token, Some(wear)).await;
+        let (_, loved) =
+            call_json::<Page<OutfitDetail>>(&api, Method::GET, "/outfits?sort=loved", token, None)
+                .await;
+        let loved = loved.result().unwrap();
+        let loved: Vec<_> = loved.items.iter().map(|o| o.outfit.id.clone()).collect();
+        assert_eq!(loved, vec![outfits[0].clone(), outfits[1].clone()]);
+        let (_, loved) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items?sort=loved", token, None)
+                .await;
+        let loved: Vec<_> =
+            loved.result().unwrap().items.iter().map(|i| i.item.id.clone()).collect();
+        assert_eq!(loved, vec![ids[1].clone(), ids[0].clone(), ids[2].clone()]);
+        let (mut paged, mut cursor) = (Vec::new(), String::new());
+        for _ in 0..ids.len() {
+            let uri = format!("/items?sort=loved&limit=1{}", cursor);
+            let (_, page) =
+                call_json::<Page<ItemDetail>>(&api, Method::GET, &uri, token, None).await;
+            let page = page.result().unwrap();
+            paged.extend(page.items.iter().map(|i| i.item.id.clone()));
+            match &page.next {
+                Some(next) => cursor = format!("&cursor={}", next),
+                None => break,
+            }
+        }
+        assert_eq!(paged, loved, "pages follow the loved order");
+
+        // favorites come first whatever their ratings
+        let uri = format!("/outfits/{}/favorite", outfits[1]);
+        let (status, _) = call_json::<favorite::Model>(&api, Method::PUT, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call_json::<favorite::Model>(&api, Method::PUT, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let uri = format!("/items/{}/favorite", ids[2]);
+        call_json::<favorite::Model>(&api, Method::PUT, &uri, token, None).await;
+        let (status, _) =
+            call_json::<favorite::Model>(&api, Method::PUT, &uri, other.token.as_deref(), None)
+                .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, loved) =
+            call_json::<Page<OutfitDetail>>(&api, Method::GET, "/outfits?sort=loved", token, None)
+                .await;
+        assert_eq!(loved.result().unwrap().items[0].outfit.id, outfits[1]);
+        let (_, loved) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items?sort=loved", token, None)
+                .await;
+        assert_eq!(loved.result().unwrap().items[0].item.id, ids[2]);
+
+        let (_, favorites) =
+            call_json::<Favorites>(&api, Method::GET, "/favorites", token, None).await;
+        let favorites = favorites.result().unwrap();
+        assert_eq!(favorites.items.len(), 1);
+        assert_eq!(favorites.outfits[0].outfit.id, outfits[1]);
+        let (status, _) =
+            call_json::<favorite::Model>(&api, Method::DELETE, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) =
+            call_json::<favorite::Model>(&api, Method::DELETE, &uri, token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, favorites) =
+            call_json::<Favorites>(&api, Method::GET, "/favorites", token, None).await;
+        assert!(favorites.result().unwrap().items.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use axum::{extract::State, Router};
 use entity::{class::status::Status, model::health};
 use hyper::StatusCode;
+use schemars::JsonSchema;
 use sea_orm::{EntityTrait, FromJsonQueryResult, FromQueryResult};
 use serde::{Deserialize, Serialize};
 
@@ -10,7 +11,15 @@ use crate::{
 };
 
 #[derive(
-    Debug, Clone, Eq, PartialEq, FromQueryResult, FromJsonQueryResult, Serialize, Deserialize,
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    FromQueryResult,
+    FromJsonQueryResult,
+    Serialize,
+    Deserialize,
+    JsonSchema,
 )]
 pub struct RichHealth {
     pub status: Status,
@@ -0,0 +1,755 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Router,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        availability::Availability,
+        care::Care,
+        category::Category,
+        color::Color,
+        id::Id,
+        money::Money,
+        role::Role,
+        size::{Fit, Size},
+        tag::Tag,
+        time_zone::TimeZone,
+    },
+    error::{FieldErrors, ValidateError},
+    model::{
+        closet,
+        item::{self, InsertItem},
+        item_color, item_image, item_tag, item_transition, item_wear,
+    },
+};
+use hyper::{header, StatusCode};
+use image::ImageFormat;
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseTransaction, DbErr,
+    EntityTrait, IntoActiveModel, LoaderTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    feedback::Loved,
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    service::{duplicate, palette, suggestion::Garment},
+    AppState,
+};
+
+pub fn item_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/import", axum::routing::post(super::bulk::import))
+        .route("/export", axum::routing::get(super::bulk::export))
+        .route("/retired", axum::routing::get(super::retirement::retired))
+        .route("/declutter", axum::routing::get(super::retirement::declutter))
+        .route("/duplicates", axum::routing::get(super::duplicate::duplicates))
+        .route("/:id", axum::routing::get(detail).put(update).delete(delete))
+        .route("/:id/images", axum::routing::post(upload_image))
+        .route("/:id/images/:image_id", axum::routing::get(image))
+        .route("/:id/transition", axum::routing::post(transition))
+        .route("/:id/transitions", axum::routing::get(transitions))
+        .route("/:id/wear", axum::routing::post(wear))
+        .route("/:id/wears", axum::routing::get(wears))
+        .route(
+            "/:id/retire",
+            axum::routing::post(super::retirement::retire).delete(super::retirement::restore),
+        )
+        .route("/:id/merge", axum::routing::post(super::duplicate::merge))
+        .route(
+            "/:id/favorite",
+            axum::routing::put(super::feedback::favorite_item)
+                .delete(super::feedback::unfavorite_item),
+        )
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemDetail {
+    pub item: item::Model,
+    pub colors: Vec<item_color::Model>,
+    pub tags: Vec<Tag>,
+    pub images: Vec<Id<item_image::Model>>,
+}
+impl ItemDetail {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        items: Vec<item::Model>,
+    ) -> Result<Vec<Self>, DbErr> {
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+        let colors = items
+            .load_many(item_color::Entity::find().order_by_desc(item_color::Column::Ratio), db)
+            .await?;
+        let tags = items
+            .load_many(item_tag::Entity::find().order_by_asc(item_tag::Column::Tag), db)
+            .await?;
+        let images: Vec<(Id<item_image::Model>, Id<item::Model>)> = item_image::Entity::find()
+            .select_only()
+            .column(item_image::Column::Id)
+            .column(item_image::Column::ItemId)
+            .filter(item_image::Column::ItemId.is_in(items.iter().map(|i| i.id.clone())))
+            .order_by_asc(item_image::Column::CreatedAt)
+            .into_tuple()
+            .all(db)
+            .await?;
+        Ok(items
+            .into_iter()
+            .zip(colors)
+            .zip(tags)
+            .map(|((item, colors), tags)| {
+                let images = images
+                    .iter()
+                    .filter(|(_, item_id)| item_id == &item.id)
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                let tags = tags.into_iter().map(|t| t.tag).collect();
+                Self { item, colors, tags, images }
+            })
+            .collect())
+    }
+
+    /// as a garment for suggestions and packing, if categorized
+    pub fn garment(&self, last_worn: Option<NaiveDate>) -> Option<Garment> {
+        Some(Garment {
+            id: self.item.id.clone(),
+            name: self.item.name.clone(),
+            category: self.item.category?,
+            color: self.colors.first().map(|c| c.rgb),
+            tags: self.tags.clone(),
+            last_worn,
+            warmth: self.item.warmth,
+            waterproof: self.item.waterproof,
+        })
+    }
+}
+
+/// The item, if the user has at least `required` role in its closet.
+pub(crate) async fn find_accessible<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<item::Model>,
+    required: Role,
+) -> Result<item::Model, ApiError> {
+    let item = item::Entity::find_by_id(id).one(db).await?.ok_or(ApiError::RecordNotFound)?;
+    super::closet::authorize(db, user, &item.closet_id, required).await?;
+    Ok(item)
+}
+/// `find_accessible` locking the row until the transaction `txn` ends, for read-modify-writes
+pub(crate) async fn lock_accessible(
+    txn: &DatabaseTransaction,
+    user: &AuthUser,
+    id: Id<item::Model>,
+    required: Role,
+) -> Result<item::Model, ApiError> {
+    let locked = item::Entity::find_by_id(id).lock_exclusive().one(txn).await?;
+    let item = locked.ok_or(ApiError::RecordNotFound)?;
+    super::closet::authorize(txn, user, &item.closet_id, required).await?;
+    Ok(item)
+}
+
+/// Move the item to `to` along the availability graph, recording the transition.
+pub(crate) async fn transition_item<C: ConnectionTrait>(
+    db: &C,
+    item: item::Model,
+    to: Availability,
+) -> Result<item::Model, ApiError> {
+    let from = item.availability;
+    let to = from.transition(to).map_err(|e| (StatusCode::CONFLICT, e))?;
+    item_transition::ActiveModel {
+        item_id: ActiveValue::Set(item.id.clone()),
+        from: ActiveValue::Set(from),
+        to: ActiveValue::Set(to),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    let mut active = item.into_active_model();
+    active.availability = ActiveValue::Set(to);
+    Ok(active.update(db).await?)
+}
+
+/// Record that the item was worn on `worn_on`; a clean item becomes worn, a lent one stays lent.
+/// A wear older than the latest transition, as of a late settle or a backfill, only goes to the
+/// log: the item may have been washed since.
+pub(crate) async fn record_wear<C: ConnectionTrait>(
+    db: &C,
+    item: item::Model,
+    worn_on: NaiveDate,
+    time_zone: &TimeZone,
+) -> Result<item::Model, ApiError> {
+    item_wear::ActiveModel {
+        item_id: ActiveValue::Set(item.id.clone()),
+        worn_on: ActiveValue::Set(worn_on),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    let latest = item_transition::Entity::find()
+        .filter(item_transition::Column::ItemId.eq(item.id.clone()))
+        .order_by_desc(item_transition::Column::TransitionedAt)
+        .one(db)
+        .await?;
+    let current = worn_on == time_zone.today()
+        || latest.is_none_or(|latest| time_zone.date_of(&latest.transitioned_at) <= worn_on);
+    let lent = item.availability == Availability::LentOut;
+    if current && !lent && item.availability.can_transition(&Availability::Worn) {
+        transition_item(db, item, Availability::Worn).await
+    } else {
+        Ok(item)
+    }
+}
+
+pub(crate) async fn replace_tags<C: ConnectionTrait>(
+    db: &C,
+    item: &item::Model,
+    mut tags: Vec<Tag>,
+) -> Result<(), DbErr> {
+    item_tag::Entity::delete_many()
+        .filter(item_tag::Column::ItemId.eq(item.id.clone()))
+        .exec(db)
+        .await?;
+    tags.sort();
+    tags.dedup();
+    for tag in tags {
+        let item_id = ActiveValue::Set(item.id.clone());
+        item_tag::ActiveModel { item_id, tag: ActiveValue::Set(tag), ..Default::default() }
+            .insert(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// the price unless it is negative, in which case the error of its field
+pub(crate) fn validate_price(
+    field: &str,
+    price: Option<Money>,
+) -> Result<Option<Money>, FieldErrors> {
+    price.map(|price| price.validate_price().map_err(|e| FieldErrors::of(field, e))).transpose()
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ItemCreate {
+    pub name: String,
+    /// defaults to the personal closet on create, ignored on update
+    pub closet_id: Option<Id<closet::Model>>,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub notes: Option<String>,
+    /// 0 to 5, defaults to light clothing
+    pub warmth: Option<i32>,
+    #[serde(default)]
+    pub waterproof: bool,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    pub purchase_price: Option<Money>,
+    pub purchased_on: Option<NaiveDate>,
+    pub care: Option<Care>,
+    pub size: Option<Size>,
+    pub fit: Option<Fit>,
+    pub fit_note: Option<String>,
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<ItemCreate>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let ItemCreate { name, closet_id, category, brand, notes, warmth, waterproof, tags, .. } =
+        schema;
+    let warmth = item::Model::validate_warmth(warmth.unwrap_or(item::Model::DEFAULT_WARMTH))?;
+    let purchase_price = validate_price("purchase_price", schema.purchase_price)?;
+    let txn = state.db.begin().await?;
+    let closet_id = super::closet::writable_closet(&txn, &user, closet_id).await?;
+    let owner_id = user.user.id;
+    let insert_item =
+        InsertItem { owner_id, closet_id, name, category, brand, notes, warmth, waterproof };
+    let mut active = insert_item.into_active_model();
+    active.set_purchase_price(purchase_price);
+    active.purchased_on = ActiveValue::Set(schema.purchased_on);
+    active.care = ActiveValue::Set(schema.care);
+    active.size = ActiveValue::Set(schema.size);
+    active.fit = ActiveValue::Set(schema.fit);
+    active.fit_note = ActiveValue::Set(schema.fit_note);
+    let created = active.insert(&txn).await?;
+    replace_tags(&txn, &created, tags).await?;
+    let mut details = ItemDetail::load(&txn, vec![created]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    Json(schema): Json<ItemCreate>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let ItemCreate { name, category, brand, notes, warmth, waterproof, tags, .. } = schema;
+    let warmth = item::Model::validate_warmth(warmth.unwrap_or(item::Model::DEFAULT_WARMTH))?;
+    let purchase_price = validate_price("purchase_price", schema.purchase_price)?;
+    let txn = state.db.begin().await?;
+    let mut active = lock_accessible(&txn, &user, id, Role::Editor).await?.into_active_model();
+    active.name = ActiveValue::Set(name);
+    active.category = ActiveValue::Set(category);
+    active.brand = ActiveValue::Set(brand);
+    active.notes = ActiveValue::Set(notes);
+    active.warmth = ActiveValue::Set(warmth);
+    active.waterproof = ActiveValue::Set(waterproof);
+    active.set_purchase_price(purchase_price);
+    active.purchased_on = ActiveValue::Set(schema.purchased_on);
+    active.care = ActiveValue::Set(schema.care);
+    active.size = ActiveValue::Set(schema.size);
+    active.fit = ActiveValue::Set(schema.fit);
+    active.fit_note = ActiveValue::Set(schema.fit_note);
+    let updated = active.update(&txn).await?;
+    replace_tags(&txn, &updated, tags).await?;
+    let mut details = ItemDetail::load(&txn, vec![updated]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ItemQuery {
+    /// only items of this closet, otherwise of every closet the user is a member of
+    pub closet: Option<Id<closet::Model>>,
+    /// named color to filter by
+    pub color: Option<Color>,
+    /// if given along with `color`, match palettes perceptually within this delta E (0 or more)
+    /// instead of by name
+    pub near: Option<f64>,
+    /// only items that can (or cannot) be worn right now
+    pub available: Option<bool>,
+}
+impl Listed for item::Entity {
+    type Entity = Self;
+    const ID: item::Column = item::Column::Id;
+    const SORTABLE: &'static [item::Column] = &[item::Column::CreatedAt, item::Column::Name];
+    const COMPUTED: &'static [&'static str] = &[Loved::SORT];
+    const FILTERABLE: &'static [item::Column] = &[];
+    const DEFAULT_SORT: &'static str = "created_at";
+
+    /// `near` is a non-negative delta E, only along with `color`
+    fn check(params: &[(String, String)], errors: &mut FieldErrors) {
+        let Some((_, near)) = params.iter().find(|(key, _)| key == "near") else { return };
+        let threshold = near.parse::<f64>().ok().filter(|t| t.is_finite() && *t >= 0.0);
+        if threshold.is_none() {
+            let (field, invalid_value) = ("near".into(), near.clone());
+            errors.push("near", ValidateError::CannotValidateField { field, invalid_value });
+        }
+        if !params.iter().any(|(key, _)| key == "color") {
+            errors.push("color", ValidateError::MissingField { missing_field: "color".into() });
+        }
+    }
+}
+/// items with a swatch within `threshold` (CIE76 delta E) of the reference of `color`
+fn near(color: &Color, threshold: f64) -> SimpleExpr {
+    let squared = |column, value: i32| {
+        let difference = || Expr::col(column).sub(value);
+        Expr::expr(difference()).mul(difference())
+    };
+    let [l, a, b] = item_color::Model::lab(&color.reference());
+    let distance = squared(item_color::Column::LabL, l)
+        .add(squared(item_color::Column::LabA, a))
+        .add(squared(item_color::Column::LabB, b));
+    let within = ((threshold * 100.0).powi(2)).round() as i64;
+    let swatches = item_color::Entity::find()
+        .select_only()
+        .column(item_color::Column::ItemId)
+        .filter(Expr::expr(distance).lte(within));
+    item::Column::Id.in_subquery(swatches.into_query())
+}
+/// Items of the closets the user is a member of, the oldest first by default.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    // before the query, whose `near` it checks
+    listing: Listing<item::Entity>,
+    Query(query): Query<ItemQuery>,
+) -> ApiPage<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)));
+    if let Some(closet) = query.closet {
+        select = select.filter(item::Column::ClosetId.eq(closet));
+    }
+    // retired items are listed by `handler::retirement::retired`
+    select = select.filter(item::Column::RetiredOn.is_null());
+    match (query.color, query.near) {
+        (Some(color), Some(threshold)) => select = select.filter(near(&color, threshold)),
+        (Some(color), None) => {
+            let colored = item_color::Entity::find()
+                .select_only()
+                .column(item_color::Column::ItemId)
+                .filter(item_color::Column::Color.eq(color));
+            select = select.filter(item::Column::Id.in_subquery(colored.into_query()));
+        }
+        _ => {}
+    }
+    if let Some(available) = query.available {
+        let (column, clean) = (item::Column::Availability, Availability::Clean);
+        select = select.filter(if available { column.eq(clean) } else { column.ne(clean) });
+    }
+    let loved = Loved(user.user.id.clone());
+    let page = listing.fetch_computed(&state.db, select, &[&loved]).await?;
+    let details = ItemDetail::load(&state.db, page.items.clone()).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let mut details = ItemDetail::load(&state.db, vec![item]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+) -> ApiResult<item::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Editor).await?;
+    item.clone().delete(&state.db).await?;
+    Ok(ApiResponse::Success(item))
+}
+
+/// Store the photo in the request body (png or jpeg) and replace the item palette with its
+/// dominant colors.
+pub async fn upload_image(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    body: Bytes,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Editor).await?;
+
+    let format = match image::guess_format(&body) {
+        Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg)) => format,
+        _ => Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, anyhow::anyhow!("expected png or jpeg")))?,
+    };
+    let decoded = image::load_from_memory_with_format(&body, format)
+        .map_err(|e| (StatusCode::BAD_REQUEST, anyhow::anyhow!(e)))?;
+    let swatches = palette::dominant_colors(&decoded, palette::PALETTE_SIZE);
+    let perceptual_hash = duplicate::perceptual_hash(&decoded);
+
+    let txn = state.db.begin().await?;
+    item_image::ActiveModel {
+        item_id: ActiveValue::Set(item.id.clone()),
+        content_type: ActiveValue::Set(format.to_mime_type().into()),
+        data: ActiveValue::Set(body.to_vec()),
+        perceptual_hash: ActiveValue::Set(Some(perceptual_hash as i64)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    item_color::Entity::delete_many()
+        .filter(item_color::Column::ItemId.eq(item.id.clone()))
+        .exec(&txn)
+        .await?;
+    for swatch in swatches {
+        item_color::ActiveModel {
+            item_id: ActiveValue::Set(item.id.clone()),
+            color: ActiveValue::Set(Color::nearest(&swatch.rgb)),
+            rgb: ActiveValue::Set(swatch.rgb),
+            ratio: ActiveValue::Set(swatch.ratio),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+    let mut details = ItemDetail::load(&txn, vec![item]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+pub async fn image(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, image_id)): Path<(Id<item::Model>, Id<item_image::Model>)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let image = item_image::Entity::find_by_id(image_id)
+        .filter(item_image::Column::ItemId.eq(item.id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    Ok(([(header::CONTENT_TYPE, image.content_type)], image.data))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ItemTransitionCreate")]
+pub struct ItemTransition {
+    pub to: Availability,
+}
+pub async fn transition(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    Json(schema): Json<ItemTransition>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let item = lock_accessible(&txn, &user, id, Role::Editor).await?;
+    if item.is_retired() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", item.name)))?
+    }
+    // lent out follows the loans, see `handler::loan`
+    if item.availability == Availability::LentOut || schema.to == Availability::LentOut {
+        let error = anyhow::anyhow!("lending and returning go through loans");
+        Err((StatusCode::CONFLICT, error))?
+    }
+    let transitioned = transition_item(&txn, item, schema.to).await?;
+    txn.commit().await?;
+    let mut details = ItemDetail::load(&state.db, vec![transitioned]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+impl Listed for item_transition::Entity {
+    type Entity = Self;
+    const ID: item_transition::Column = item_transition::Column::Id;
+    const SORTABLE: &'static [item_transition::Column] = &[item_transition::Column::TransitionedAt];
+    const FILTERABLE: &'static [item_transition::Column] =
+        &[item_transition::Column::From, item_transition::Column::To];
+    const DEFAULT_SORT: &'static str = "transitioned_at";
+}
+pub async fn transitions(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    listing: Listing<item_transition::Entity>,
+) -> ApiPage<item_transition::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let history = item.find_related(item_transition::Entity);
+    Ok(ApiResponse::Success(listing.fetch(&state.db, history).await?))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ItemWearCreate")]
+pub struct ItemWear {
+    /// defaults to today
+    pub worn_on: Option<NaiveDate>,
+}
+pub async fn wear(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    Json(schema): Json<ItemWear>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let worn_on = schema.worn_on.unwrap_or_else(|| user.user.today());
+    let txn = state.db.begin().await?;
+    let item = lock_accessible(&txn, &user, id, Role::Editor).await?;
+    if item.is_retired() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", item.name)))?
+    }
+    let worn = record_wear(&txn, item, worn_on, &user.user.time_zone).await?;
+    txn.commit().await?;
+    let mut details = ItemDetail::load(&state.db, vec![worn]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+impl Listed for item_wear::Entity {
+    type Entity = Self;
+    const ID: item_wear::Column = item_wear::Column::Id;
+    const SORTABLE: &'static [item_wear::Column] =
+        &[item_wear::Column::WornOn, item_wear::Column::CreatedAt];
+    const FILTERABLE: &'static [item_wear::Column] = &[item_wear::Column::WornOn];
+    const DEFAULT_SORT: &'static str = "-worn_on";
+}
+pub async fn wears(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    listing: Listing<item_wear::Entity>,
+) -> ApiPage<item_wear::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let item = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let wears = item.find_related(item_wear::Entity);
+    Ok(ApiResponse::Success(listing.fetch(&state.db, wears).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_item_color_scenario() {
+        use super::*;
+        use hyper::{Body, Method};
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use crate::{
+            call, call_json, configuration::Configuration, request, response::page::Page, signup,
+            with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let create = serde_json::json!({ "name": "navy coat", "brand": "closet", "notes": null });
+        let (status, created) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+        assert_eq!(status, StatusCode::OK);
+        let id = created.result().unwrap().item.id.clone();
+
+        let mut png = Vec::new();
+        let photo = RgbaImage::from_fn(20, 20, |_, y| {
+            if y < 15 {
+                Rgba([30, 40, 70, 255])
+            } else {
+                Rgba([245, 245, 245, 255])
+            }
+        });
+        DynamicImage::ImageRgba8(photo)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let upload = request(Method::POST, &format!("/items/{}/images", id), token)
+            .body(Body::from(png.clone()))
+            .unwrap();
+        let (status, bytes) = call(&api, upload).await;
+        assert_eq!(status, StatusCode::OK);
+        let uploaded: ApiResponse<ItemDetail> = serde_json::from_slice(&bytes).unwrap();
+        let uploaded = uploaded.result().unwrap();
+        let colors: Vec<_> = uploaded.colors.iter().map(|c| (c.color, c.ratio)).collect();
+        assert_eq!(colors, vec![(Color::Navy, 750), (Color::White, 250)]);
+        assert_eq!(uploaded.images.len(), 1);
+
+        let image_uri = format!("/items/{}/images/{}", id, uploaded.images[0]);
+        let (status, bytes) =
+            call(&api, request(Method::GET, &image_uri, token).body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(&bytes[..], &png[..]);
+
+        let listed = |query: &'static str| {
+            let (api, uri) = (&api, format!("/items?{}", query));
+            async move {
+                let (_, page) =
+                    call_json::<Page<ItemDetail>>(api, Method::GET, &uri, token, None).await;
+                page.result().unwrap().items.len()
+            }
+        };
+        assert_eq!(listed("color=navy").await, 1);
+        assert_eq!(listed("color=red").await, 0);
+        assert_eq!(listed("color=black&near=25").await, 1);
+        assert_eq!(listed("color=black&near=5").await, 0);
+        assert_eq!(listed("color=navy&near=15").await, 1);
+        assert_eq!(listed("color=red&near=15").await, 0);
+        for (query, invalid) in [
+            ("near=15", vec!["color"]),
+            ("color=red&near=-1", vec!["near"]),
+            ("color=red&near=NaN", vec!["near"]),
+            ("color=red&near=close", vec!["near"]),
+            ("near=inf&limit=0", vec!["color", "limit", "near"]),
+        ] {
+            let uri = format!("/items?{}", query);
+            let (_, failure) =
+                call_json::<Page<ItemDetail>>(&api, Method::GET, &uri, token, None).await;
+            let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+            assert_eq!(fields.0.keys().collect::<Vec<_>>(), invalid, "{}", query);
+        }
+
+        let other = signup(&api, "other").await;
+        let uri = format!("/items/{}", id);
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::GET, &uri, other.token.as_deref(), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let not_png = request(Method::POST, &format!("/items/{}/images", id), token)
+            .body(Body::from("not an image"))
+            .unwrap();
+        let (status, _) = call(&api, not_png).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_availability_scenario() {
+        use super::*;
+        use hyper::Method;
+
+        use crate::{
+            call_json, configuration::Configuration, response::page::Page, signup, with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let create = serde_json::json!({ "name": "white shirt" });
+        let (_, created) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+        let created = created.result().unwrap();
+        assert_eq!(created.item.availability, Availability::Clean);
+        let uri = format!("/items/{}/transition", created.item.id);
+
+        let worn = serde_json::json!({ "to": "worn" });
+        let (status, transitioned) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(worn)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(transitioned.result().unwrap().item.availability, Availability::Worn);
+
+        let stored = serde_json::json!({ "to": "stored" });
+        let (status, forbidden) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(stored)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(matches!(forbidden.result().unwrap_err(), ApiError::EntityError { .. }));
+
+        let (_, available) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items?available=true", token, None)
+                .await;
+        assert!(available.result().unwrap().items.is_empty());
+        let (_, unavailable) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items?available=false", token, None)
+                .await;
+        assert_eq!(unavailable.result().unwrap().items.len(), 1);
+
+        let uri = format!("/items/{}/transitions", created.item.id);
+        let (_, history) =
+            call_json::<Page<item_transition::Model>>(&api, Method::GET, &uri, token, None).await;
+        let history = &history.result().unwrap().items;
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].from, history[0].to), (Availability::Clean, Availability::Worn));
+
+        let uri = format!("/items/{}/transition", created.item.id);
+        for to in ["in_laundry", "clean"] {
+            let to = serde_json::json!({ "to": to });
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(to)).await;
+        }
+        // a wear from before the wash is only logged, one from today dirties the item again
+        let uri = format!("/items/{}/wear", created.item.id);
+        let yesterday = owner.user.today().pred_opt().unwrap();
+        let backfill = serde_json::json!({ "worn_on": yesterday });
+        let (status, backfilled) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(backfill)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(backfilled.result().unwrap().item.availability, Availability::Clean);
+        let today = serde_json::json!({});
+        let (_, worn) = call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(today)).await;
+        assert_eq!(worn.result().unwrap().item.availability, Availability::Worn);
+        let uri = format!("/items/{}/wears", created.item.id);
+        let (_, wears) =
+            call_json::<Page<item_wear::Model>>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(wears.result().unwrap().items.len(), 2);
+    }
+}
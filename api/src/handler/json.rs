@@ -0,0 +1,123 @@
+use axum::{
+    async_trait,
+    body::{Bytes, HttpBody},
+    extract::FromRequest,
+    BoxError,
+};
+use entity::error::FieldErrors;
+use hyper::{header, HeaderMap, Request, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::response::error::ApiError;
+
+/// key of the errors which are not of a field, e.g. syntax errors
+pub const BODY: &str = "body";
+
+/// `axum::Json` whose rejections are `ApiError::ValidationError` keyed by the path of the field,
+/// so that malformed bodies are reported in the same shape as invalid fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        if !json_content_type(req.headers()) {
+            let expected = "expected request with `Content-Type: application/json`";
+            Err(rejection(StatusCode::UNSUPPORTED_MEDIA_TYPE, BODY.into(), expected))?
+        }
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| rejection(e.status(), BODY.into(), &e.body_text()))?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+        serde_path_to_error::deserialize(deserializer).map(Json).map_err(|e| {
+            let code = match e.inner().classify() {
+                serde_json::error::Category::Data => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            let (path, message) = (e.path().to_string(), e.inner().to_string());
+            // serde_json appends the position, which is meaningless for the field
+            let message = message.rsplit_once(" at line ").map_or(&*message, |(m, _)| m);
+            let missing = message.strip_prefix("missing field `").and_then(|f| f.strip_suffix('`'));
+            let field = match (path.as_str(), missing) {
+                (".", Some(field)) => field.to_string(),
+                (".", None) => BODY.to_string(),
+                (path, Some(field)) => format!("{}.{}", path, field),
+                (path, None) => path.to_string(),
+            };
+            rejection(code, field, message)
+        })
+    }
+}
+
+fn rejection(code: StatusCode, field: String, message: &str) -> ApiError {
+    let mut fields = FieldErrors::default();
+    fields.push(&field, message);
+    ApiError::ValidationError { code, fields }
+}
+
+fn json_content_type(headers: &HeaderMap) -> bool {
+    let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    let media = content_type.and_then(|c| c.split(';').next()).unwrap_or_default().trim();
+    let media = media.to_ascii_lowercase();
+    media == "application/json" || media.starts_with("application/") && media.ends_with("+json")
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::Body;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Form {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    async fn extract(content_type: &str, body: &str) -> Result<Json<Form>, ApiError> {
+        let request = Request::builder().header(header::CONTENT_TYPE, content_type);
+        let request = request.body(Body::from(body.to_string())).unwrap();
+        Json::from_request(request, &()).await
+    }
+    fn fields(error: ApiError) -> (StatusCode, Vec<(String, Vec<String>)>) {
+        match error {
+            ApiError::ValidationError { code, fields } => (code, fields.0.into_iter().collect()),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejection() {
+        let json = "application/json";
+        assert!(extract(json, r#"{"name":"coat","tags":[]}"#).await.is_ok());
+        assert!(extract("application/merge-patch+json", r#"{"name":"","tags":[]}"#).await.is_ok());
+
+        let (code, errors) = fields(extract(json, r#"{"tags":[]}"#).await.unwrap_err());
+        assert_eq!(code, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors, [("name".into(), vec!["missing field `name`".into()])]);
+
+        let (code, errors) =
+            fields(extract(json, r#"{"name":"coat","tags":[1]}"#).await.unwrap_err());
+        assert_eq!(code, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(errors[0].0, "tags[0]");
+        assert_eq!(errors[0].1, ["invalid type: integer `1`, expected a string"]);
+
+        let (code, errors) = fields(extract(json, "not json").await.unwrap_err());
+        assert_eq!((code, errors[0].0.as_str()), (StatusCode::BAD_REQUEST, BODY));
+
+        let (code, errors) = fields(extract("text/plain", "").await.unwrap_err());
+        assert_eq!((code, errors[0].0.as_str()), (StatusCode::UNSUPPORTED_MEDIA_TYPE, BODY));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, Extension, Router};
+use entity::{
+    class::{
+        availability::Availability,
+        care::{Bleach, Process, Professional, TumbleDry},
+    },
+    model::{item, item_color},
+};
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::laundry::{self, Dirty, Method, Shade},
+    AppState,
+};
+
+pub fn laundry_router() -> Router<AppState> {
+    axum::Router::new().route("/plan", axum::routing::get(plan))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LaundryLoad {
+    pub method: Method,
+    pub shade: Option<Shade>,
+    pub temperature: Option<u8>,
+    pub process: Option<Process>,
+    pub professional: Option<Professional>,
+    pub bleach: Bleach,
+    pub dry: TumbleDry,
+    pub items: Vec<item::Model>,
+    pub notes: Vec<String>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LaundryPlan {
+    pub loads: Vec<LaundryLoad>,
+    /// e.g. items which can be neither washed nor dry cleaned, left out of the loads
+    pub notes: Vec<String>,
+}
+/// Worn items of the closets the user is a member of, grouped into compatible wash loads.
+pub async fn plan(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<LaundryPlan> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(item::Column::Availability.eq(Availability::Worn))
+        .filter(item::Column::RetiredOn.is_null())
+        .order_by_asc(item::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+    // ascending, so the most dominant swatch is inserted last
+    let dominant: HashMap<_, _> = item_color::Entity::find()
+        .filter(item_color::Column::ItemId.is_in(items.iter().map(|i| i.id.clone())))
+        .order_by_asc(item_color::Column::Ratio)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|c| (c.item_id.identifier(), c.color))
+        .collect();
+    let dirty: Vec<_> = items
+        .iter()
+        .map(|item| Dirty {
+            id: item.id.clone(),
+            care: item.care,
+            color: dominant.get(&item.id.identifier()).copied(),
+        })
+        .collect();
+    let planned = laundry::plan(&dirty);
+    let loads = planned
+        .loads
+        .into_iter()
+        .map(|load| LaundryLoad {
+            method: load.method,
+            shade: load.shade,
+            temperature: load.temperature,
+            process: load.process,
+            professional: load.professional,
+            bleach: load.bleach,
+            dry: load.dry,
+            items: items.iter().filter(|i| load.items.contains(&i.id)).cloned().collect(),
+            notes: load.notes,
+        })
+        .collect();
+    let notes = items
+        .iter()
+        .filter(|i| planned.unwashable.contains(&i.id))
+        .map(|i| format!("{} can be neither washed nor dry cleaned", i.name))
+        .collect();
+    Ok(ApiResponse::Success(LaundryPlan { loads, notes }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_laundry_plan_scenario() {
+        use hyper::Method as HttpMethod;
+
+        use super::*;
+        use crate::handler::item::ItemDetail;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let labels = [
+            ("towel", "wash_60,tumble_dry"),
+            ("shirt", "wash_40,tumble_dry_low,iron_medium"),
+            ("sweater", "hand_wash,do_not_tumble_dry"),
+            ("suit", "do_not_wash,dry_clean_p"),
+            ("socks", "wash_60"),
+            ("coat", "do_not_wash,dry_clean_f"),
+            ("costume", "do_not_wash,do_not_dry_clean"),
+        ];
+        let mut ids = Vec::new();
+        for (name, care) in labels {
+            let create = serde_json::json!({ "name": name, "care": care });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, HttpMethod::POST, "/items", token, Some(create))
+                    .await;
+            let created = created.result().unwrap().item.clone();
+            assert_eq!(created.care.unwrap().to_string(), care);
+            ids.push(created.id);
+        }
+        // socks stay clean
+        for id in ids.iter().filter(|id| **id != ids[4]) {
+            let uri = format!("/items/{}/wear", id);
+            let wear = serde_json::json!({});
+            call_json::<ItemDetail>(&api, HttpMethod::POST, &uri, token, Some(wear)).await;
+        }
+
+        let (_, planned) =
+            call_json::<LaundryPlan>(&api, HttpMethod::GET, "/laundry/plan", token, None).await;
+        let LaundryPlan { loads, notes } = planned.result().unwrap();
+        assert_eq!(loads.len(), 4);
+        let machine = loads.iter().find(|l| l.method == Method::Machine).unwrap();
+        let names: Vec<_> = machine.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["towel", "shirt"]);
+        assert_eq!((machine.temperature, machine.dry), (Some(40), TumbleDry::Low));
+        assert_eq!(machine.bleach, Bleach::DoNotBleach);
+        assert!(loads.iter().any(|l| l.method == Method::Hand && l.items[0].name == "sweater"));
+        let professional: Vec<_> = loads
+            .iter()
+            .filter(|l| l.method == Method::Professional)
+            .map(|l| (l.professional, l.items[0].name.as_str()))
+            .collect();
+        assert_eq!(
+            professional,
+            vec![(Some(Professional::DryCleanP), "suit"), (Some(Professional::DryCleanF), "coat")]
+        );
+        assert_eq!(notes, &vec!["costume can be neither washed nor dry cleaned".to_string()]);
+    }
+}
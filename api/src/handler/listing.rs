@@ -0,0 +1,482 @@
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use chrono::{DateTime, NaiveDate};
+use entity::error::{EntityError, FieldErrors, ValidateError};
+use hyper::StatusCode;
+use sea_orm::{
+    prelude::Uuid,
+    sea_query::{ColumnType, Expr, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, IdenStatic, ModelTrait, Order,
+    QueryFilter, QueryOrder, QuerySelect, Select, Value,
+};
+
+use crate::response::{error::ApiError, page::Page};
+
+/// Allow-list of a list endpoint, whose entity has a ULID primary key that sorts by creation.
+/// Usually the entity itself, or a marker type for endpoints listing it otherwise.
+pub trait Listed: Send + Sync + 'static {
+    type Entity: EntityTrait;
+    /// the ULID primary key, which also breaks ties of the sort
+    const ID: Column<Self>;
+    /// columns the list can be sorted by, which must not be nullable in the list
+    const SORTABLE: &'static [Column<Self>];
+    /// sorts by keys computed for the request, see [`Computed`]
+    const COMPUTED: &'static [&'static str] = &[];
+    /// columns the list can be filtered by equality with `filter[column]=value`
+    const FILTERABLE: &'static [Column<Self>];
+    /// e.g. `-worn_on`
+    const DEFAULT_SORT: &'static str;
+
+    /// check the other parameters of the endpoint, so that their errors are reported together
+    /// with those of the listing
+    fn check(_params: &[(String, String)], _errors: &mut FieldErrors) {}
+}
+pub type Column<L> = <<L as Listed>::Entity as EntityTrait>::Column;
+pub type Model<L> = <<L as Listed>::Entity as EntityTrait>::Model;
+
+/// Sort by integer keys computed in SQL for the request, e.g. how much the user loves each row.
+#[async_trait::async_trait]
+pub trait Computed<E: EntityTrait>: Send + Sync {
+    /// `sort` to use it for, which must be in [`Listed::COMPUTED`]
+    fn name(&self) -> &'static str;
+    /// the keys, compared in turn
+    fn keys(&self) -> Vec<SimpleExpr>;
+    /// the keys of each model, equal to what [`Computed::keys`] gives for its row
+    async fn values(
+        &self,
+        db: &DatabaseConnection,
+        models: &[E::Model],
+    ) -> Result<Vec<Vec<i64>>, DbErr>;
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Sort<C> {
+    Column(C),
+    Computed(&'static str),
+}
+impl<C: IdenStatic> Sort<C> {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Sort::Column(column) => column.as_str(),
+            Sort::Computed(name) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    After,
+    Before,
+}
+
+/// Where a page starts, exclusively: the row of `id`, and the values it is sorted by, so that
+/// the page does not depend on the row still being there.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cursor {
+    pub direction: Direction,
+    pub id: Uuid,
+    /// the sort keys of the row as text, empty if sorted by id
+    pub keys: Vec<String>,
+}
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = match self.direction {
+            Direction::After => "after",
+            Direction::Before => "before",
+        };
+        write!(f, "{}.{}", direction, self.id)?;
+        if !self.keys.is_empty() {
+            // hex, so that the keys are opaque and need no escaping in a query string
+            let keys = serde_json::to_string(&self.keys).map_err(|_| std::fmt::Error)?;
+            let hex: String = keys.bytes().map(|b| format!("{:02x}", b)).collect();
+            write!(f, ".{}", hex)?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for Cursor {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ValidateError::CannotValidateField {
+            field: "cursor".into(),
+            invalid_value: s.into(),
+        };
+        let mut parts = s.splitn(3, '.');
+        let direction = match parts.next() {
+            Some("after") => Direction::After,
+            Some("before") => Direction::Before,
+            _ => Err(invalid())?,
+        };
+        let id = parts.next().and_then(|id| id.parse::<Uuid>().ok()).ok_or_else(invalid)?;
+        let keys = match parts.next() {
+            Some(hex) => {
+                let bytes = (0..hex.len())
+                    .step_by(2)
+                    .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid)?;
+                serde_json::from_slice(&bytes).map_err(|_| invalid())?
+            }
+            None => Vec::new(),
+        };
+        Ok(Self { direction, id, keys })
+    }
+}
+
+/// `?limit=&cursor=&sort=-column&filter[column]=value` of a list endpoint, validated against
+/// the allow-list. Other parameters are left to other extractors.
+#[derive(Debug, Clone)]
+pub struct Listing<L: Listed> {
+    pub limit: u64,
+    pub cursor: Option<Cursor>,
+    pub sort: Sort<Column<L>>,
+    pub order: Order,
+    pub filters: Vec<(Column<L>, Value)>,
+    /// sort keys of the cursor, typed as the sort
+    pub bounds: Vec<Value>,
+}
+impl<L: Listed> Listing<L> {
+    pub const DEFAULT_LIMIT: u64 = 20;
+    pub const MAX_LIMIT: u64 = 100;
+
+    pub fn parse(params: &[(String, String)]) -> Result<Self, ApiError> {
+        let invalid = |field: &str, invalid_value: &str| {
+            let (field, invalid_value) = (field.into(), invalid_value.into());
+            ValidateError::CannotValidateField { field, invalid_value }
+        };
+        let mut errors = FieldErrors::default();
+        let (mut limit, mut cursor, mut filters) = (Self::DEFAULT_LIMIT, None, Vec::new());
+        let (mut sort, mut order) = Self::sort(L::DEFAULT_SORT).ok_or_else(|| {
+            let error = anyhow::anyhow!("default sort {} is not allowed", L::DEFAULT_SORT);
+            (StatusCode::INTERNAL_SERVER_ERROR, error)
+        })?;
+        for (key, value) in params {
+            match key.as_str() {
+                "limit" => {
+                    let parsed = value.parse().ok().filter(|l| (1..=Self::MAX_LIMIT).contains(l));
+                    limit = errors
+                        .check("limit", parsed.ok_or_else(|| invalid("limit", value)))
+                        .unwrap_or(limit);
+                }
+                "cursor" => cursor = errors.check("cursor", value.parse()),
+                "sort" => {
+                    let parsed = Self::sort(value).ok_or_else(|| invalid("sort", value));
+                    (sort, order) = errors.check("sort", parsed).unwrap_or((sort, order));
+                }
+                key => {
+                    let Some(field) = key.strip_prefix("filter[").and_then(|k| k.strip_suffix(']'))
+                    else {
+                        continue;
+                    };
+                    let Some(column) = L::FILTERABLE.iter().find(|c| c.as_str() == field) else {
+                        errors.push("filter", invalid("filter", field));
+                        continue;
+                    };
+                    let value = Self::value(column.def().get_column_type(), value)
+                        .ok_or_else(|| invalid(key, value));
+                    filters.extend(errors.check(key, value).map(|value| (*column, value)));
+                }
+            }
+        }
+        let bounds = match &cursor {
+            Some(cursor) => Self::bounds(sort, cursor).unwrap_or_else(|| {
+                errors.push("cursor", "cursor does not match the sort");
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        L::check(params, &mut errors);
+        if !errors.is_empty() {
+            Err(errors)?
+        }
+        Ok(Self { limit, cursor, sort, order, filters, bounds })
+    }
+    fn sort(sort: &str) -> Option<(Sort<Column<L>>, Order)> {
+        let (field, order) = match sort.strip_prefix('-') {
+            Some(field) => (field, Order::Desc),
+            None => (sort, Order::Asc),
+        };
+        let mut sortable = L::SORTABLE.iter().copied().chain([L::ID]);
+        let column = sortable.find(|c| c.as_str() == field).map(Sort::Column);
+        let computed = || L::COMPUTED.iter().copied().find(|c| *c == field).map(Sort::Computed);
+        column.or_else(computed).map(|sort| (sort, order))
+    }
+    fn bounds(sort: Sort<Column<L>>, cursor: &Cursor) -> Option<Vec<Value>> {
+        match (sort, cursor.keys.as_slice()) {
+            (Sort::Column(column), []) if column.as_str() == L::ID.as_str() => Some(Vec::new()),
+            (Sort::Column(column), [key]) if column.as_str() != L::ID.as_str() => {
+                Some(vec![Self::value(column.def().get_column_type(), key)?])
+            }
+            (Sort::Column(_), _) => None,
+            (Sort::Computed(_), keys) => {
+                keys.iter().map(|k| k.parse::<i64>().ok().map(Into::into)).collect()
+            }
+        }
+    }
+    fn value(column_type: &ColumnType, value: &str) -> Option<Value> {
+        match column_type {
+            ColumnType::Char(_) | ColumnType::String(_) | ColumnType::Text => Some(value.into()),
+            ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger => value.parse::<i64>().ok().map(Into::into),
+            ColumnType::Boolean => value.parse::<bool>().ok().map(Into::into),
+            ColumnType::Date => value.parse::<NaiveDate>().ok().map(Into::into),
+            ColumnType::TimestampWithTimeZone => {
+                DateTime::parse_from_rfc3339(value).ok().map(Into::into)
+            }
+            ColumnType::Uuid => value.parse::<Uuid>().ok().map(Into::into),
+            _ => None,
+        }
+    }
+    /// text of a sort key in a cursor, as parsed back by [`Listing::value`]
+    fn text(value: Value) -> Option<String> {
+        match value {
+            Value::String(Some(s)) => Some(*s),
+            Value::TinyInt(Some(i)) => Some(i.to_string()),
+            Value::SmallInt(Some(i)) => Some(i.to_string()),
+            Value::Int(Some(i)) => Some(i.to_string()),
+            Value::BigInt(Some(i)) => Some(i.to_string()),
+            Value::Bool(Some(b)) => Some(b.to_string()),
+            Value::ChronoDate(Some(d)) => Some(d.to_string()),
+            Value::ChronoDateTimeWithTimeZone(Some(d)) => Some(d.to_rfc3339()),
+            Value::Uuid(Some(u)) => Some(u.to_string()),
+            _ => None,
+        }
+    }
+
+    /// order in which the rows are fetched, reversed to go back from a `before` cursor
+    fn fetch_order(&self) -> Order {
+        match (self.cursor.as_ref().map(|c| c.direction), &self.order) {
+            (Some(Direction::Before), Order::Asc) => Order::Desc,
+            (Some(Direction::Before), _) => Order::Asc,
+            (_, order) => order.clone(),
+        }
+    }
+    /// rows strictly beyond the cursor in the fetch order, compared key by key then by id
+    fn beyond(&self, keys: &[SimpleExpr], cursor: &Cursor) -> Condition {
+        let ascending = matches!(self.fetch_order(), Order::Asc);
+        let mut keys: Vec<_> = keys.iter().cloned().zip(self.bounds.clone()).collect();
+        keys.push((Expr::col((L::Entity::default(), L::ID)).into(), cursor.id.into()));
+        let mut beyond = Condition::any();
+        for (i, (key, bound)) in keys.iter().enumerate() {
+            let mut condition = Condition::all();
+            for (key, bound) in &keys[..i] {
+                condition = condition.add(Expr::expr(key.clone()).eq(bound.clone()));
+            }
+            beyond = beyond.add(condition.add(match ascending {
+                true => Expr::expr(key.clone()).gt(bound.clone()),
+                false => Expr::expr(key.clone()).lt(bound.clone()),
+            }));
+        }
+        beyond
+    }
+
+    /// The page of the select, sorted by columns.
+    pub async fn fetch(
+        &self,
+        db: &DatabaseConnection,
+        select: Select<L::Entity>,
+    ) -> Result<Page<Model<L>>, ApiError> {
+        self.fetch_computed(db, select, &[]).await
+    }
+
+    /// The page of the select, which can also be sorted by the computed keys.
+    pub async fn fetch_computed(
+        &self,
+        db: &DatabaseConnection,
+        mut select: Select<L::Entity>,
+        computed: &[&dyn Computed<L::Entity>],
+    ) -> Result<Page<Model<L>>, ApiError> {
+        let (keys, computed) = match self.sort {
+            Sort::Column(column) if column.as_str() == L::ID.as_str() => (Vec::new(), None),
+            Sort::Column(column) => (vec![Expr::col((L::Entity::default(), column)).into()], None),
+            Sort::Computed(name) => {
+                let computed = computed.iter().find(|c| c.name() == name).ok_or_else(|| {
+                    let error = anyhow::anyhow!("sort {} is not computed", name);
+                    (StatusCode::INTERNAL_SERVER_ERROR, error)
+                })?;
+                (computed.keys(), Some(*computed))
+            }
+        };
+        for (column, value) in &self.filters {
+            select = select.filter(column.eq(value.clone()));
+        }
+        if let Some(cursor) = &self.cursor {
+            if self.bounds.len() != keys.len() {
+                Err(FieldErrors::of("cursor", "cursor does not match the sort"))?
+            }
+            select = select.filter(self.beyond(&keys, cursor));
+        }
+        let order = self.fetch_order();
+        for key in keys {
+            select = select.order_by(key, order.clone());
+        }
+        // one more row, to know if there is more
+        let select = select.order_by(L::ID, order).limit(self.limit + 1);
+        let mut models = select.all(db).await?;
+
+        let more = models.len() as u64 > self.limit;
+        models.truncate(self.limit as usize);
+        if let Some(Direction::Before) = self.cursor.as_ref().map(|c| c.direction) {
+            models.reverse();
+        }
+        // the first and last models, whose keys the cursors carry
+        let bounds = match models.as_slice() {
+            [first, .., last] => vec![first.clone(), last.clone()],
+            models => models.to_vec(),
+        };
+        let keys: Vec<Vec<String>> = match (self.sort, computed) {
+            (_, Some(computed)) => {
+                let values = computed.values(db, &bounds).await?;
+                values.iter().map(|v| v.iter().map(|k| k.to_string()).collect()).collect()
+            }
+            (Sort::Column(column), _) if column.as_str() != L::ID.as_str() => {
+                bounds.iter().map(|m| Self::text(m.get(column)).into_iter().collect()).collect()
+            }
+            _ => bounds.iter().map(|_| Vec::new()).collect(),
+        };
+        let cursor = |direction, i: usize| {
+            let id = match bounds.get(i)?.get(L::ID) {
+                Value::Uuid(Some(id)) => *id,
+                _ => None?,
+            };
+            Some(Cursor { direction, id, keys: keys.get(i)?.clone() }.to_string())
+        };
+        let after = cursor(Direction::After, bounds.len().max(1) - 1);
+        let before = cursor(Direction::Before, 0);
+        let (next, prev) = match self.cursor.as_ref().map(|c| c.direction) {
+            None => (after.filter(|_| more), None),
+            Some(Direction::After) => (after.filter(|_| more), before),
+            Some(Direction::Before) => (after, before.filter(|_| more)),
+        };
+        Ok(Page { items: models, next, prev })
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Send + Sync, L: Listed> FromRequestParts<S> for Listing<L> {
+    type Rejection = ApiError;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<Vec<(String, String)>>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, anyhow::anyhow!("{}", e)))?;
+        Self::parse(&params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::model::item_wear;
+
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        let id = entity::class::id::Id::<item_wear::Model>::create().identifier();
+        for direction in [Direction::After, Direction::Before] {
+            for keys in [vec![], vec!["st. john's.".to_string()]] {
+                let cursor = Cursor { direction, id, keys };
+                assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+            }
+        }
+        assert!(format!("after.{}.zz", id).parse::<Cursor>().is_err());
+        assert!(format!("around.{}", id).parse::<Cursor>().is_err());
+        assert!("after.".parse::<Cursor>().is_err());
+        assert!(id.to_string().parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let params = |params: &[(&str, &str)]| {
+            let params: Vec<_> =
+                params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            Listing::<item_wear::Entity>::parse(&params)
+        };
+        let default = params(&[("closet", "ignored")]).unwrap();
+        assert_eq!(
+            (default.limit, default.sort.as_str(), default.order),
+            (20, "worn_on", Order::Desc)
+        );
+        let parsed =
+            params(&[("limit", "5"), ("sort", "created_at"), ("filter[worn_on]", "2023-06-01")])
+                .unwrap();
+        assert_eq!(
+            (parsed.limit, parsed.sort.as_str(), parsed.order),
+            (5, "created_at", Order::Asc)
+        );
+        assert_eq!(parsed.filters.len(), 1);
+        assert_eq!(params(&[("sort", "-id")]).unwrap().sort.as_str(), "id");
+        assert!(params(&[("limit", "0")]).is_err());
+        assert!(params(&[("limit", "101")]).is_err());
+        assert!(params(&[("sort", "item_id")]).is_err(), "not in the allow-list");
+        assert!(params(&[("filter[item_id]", "x")]).is_err(), "not in the allow-list");
+        assert!(params(&[("filter[worn_on]", "yesterday")]).is_err());
+        assert!(params(&[("cursor", "after.x")]).is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_listing_scenario() {
+        use hyper::Method;
+
+        use crate::{
+            call_json, configuration::Configuration, handler::item::ItemDetail,
+            response::page::Page, signup, with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let create = serde_json::json!({ "name": "shirt" });
+        let (_, created) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+        let id = created.result().unwrap().item.id.clone();
+        for day in [1, 2, 3, 3, 5] {
+            let wear = serde_json::json!({ "worn_on": format!("2023-06-0{}", day) });
+            let uri = format!("/items/{}/wear", id);
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(wear)).await;
+        }
+
+        let page = |query: String| {
+            let (api, uri) = (&api, format!("/items/{}/wears?{}", id, query));
+            async move {
+                let (status, page) =
+                    call_json::<Page<item_wear::Model>>(api, Method::GET, &uri, token, None).await;
+                (status, page.result().ok().cloned())
+            }
+        };
+        let days = |page: &Page<item_wear::Model>| -> Vec<_> {
+            page.items.iter().map(|w| w.worn_on.format("%d").to_string()).collect()
+        };
+        let (status, first) = page("limit=2".into()).await;
+        assert_eq!(status, StatusCode::OK);
+        let first = first.unwrap();
+        assert_eq!((days(&first), first.prev.as_ref()), (vec!["05".into(), "03".into()], None));
+        let (_, second) = page(format!("limit=2&cursor={}", first.next.as_ref().unwrap())).await;
+        let second = second.unwrap();
+        assert_eq!(days(&second), vec!["03", "02"]);
+        assert_ne!(first.items[1].id, second.items[0].id, "ties are broken by id");
+        let (_, last) = page(format!("limit=2&cursor={}", second.next.as_ref().unwrap())).await;
+        let last = last.unwrap();
+        assert_eq!((days(&last), last.next.as_ref()), (vec!["01".into()], None));
+        let (_, back) = page(format!("limit=2&cursor={}", last.prev.as_ref().unwrap())).await;
+        assert_eq!(back.unwrap().items, second.items);
+        let (_, back) = page(format!("limit=2&cursor={}", second.prev.as_ref().unwrap())).await;
+        let back = back.unwrap();
+        assert_eq!((back.items, back.prev), (first.items.clone(), None));
+
+        let (_, ascending) = page("sort=worn_on&limit=3".into()).await;
+        assert_eq!(days(&ascending.unwrap()), vec!["01", "02", "03"]);
+        let (_, filtered) = page("filter[worn_on]=2023-06-03".into()).await;
+        let filtered = filtered.unwrap();
+        assert_eq!((filtered.items.len(), filtered.next), (2, None));
+        for invalid in ["sort=item_id", "limit=1000", "filter[item_id]=x", "cursor=next"] {
+            let (status, _) = page(invalid.into()).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", invalid);
+        }
+    }
+}
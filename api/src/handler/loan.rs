@@ -0,0 +1,378 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Router,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{availability::Availability, id::Id, role::Role, username::Username},
+    error::{FieldErrors, ValidateError},
+    model::{closet_member, item, loan, user},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::ItemDetail,
+    json::Json,
+    listing::{Listed, Listing},
+    share::PublicItem,
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    AppState,
+};
+
+pub fn loan_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::post(lend))
+        .route("/lent", axum::routing::get(lent))
+        .route("/borrowed", axum::routing::get(borrowed))
+        .route("/:id", axum::routing::get(detail))
+        .route("/:id/return", axum::routing::post(confirm_return))
+}
+
+/// The lent item, in full only for members of its closet.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum LoanItem {
+    Member(item::Model),
+    /// for borrowers outside the closet
+    Public(PublicItem),
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LoanDetail {
+    pub loan: loan::Model,
+    pub item: LoanItem,
+    pub lender: Username,
+    pub borrower: Option<Username>,
+    pub overdue: bool,
+}
+impl LoanDetail {
+    /// as seen by `viewer`, by whose today loans are overdue
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        loans: Vec<loan::Model>,
+        viewer: &user::Model,
+    ) -> Result<Vec<Self>, DbErr> {
+        let items = item::Entity::find()
+            .filter(item::Column::Id.is_in(loans.iter().map(|l| l.item_id.clone())))
+            .all(db)
+            .await?;
+        let member_closets: Vec<_> = closet_member::Entity::find()
+            .filter(closet_member::Column::UserId.eq(viewer.id.clone()))
+            .filter(
+                closet_member::Column::ClosetId.is_in(items.iter().map(|i| i.closet_id.clone())),
+            )
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| m.closet_id)
+            .collect();
+        let (items, others): (Vec<_>, Vec<_>) =
+            items.into_iter().partition(|i| member_closets.contains(&i.closet_id));
+        let items: Vec<_> = items
+            .into_iter()
+            .map(|i| (i.id.clone(), LoanItem::Member(i)))
+            .chain(
+                ItemDetail::load(db, others)
+                    .await?
+                    .into_iter()
+                    .map(|d| (d.item.id.clone(), LoanItem::Public(d.into()))),
+            )
+            .collect();
+        let user_ids = loans.iter().flat_map(|l| [Some(&l.lender_id), l.borrower_id.as_ref()]);
+        let users = user::Entity::find()
+            .filter(user::Column::Id.is_in(user_ids.flatten().cloned()))
+            .all(db)
+            .await?;
+        let username =
+            |id: &Id<user::Model>| users.iter().find(|u| &u.id == id).map(|u| u.username.clone());
+        let today = viewer.today();
+        Ok(loans
+            .into_iter()
+            .filter_map(|loan| {
+                let item = items.iter().find(|(id, _)| id == &loan.item_id)?.1.clone();
+                let lender = username(&loan.lender_id)?;
+                let borrower = loan.borrower_id.as_ref().and_then(username);
+                let overdue = loan.is_overdue(&today);
+                Some(Self { loan, item, lender, borrower, overdue })
+            })
+            .collect())
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct LoanCreate {
+    pub item_id: Id<item::Model>,
+    /// username of a registered borrower
+    pub borrower: Option<String>,
+    /// how to reach a borrower without an account
+    pub contact: Option<String>,
+    pub due_on: Option<NaiveDate>,
+}
+/// Lend a clean item, which stays lent out until the loan is returned.
+pub async fn lend(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<LoanCreate>,
+) -> ApiResult<LoanDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let borrower_id = match schema.borrower {
+        Some(username) => {
+            let borrower =
+                user::Entity::find()
+                    .filter(user::Column::Username.eq(
+                        Username::parse(&username).map_err(|e| FieldErrors::of("borrower", e))?,
+                    ))
+                    .one(&state.db)
+                    .await?;
+            let borrower = borrower.filter(|u| u.is_active).ok_or(ApiError::RecordNotFound)?;
+            if borrower.id == user.user.id {
+                Err((StatusCode::BAD_REQUEST, anyhow::anyhow!("cannot lend to yourself")))?
+            }
+            Some(borrower.id)
+        }
+        None if schema.contact.is_some() => None,
+        None => {
+            let missing =
+                ValidateError::MissingField { missing_field: "borrower or contact".into() };
+            Err(FieldErrors::of("borrower", missing))?
+        }
+    };
+    let txn = state.db.begin().await?;
+    let item = super::item::lock_accessible(&txn, &user, schema.item_id, Role::Editor).await?;
+    if item.is_retired() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", item.name)))?
+    }
+    let item = super::item::transition_item(&txn, item, Availability::LentOut).await?;
+    let created = loan::ActiveModel {
+        item_id: ActiveValue::Set(item.id),
+        lender_id: ActiveValue::Set(user.user.id.clone()),
+        borrower_id: ActiveValue::Set(borrower_id),
+        contact: ActiveValue::Set(schema.contact),
+        due_on: ActiveValue::Set(schema.due_on),
+        returned_at: ActiveValue::Set(None),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    let mut details = LoanDetail::load(&txn, vec![created], &user.user).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LoanQuery {
+    /// include returned loans, otherwise only outstanding ones
+    #[serde(default)]
+    pub all: bool,
+}
+impl Listed for loan::Entity {
+    type Entity = Self;
+    const ID: loan::Column = loan::Column::Id;
+    const SORTABLE: &'static [loan::Column] = &[loan::Column::CreatedAt];
+    const FILTERABLE: &'static [loan::Column] = &[loan::Column::DueOn];
+    const DEFAULT_SORT: &'static str = "created_at";
+}
+/// Loans of items in the closets the user is a member of, the oldest first by default.
+pub async fn lent(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<LoanQuery>,
+    listing: Listing<loan::Entity>,
+) -> ApiPage<LoanDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = loan::Entity::find()
+        .inner_join(item::Entity)
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)));
+    if !query.all {
+        select = select.filter(loan::Column::ReturnedAt.is_null());
+    }
+    let page = listing.fetch(&state.db, select).await?;
+    let details = LoanDetail::load(&state.db, page.items.clone(), &user.user).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+/// Loans to the user, the oldest first by default.
+pub async fn borrowed(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<LoanQuery>,
+    listing: Listing<loan::Entity>,
+) -> ApiPage<LoanDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = loan::Entity::find().filter(loan::Column::BorrowerId.eq(user.user.id.clone()));
+    if !query.all {
+        select = select.filter(loan::Column::ReturnedAt.is_null());
+    }
+    let page = listing.fetch(&state.db, select).await?;
+    let details = LoanDetail::load(&state.db, page.items.clone(), &user.user).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+/// The loan, if the user is its borrower or has at least `required` role in the item's closet.
+async fn find_visible<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<loan::Model>,
+    required: Role,
+) -> Result<(loan::Model, item::Model), ApiError> {
+    let loan = loan::Entity::find_by_id(id).one(db).await?.ok_or(ApiError::RecordNotFound)?;
+    let item = item::Entity::find_by_id(loan.item_id.clone()).one(db).await?;
+    let item = item.ok_or(ApiError::RecordNotFound)?;
+    if required == Role::Viewer && loan.borrower_id.as_ref() == Some(&user.user.id) {
+        return Ok((loan, item));
+    }
+    super::closet::authorize(db, user, &item.closet_id, required).await?;
+    Ok((loan, item))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<loan::Model>>,
+) -> ApiResult<LoanDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let (loan, _) = find_visible(&state.db, &user, id, Role::Viewer).await?;
+    let mut details = LoanDetail::load(&state.db, vec![loan], &user.user).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ReturnConfirm {
+    /// the garment came back worn rather than clean
+    #[serde(default)]
+    pub worn: bool,
+}
+/// Confirm the garment is back, by a closet editor.
+pub async fn confirm_return(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<loan::Model>>,
+    Json(schema): Json<ReturnConfirm>,
+) -> ApiResult<LoanDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let (loan, item) = find_visible(&txn, &user, id, Role::Editor).await?;
+    if !loan.is_open() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("loan is already returned")))?
+    }
+    let to = if schema.worn { Availability::Worn } else { Availability::Clean };
+    super::item::transition_item(&txn, item, to).await?;
+    let mut active = loan.into_active_model();
+    active.returned_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+    let returned = active.update(&txn).await?;
+    let mut details = LoanDetail::load(&txn, vec![returned], &user.user).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_loan_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::handler::item::ItemDetail;
+        use crate::response::page::Page;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let (lender, friend) = (signup(&api, "lender").await, signup(&api, "friend").await);
+        let (lender_token, friend_token) = (lender.token.as_deref(), friend.token.as_deref());
+        let create = serde_json::json!({ "name": "leather jacket" });
+        let (_, jacket) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", lender_token, Some(create)).await;
+        let jacket = jacket.result().unwrap().item.clone();
+
+        let nobody = serde_json::json!({ "item_id": jacket.id });
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::POST, "/loans", lender_token, Some(nobody)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let stranger = serde_json::json!({ "item_id": jacket.id, "borrower": "lender" });
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::POST, "/loans", friend_token, Some(stranger))
+                .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "the jacket is not in a closet of the friend");
+
+        let lend = serde_json::json!({
+            "item_id": jacket.id, "borrower": "friend", "due_on": "2000-01-01"
+        });
+        let (status, lent) =
+            call_json::<LoanDetail>(&api, Method::POST, "/loans", lender_token, Some(lend.clone()))
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        let lent = lent.result().unwrap().clone();
+        let LoanItem::Member(item) = &lent.item else { panic!("{:?}", lent.item) };
+        assert_eq!(item.availability, Availability::LentOut);
+        assert_eq!(lent.borrower.unwrap().to_string(), "friend");
+        assert!(lent.overdue);
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::POST, "/loans", lender_token, Some(lend)).await;
+        assert_eq!(status, StatusCode::CONFLICT, "already lent out");
+
+        let uri = format!("/items/{}/transition", jacket.id);
+        let clean = serde_json::json!({ "to": "clean" });
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, lender_token, Some(clean)).await;
+        assert_eq!(status, StatusCode::CONFLICT, "only returning ends the loan");
+
+        let (_, borrowed) =
+            call_json::<Page<LoanDetail>>(&api, Method::GET, "/loans/borrowed", friend_token, None)
+                .await;
+        let borrowed = &borrowed.result().unwrap().items;
+        assert_eq!(borrowed.len(), 1);
+        let LoanItem::Public(item) = &borrowed[0].item else { panic!("{:?}", borrowed[0].item) };
+        assert_eq!(item.name, "leather jacket", "the friend is not a member of the closet");
+        let uri = format!("/loans/{}", lent.loan.id);
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::GET, &uri, friend_token, None).await;
+        assert_eq!(status, StatusCode::OK, "the borrower can see the loan");
+        let uri = format!("/loans/{}/return", lent.loan.id);
+        let worn = serde_json::json!({ "worn": true });
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::POST, &uri, friend_token, Some(worn.clone()))
+                .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "the lender confirms the return");
+        let (_, returned) =
+            call_json::<LoanDetail>(&api, Method::POST, &uri, lender_token, Some(worn.clone()))
+                .await;
+        let returned = returned.result().unwrap();
+        let LoanItem::Member(item) = &returned.item else { panic!("{:?}", returned.item) };
+        assert_eq!(item.availability, Availability::Worn);
+        assert!(!returned.loan.is_open() && !returned.overdue);
+        let (status, _) =
+            call_json::<LoanDetail>(&api, Method::POST, &uri, lender_token, Some(worn)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (_, outstanding) =
+            call_json::<Page<LoanDetail>>(&api, Method::GET, "/loans/lent", lender_token, None)
+                .await;
+        assert!(outstanding.result().unwrap().items.is_empty());
+        let uri = "/loans/lent?all=true";
+        let (_, all) =
+            call_json::<Page<LoanDetail>>(&api, Method::GET, uri, lender_token, None).await;
+        assert_eq!(all.result().unwrap().items.len(), 1);
+
+        // worn items cannot be lent, but lending to someone without an account works once clean
+        let uri = format!("/items/{}/transition", jacket.id);
+        let clean = serde_json::json!({ "to": "clean" });
+        call_json::<ItemDetail>(&api, Method::POST, &uri, lender_token, Some(clean)).await;
+        let contact = serde_json::json!({ "item_id": jacket.id, "contact": "cousin" });
+        let (_, lent) =
+            call_json::<LoanDetail>(&api, Method::POST, "/loans", lender_token, Some(contact))
+                .await;
+        let lent = lent.result().unwrap();
+        assert_eq!((lent.borrower.clone(), lent.loan.contact.as_deref()), (None, Some("cousin")));
+    }
+}
@@ -1,2 +1,21 @@
+pub mod analytics;
 pub mod auth;
+pub mod bulk;
+pub mod calendar;
+pub mod closet;
+pub mod duplicate;
+pub mod feedback;
 pub mod health;
+pub mod item;
+pub mod json;
+pub mod laundry;
+pub mod listing;
+pub mod loan;
+pub mod outfit;
+pub mod profile;
+pub mod retirement;
+pub mod search;
+pub mod share;
+pub mod storage;
+pub mod trip;
+pub mod wishlist;
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Router,
+};
+use chrono::{Datelike, NaiveDate};
+use entity::{
+    class::{availability::Availability, id::Id, role::Role, season::Season, tag::Tag},
+    error::FieldErrors,
+    model::{closet, item, item_wear, outfit, outfit_item},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, LoaderTrait,
+    ModelTrait, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    feedback::Loved,
+    item::ItemDetail,
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    service::suggestion::{self, Criteria},
+    AppState,
+};
+
+pub fn outfit_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/suggest", axum::routing::get(suggest))
+        .route("/:id", axum::routing::get(detail).delete(delete))
+        .route("/:id/wear", axum::routing::post(super::feedback::wear))
+        .route("/:id/wears", axum::routing::get(super::feedback::wears))
+        .route("/:id/wears/:wear_id", axum::routing::put(super::feedback::rate))
+        .route(
+            "/:id/favorite",
+            axum::routing::put(super::feedback::favorite_outfit)
+                .delete(super::feedback::unfavorite_outfit),
+        )
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutfitDetail {
+    pub outfit: outfit::Model,
+    pub items: Vec<item::Model>,
+}
+impl OutfitDetail {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        outfits: Vec<outfit::Model>,
+    ) -> Result<Vec<Self>, DbErr> {
+        if outfits.is_empty() {
+            return Ok(Vec::new());
+        }
+        let items = outfits.load_many_to_many(item::Entity, outfit_item::Entity, db).await?;
+        Ok(outfits.into_iter().zip(items).map(|(outfit, items)| Self { outfit, items }).collect())
+    }
+}
+
+/// The outfit, if the user has at least `required` role in its closet.
+pub(crate) async fn find_accessible<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<outfit::Model>,
+    required: Role,
+) -> Result<outfit::Model, ApiError> {
+    let outfit = outfit::Entity::find_by_id(id).one(db).await?.ok_or(ApiError::RecordNotFound)?;
+    super::closet::authorize(db, user, &outfit.closet_id, required).await?;
+    Ok(outfit)
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct OutfitCreate {
+    pub name: String,
+    /// defaults to the personal closet, and all items must belong to it
+    pub closet_id: Option<Id<closet::Model>>,
+    pub occasion: Option<Tag>,
+    pub items: Vec<Id<item::Model>>,
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<OutfitCreate>,
+) -> ApiResult<OutfitDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let OutfitCreate { name, closet_id, occasion, mut items } = schema;
+    items.sort_by_key(|id| id.identifier());
+    items.dedup();
+    let txn = state.db.begin().await?;
+    let closet_id = super::closet::writable_closet(&txn, &user, closet_id).await?;
+    let owned = item::Entity::find()
+        .filter(item::Column::ClosetId.eq(closet_id.clone()))
+        .filter(item::Column::Id.is_in(items.clone()))
+        .all(&txn)
+        .await?;
+    if owned.len() != items.len() {
+        Err(ApiError::RecordNotFound)?
+    }
+    if let Some(retired) = owned.iter().find(|i| i.is_retired()) {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", retired.name)))?
+    }
+    let created = outfit::ActiveModel {
+        owner_id: ActiveValue::Set(user.user.id),
+        closet_id: ActiveValue::Set(closet_id),
+        name: ActiveValue::Set(name),
+        occasion: ActiveValue::Set(occasion),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    for item_id in items {
+        let outfit_id = ActiveValue::Set(created.id.clone());
+        outfit_item::ActiveModel {
+            outfit_id,
+            item_id: ActiveValue::Set(item_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+    let mut details = OutfitDetail::load(&txn, vec![created]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+impl Listed for outfit::Entity {
+    type Entity = Self;
+    const ID: outfit::Column = outfit::Column::Id;
+    const SORTABLE: &'static [outfit::Column] = &[outfit::Column::CreatedAt, outfit::Column::Name];
+    const COMPUTED: &'static [&'static str] = &[Loved::SORT];
+    const FILTERABLE: &'static [outfit::Column] = &[];
+    const DEFAULT_SORT: &'static str = "created_at";
+}
+/// Outfits of the closets the user is a member of, the oldest first by default.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    listing: Listing<outfit::Entity>,
+) -> ApiPage<OutfitDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let select = outfit::Entity::find()
+        .filter(outfit::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)));
+    let loved = Loved(user.user.id.clone());
+    let page = listing.fetch_computed(&state.db, select, &[&loved]).await?;
+    let details = OutfitDetail::load(&state.db, page.items.clone()).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+) -> ApiResult<OutfitDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let outfit = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let mut details = OutfitDetail::load(&state.db, vec![outfit]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<outfit::Model>>,
+) -> ApiResult<outfit::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let outfit = find_accessible(&state.db, &user, id, Role::Editor).await?;
+    outfit.clone().delete(&state.db).await?;
+    Ok(ApiResponse::Success(outfit))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SuggestQuery {
+    pub occasion: Option<Tag>,
+    /// day to dress for, defaults to today
+    pub date: Option<NaiveDate>,
+    /// defaults to the season of the date
+    pub season: Option<Season>,
+    /// defaults to the date, so suggestions change day by day
+    pub seed: Option<u64>,
+    /// up to [`suggestion::MAX_LIMIT`]
+    pub limit: Option<usize>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutfitSuggestion {
+    pub items: Vec<item::Model>,
+    pub score: u32,
+    pub explanations: Vec<String>,
+}
+pub async fn suggest(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<SuggestQuery>,
+) -> ApiResult<Vec<OutfitSuggestion>> {
+    const DEFAULT_LIMIT: usize = 3;
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=suggestion::MAX_LIMIT).contains(&limit) {
+        let error = format!("{} is not between 1 and {}", limit, suggestion::MAX_LIMIT);
+        Err(FieldErrors::of("limit", error))?
+    }
+    let today = query.date.unwrap_or_else(|| user.user.today());
+    let forecast = match &user.user.home {
+        // suggestions still work without weather, so provider failures are not fatal
+        Some(home) => state
+            .weather
+            .forecast(home, today)
+            .await
+            .map_err(|e| tracing::warn!("cannot get forecast: {:?}", e))
+            .ok(),
+        None => None,
+    };
+    let criteria = Criteria {
+        occasion: query.occasion,
+        season: query.season.unwrap_or_else(|| Season::of(&today, user.user.hemisphere())),
+        today,
+        seed: query.seed.unwrap_or(today.num_days_from_ce() as u64),
+        limit,
+        forecast,
+    };
+
+    let items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(item::Column::Availability.eq(Availability::Clean))
+        .filter(item::Column::Category.is_not_null())
+        .filter(item::Column::RetiredOn.is_null())
+        .all(&state.db)
+        .await?;
+    let recent = criteria.today - chrono::Duration::days(suggestion::RECENT_DAYS);
+    let wears = item_wear::Entity::find()
+        .filter(item_wear::Column::ItemId.is_in(items.iter().map(|i| i.id.clone())))
+        .filter(item_wear::Column::WornOn.gte(recent))
+        .all(&state.db)
+        .await?;
+    // outfits planned for past days count as worn even before they are settled
+    let planned =
+        super::calendar::unsettled_wears(&state.db, &user.user.id, recent, user.user.today())
+            .await?;
+    let wears = wears.into_iter().map(|w| (w.item_id, w.worn_on)).chain(planned);
+    let mut last_worn = HashMap::new();
+    for (item_id, worn_on) in wears {
+        let worn = last_worn.entry(item_id.identifier()).or_insert(worn_on);
+        *worn = worn_on.max(*worn);
+    }
+    let details = ItemDetail::load(&state.db, items).await?;
+    let garments: Vec<_> = details
+        .iter()
+        .filter_map(|d| d.garment(last_worn.get(&d.item.id.identifier()).copied()))
+        .collect();
+
+    let suggestions = suggestion::suggest(&garments, &criteria)
+        .into_iter()
+        .map(|s| OutfitSuggestion {
+            items: s
+                .items
+                .iter()
+                .filter_map(|id| details.iter().find(|d| &d.item.id == id))
+                .map(|d| d.item.clone())
+                .collect(),
+            score: s.score,
+            explanations: s.explanations,
+        })
+        .collect();
+    Ok(ApiResponse::Success(suggestions))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_outfit_suggest_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::{
+            call_json, configuration::Configuration, response::page::Page, signup, with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let mut ids = Vec::new();
+        for (name, category) in [("shirt", "top"), ("chinos", "bottom"), ("sneakers", "shoes")] {
+            let create =
+                serde_json::json!({ "name": name, "category": category, "tags": ["Casual"] });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+
+        let create = serde_json::json!({ "name": "weekend", "occasion": "casual", "items": ids });
+        let (status, outfit) =
+            call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(create)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(outfit.result().unwrap().items.len(), 3);
+        let (_, outfits) =
+            call_json::<Page<OutfitDetail>>(&api, Method::GET, "/outfits", token, None).await;
+        assert_eq!(outfits.result().unwrap().items, vec![outfit.result().unwrap().clone()]);
+
+        let uri = "/outfits/suggest?occasion=casual&season=summer&seed=1";
+        let (status, suggested) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let suggested = suggested.result().unwrap();
+        assert_eq!(suggested.len(), 1);
+        let suggested_ids: Vec<_> = suggested[0].items.iter().map(|i| i.id.clone()).collect();
+        assert_eq!(suggested_ids.len(), 3);
+        assert!(ids.iter().all(|id| suggested_ids.contains(id)));
+        let (_, again) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(again.result().unwrap(), suggested);
+        for limit in [0, 1000] {
+            let uri = format!("/outfits/suggest?limit={}", limit);
+            let (status, _) =
+                call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, &uri, token, None).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", limit);
+        }
+
+        let winter = "/outfits/suggest?occasion=casual&season=winter";
+        let (_, no_coat) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, winter, token, None).await;
+        assert!(no_coat.result().unwrap().is_empty());
+
+        let uri = format!("/items/{}/wear", ids[0]);
+        let (_, worn) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(serde_json::json!({})))
+                .await;
+        assert_eq!(worn.result().unwrap().item.availability, Availability::Worn);
+        let uri = "/outfits/suggest?occasion=casual&season=summer";
+        let (_, none) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, uri, token, None).await;
+        assert!(none.result().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_weather_suggest_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, service::weather::Forecast, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let closet = [
+            ("shirt", "top", 1, false),
+            ("jeans", "bottom", 2, false),
+            ("boots", "shoes", 2, true),
+            ("parka", "outerwear", 5, true),
+        ];
+        for (name, category, warmth, waterproof) in closet {
+            let create = serde_json::json!({
+                "name": name, "category": category, "warmth": warmth, "waterproof": waterproof
+            });
+            let (status, _) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let invalid = serde_json::json!({ "name": "sauna suit", "warmth": 9 });
+        let (status, failure) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(invalid)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else {
+            panic!("{:?}", failure)
+        };
+        assert_eq!(fields.0.keys().collect::<Vec<_>>(), ["warmth"]);
+
+        let uri = "/profile/forecast?date=2024-01-10";
+        let (status, _) = call_json::<Forecast>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(status, StatusCode::CONFLICT, "home location is not set yet");
+        // summer without forecast, no outerwear needed
+        let uri = "/outfits/suggest?date=2024-01-10&season=summer";
+        let (_, suggested) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(suggested.result().unwrap()[0].items.len(), 3);
+
+        let home = serde_json::json!({ "home": "35.6812,139.7671" });
+        let (status, user) = call_json::<entity::model::user::Model>(
+            &api,
+            Method::PUT,
+            "/profile/home",
+            token,
+            Some(home),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(user.result().unwrap().home.unwrap().to_string(), "35.681200,139.767100");
+        let (status, forecast) = call_json::<Forecast>(
+            &api,
+            Method::GET,
+            "/profile/forecast?date=2024-01-10",
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(forecast.result().unwrap().is_rainy());
+
+        // the forecast is freezing, so the parka is needed regardless of the season
+        let (_, suggested) =
+            call_json::<Vec<OutfitSuggestion>>(&api, Method::GET, uri, token, None).await;
+        let suggested = &suggested.result().unwrap()[0];
+        let names: Vec<_> = suggested.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["shirt", "jeans", "boots", "parka"]);
+        assert!(suggested.explanations.iter().any(|e| e.starts_with("forecast is")));
+        assert!(suggested.explanations.contains(&"parka is waterproof".to_string()));
+    }
+}
@@ -0,0 +1,371 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Router,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        length_unit::LengthUnit, locale::Locale, location::Location, time_zone::TimeZone,
+        unit_system::UnitSystem, week_start::WeekStart,
+    },
+    model::{item, measurement, user},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    TryIntoModel,
+};
+use serde::{Deserialize, Serialize};
+
+use super::json::Json;
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::{
+        sizing::{self, BrandSize, Fitted},
+        weather::Forecast,
+    },
+    AppState,
+};
+
+pub fn profile_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(profile))
+        .route("/home", axum::routing::put(update_home))
+        .route("/preferences", axum::routing::get(preferences).put(update_preferences))
+        .route("/forecast", axum::routing::get(forecast))
+        .route("/measurements", axum::routing::get(measurements).put(update_measurements))
+        .route("/sizes", axum::routing::get(sizes))
+}
+
+pub async fn profile(Extension(user): Extension<Option<AuthUser>>) -> ApiResult<user::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    Ok(ApiResponse::Success(user.user))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct HomeUpdate {
+    /// `latitude,longitude`, or null to forget the home location
+    pub home: Option<Location>,
+}
+pub async fn update_home(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<HomeUpdate>,
+) -> ApiResult<user::Model> {
+    let mut active = user.ok_or(ApiError::LoginRequiredError)?.into_active_model();
+    active.home = ActiveValue::Set(schema.home);
+    let updated = active.update(&state.db).await?;
+    Ok(ApiResponse::Success(updated.unauthenticated()))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Preferences {
+    pub time_zone: TimeZone,
+    pub locale: Locale,
+    pub unit_system: UnitSystem,
+    pub week_start: WeekStart,
+}
+impl From<user::Model> for Preferences {
+    fn from(user: user::Model) -> Self {
+        let user::Model { time_zone, locale, unit_system, week_start, .. } = user;
+        Self { time_zone, locale, unit_system, week_start }
+    }
+}
+pub async fn preferences(Extension(user): Extension<Option<AuthUser>>) -> ApiResult<Preferences> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    Ok(ApiResponse::Success(user.user.into()))
+}
+
+/// Only the given preferences are changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PreferencesUpdate {
+    /// IANA time zone such as `Asia/Tokyo`
+    pub time_zone: Option<TimeZone>,
+    /// BCP 47 language tag such as `en-GB`
+    pub locale: Option<Locale>,
+    pub unit_system: Option<UnitSystem>,
+    pub week_start: Option<WeekStart>,
+}
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<PreferencesUpdate>,
+) -> ApiResult<Preferences> {
+    let mut active = user.ok_or(ApiError::LoginRequiredError)?.into_active_model();
+    if let Some(time_zone) = schema.time_zone {
+        active.time_zone = ActiveValue::Set(time_zone);
+    }
+    if let Some(locale) = schema.locale {
+        active.locale = ActiveValue::Set(locale);
+    }
+    if let Some(unit_system) = schema.unit_system {
+        active.unit_system = ActiveValue::Set(unit_system);
+    }
+    if let Some(week_start) = schema.week_start {
+        active.week_start = ActiveValue::Set(week_start);
+    }
+    let updated = active.update(&state.db).await?;
+    Ok(ApiResponse::Success(updated.into()))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ForecastQuery {
+    /// defaults to today in the time zone of the user
+    pub date: Option<NaiveDate>,
+}
+pub async fn forecast(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<ForecastQuery>,
+) -> ApiResult<Forecast> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let home = user
+        .user
+        .home
+        .ok_or_else(|| (StatusCode::CONFLICT, anyhow::anyhow!("home location is not set")))?;
+    let date = query.date.unwrap_or_else(|| user.user.today());
+    let forecast = state.weather.forecast(&home, date).await;
+    Ok(ApiResponse::Success(forecast.map_err(|e| (StatusCode::BAD_GATEWAY, e))?))
+}
+
+/// Body measurements in `unit`, null if unknown.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Measurements {
+    #[serde(default)]
+    pub unit: LengthUnit,
+    pub height: Option<f64>,
+    pub chest: Option<f64>,
+    pub waist: Option<f64>,
+    pub hips: Option<f64>,
+    pub inseam: Option<f64>,
+    pub foot: Option<f64>,
+}
+impl Measurements {
+    pub fn new(measurement: &measurement::Model, unit: LengthUnit) -> Self {
+        let length = |mm: Option<i32>| mm.map(|mm| unit.from_millimetres(mm));
+        Self {
+            unit,
+            height: length(measurement.height),
+            chest: length(measurement.chest),
+            waist: length(measurement.waist),
+            hips: length(measurement.hips),
+            inseam: length(measurement.inseam),
+            foot: length(measurement.foot),
+        }
+    }
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MeasurementQuery {
+    /// defaults to the unit the measurements were entered in, or that of the unit system
+    pub unit: Option<LengthUnit>,
+}
+pub async fn measurements(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<MeasurementQuery>,
+) -> ApiResult<Measurements> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let measurement = measurement::Entity::find()
+        .filter(measurement::Column::UserId.eq(user.user.id))
+        .one(&state.db)
+        .await?;
+    let measurements = match measurement {
+        Some(m) => Measurements::new(&m, query.unit.unwrap_or(m.unit)),
+        None => {
+            let unit = query.unit.unwrap_or_else(|| user.user.unit_system.length_unit());
+            Measurements { unit, ..Default::default() }
+        }
+    };
+    Ok(ApiResponse::Success(measurements))
+}
+
+/// Replace the measurements, entered in `unit`.
+pub async fn update_measurements(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<Measurements>,
+) -> ApiResult<Measurements> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let unit = schema.unit;
+    let length = |length: Option<f64>| length.map(|l| unit.to_millimetres(l)).transpose();
+    let existing = measurement::Entity::find()
+        .filter(measurement::Column::UserId.eq(user.user.id.clone()))
+        .one(&state.db)
+        .await?;
+    let mut active = match existing {
+        Some(existing) => existing.into_active_model(),
+        None => measurement::ActiveModel {
+            user_id: ActiveValue::Set(user.user.id),
+            ..Default::default()
+        },
+    };
+    active.unit = ActiveValue::Set(unit);
+    active.height = ActiveValue::Set(length(schema.height)?);
+    active.chest = ActiveValue::Set(length(schema.chest)?);
+    active.waist = ActiveValue::Set(length(schema.waist)?);
+    active.hips = ActiveValue::Set(length(schema.hips)?);
+    active.inseam = ActiveValue::Set(length(schema.inseam)?);
+    active.foot = ActiveValue::Set(length(schema.foot)?);
+    let saved = active.save(&state.db).await?.try_into_model()?;
+    Ok(ApiResponse::Success(Measurements::new(&saved, unit)))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SizeQuery {
+    /// only this brand, ignoring case
+    pub brand: Option<String>,
+}
+/// Sizes per brand learned from the fit of items the user added, retired ones included.
+pub async fn sizes(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<SizeQuery>,
+) -> ApiResult<Vec<BrandSize>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let items = item::Entity::find()
+        .filter(item::Column::OwnerId.eq(user.user.id))
+        .filter(item::Column::Brand.is_not_null())
+        .filter(item::Column::Size.is_not_null())
+        .filter(item::Column::Fit.is_not_null())
+        .all(&state.db)
+        .await?;
+    let fitted: Vec<_> = items
+        .into_iter()
+        .filter_map(|item| {
+            Some(Fitted {
+                brand: item.brand?,
+                category: item.category,
+                size: item.size?,
+                fit: item.fit?,
+            })
+        })
+        .collect();
+    let mut mappings = sizing::mappings(&fitted);
+    if let Some(brand) = query.brand {
+        mappings.retain(|m| m.brand.to_lowercase() == brand.trim().to_lowercase());
+    }
+    Ok(ApiResponse::Success(mappings))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_size_profile_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::handler::item::ItemDetail;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let uri = "/profile/measurements";
+        let (_, empty) = call_json::<Measurements>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(empty.result().unwrap(), &Measurements::default());
+        let inches = serde_json::json!({ "unit": "inch", "waist": 32, "inseam": 30.5 });
+        let (_, saved) =
+            call_json::<Measurements>(&api, Method::PUT, uri, token, Some(inches)).await;
+        let saved = saved.result().unwrap();
+        assert_eq!((saved.unit, saved.waist, saved.chest), (LengthUnit::Inch, Some(32.0), None));
+        let uri = "/profile/measurements?unit=cm";
+        let (_, cm) = call_json::<Measurements>(&api, Method::GET, uri, token, None).await;
+        let cm = cm.result().unwrap();
+        assert_eq!((cm.waist, cm.inseam), (Some(81.3), Some(77.5)));
+        let negative = serde_json::json!({ "unit": "cm", "waist": -1 });
+        let (status, _) =
+            call_json::<Measurements>(&api, Method::PUT, uri, token, Some(negative)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        for (brand, size, fit) in
+            [("Acme", "m", "fits_well"), ("ACME", "L", "too_large"), ("Zed", "L", "fits_well")]
+        {
+            let create = serde_json::json!({
+                "name": "shirt", "category": "top", "brand": brand, "size": size, "fit": fit
+            });
+            let (status, _) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let invalid = serde_json::json!({ "name": "shoes", "size": "EU 42.3" });
+        let request = crate::request(Method::POST, "/items", token)
+            .header(hyper::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+            .body(hyper::Body::from(invalid.to_string()))
+            .unwrap();
+        let (status, _) = crate::call(&api, request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (_, sizes) =
+            call_json::<Vec<BrandSize>>(&api, Method::GET, "/profile/sizes", token, None).await;
+        let sizes = sizes.result().unwrap();
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].brand, "Acme");
+        assert_eq!(sizes[0].recommended.unwrap().to_string(), "M");
+        assert_eq!(sizes[0].too_large.len(), 1);
+        let uri = "/profile/sizes?brand=zed";
+        let (_, zed) = call_json::<Vec<BrandSize>>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(zed.result().unwrap()[0].brand, "Zed");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_preferences_scenario() {
+        use chrono::Offset;
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::{call_json, configuration::Configuration, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+
+        let uri = "/profile/preferences";
+        let (_, defaults) = call_json::<Preferences>(&api, Method::GET, uri, token, None).await;
+        let defaults = defaults.result().unwrap();
+        assert_eq!(defaults.time_zone.to_string(), "UTC");
+        assert_eq!(defaults.locale.to_string(), "en-US");
+        assert_eq!(
+            (defaults.unit_system, defaults.week_start),
+            (UnitSystem::Metric, WeekStart::Monday)
+        );
+
+        let update = serde_json::json!({ "time_zone": "Asia/Tokyo", "unit_system": "imperial" });
+        let (status, updated) =
+            call_json::<Preferences>(&api, Method::PUT, uri, token, Some(update)).await;
+        assert_eq!(status, StatusCode::OK);
+        let updated = updated.result().unwrap();
+        assert_eq!(updated.time_zone.to_string(), "Asia/Tokyo");
+        assert_eq!(updated.locale, defaults.locale, "not given, so unchanged");
+        let (_, profile) =
+            call_json::<user::Model>(&api, Method::GET, "/profile", token, None).await;
+        let profile = profile.result().unwrap();
+        assert_eq!(profile.unit_system, UnitSystem::Imperial);
+        assert_eq!(profile.updated_at.offset().fix().local_minus_utc(), 0, "stored in UTC");
+
+        let (_, empty) =
+            call_json::<Measurements>(&api, Method::GET, "/profile/measurements", token, None)
+                .await;
+        assert_eq!(empty.result().unwrap().unit, LengthUnit::Inch);
+
+        for invalid in [
+            serde_json::json!({ "time_zone": "Asia/Osaka" }),
+            serde_json::json!({ "locale": "english" }),
+            serde_json::json!({ "week_start": "friday" }),
+        ] {
+            let request = crate::request(Method::PUT, uri, token)
+                .header(hyper::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(hyper::Body::from(invalid.to_string()))
+                .unwrap();
+            let (status, _) = crate::call(&api, request).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        availability::Availability, id::Id, money::Money, retire_reason::RetireReason, role::Role,
+    },
+    error::FieldErrors,
+    model::{item, item_color, item_wear},
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::{lock_accessible, ItemDetail},
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    service::declutter::{self, Kept},
+    AppState,
+};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ItemRetire {
+    pub reason: RetireReason,
+    /// defaults to today
+    pub retired_on: Option<NaiveDate>,
+    /// only for sold items
+    pub sale_price: Option<Money>,
+}
+/// Take the item out of the active closet, keeping it for statistics.
+pub async fn retire(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+    Json(schema): Json<ItemRetire>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    if schema.sale_price.is_some() && schema.reason != RetireReason::Sold {
+        let error = format!("only sold items have a sale price, not {} ones", schema.reason);
+        Err(FieldErrors::of("sale_price", error))?
+    }
+    let sale_price = super::item::validate_price("sale_price", schema.sale_price)?;
+    let txn = state.db.begin().await?;
+    let item = lock_accessible(&txn, &user, id, Role::Editor).await?;
+    if item.is_retired() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is already retired", item.name)))?
+    }
+    if item.availability == Availability::LentOut {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is lent out", item.name)))?
+    }
+    let retired_on = schema.retired_on.unwrap_or_else(|| user.user.today());
+    let mut active = item.into_active_model();
+    active.retired_on = ActiveValue::Set(Some(retired_on));
+    active.retire_reason = ActiveValue::Set(Some(schema.reason));
+    active.set_sale_price(sale_price);
+    let retired = active.update(&txn).await?;
+    txn.commit().await?;
+    let mut details = ItemDetail::load(&state.db, vec![retired]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+/// Bring a retired item back, e.g. if it was found again.
+pub async fn restore(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<item::Model>>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let item = lock_accessible(&txn, &user, id, Role::Editor).await?;
+    if !item.is_retired() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is not retired", item.name)))?
+    }
+    let mut active = item.into_active_model();
+    active.retired_on = ActiveValue::Set(None);
+    active.retire_reason = ActiveValue::Set(None);
+    active.set_sale_price(None);
+    let restored = active.update(&txn).await?;
+    txn.commit().await?;
+    let mut details = ItemDetail::load(&state.db, vec![restored]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RetiredQuery {
+    pub reason: Option<RetireReason>,
+}
+/// Retired items, which unlike active ones can be sorted by when they were retired.
+pub struct Retired;
+impl Listed for Retired {
+    type Entity = item::Entity;
+    const ID: item::Column = item::Column::Id;
+    const SORTABLE: &'static [item::Column] =
+        &[item::Column::RetiredOn, item::Column::Name, item::Column::CreatedAt];
+    const FILTERABLE: &'static [item::Column] = &[item::Column::RetiredOn];
+    const DEFAULT_SORT: &'static str = "-retired_on";
+}
+/// Retired items of the closets the user is a member of, most recent first by default.
+pub async fn retired(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<RetiredQuery>,
+    listing: Listing<Retired>,
+) -> ApiPage<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(item::Column::RetiredOn.is_not_null());
+    if let Some(reason) = query.reason {
+        select = select.filter(item::Column::RetireReason.eq(reason));
+    }
+    let page = listing.fetch(&state.db, select).await?;
+    let details = ItemDetail::load(&state.db, page.items.clone()).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DeclutterQuery {
+    pub limit: Option<usize>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeclutterCandidate {
+    pub item: item::Model,
+    pub idle_days: i64,
+    pub duplicates: usize,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+/// Active items worth letting go, the longest idle and most duplicated first.
+pub async fn declutter(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<DeclutterQuery>,
+) -> ApiResult<Vec<DeclutterCandidate>> {
+    const DEFAULT_LIMIT: usize = 10;
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(item::Column::RetiredOn.is_null())
+        .all(&state.db)
+        .await?;
+    let wears = item_wear::Entity::find()
+        .filter(item_wear::Column::ItemId.is_in(items.iter().map(|i| i.id.clone())))
+        .all(&state.db)
+        .await?;
+    let mut last_worn = HashMap::new();
+    for wear in wears {
+        let worn = last_worn.entry(wear.item_id.identifier()).or_insert(wear.worn_on);
+        *worn = wear.worn_on.max(*worn);
+    }
+    // ascending, so the most dominant swatch is inserted last
+    let dominant: HashMap<_, _> = item_color::Entity::find()
+        .filter(item_color::Column::ItemId.is_in(items.iter().map(|i| i.id.clone())))
+        .order_by_asc(item_color::Column::Ratio)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|c| (c.item_id.identifier(), c.color))
+        .collect();
+
+    let kept: Vec<_> = items
+        .iter()
+        .map(|item| Kept {
+            id: item.id.clone(),
+            name: item.name.clone(),
+            category: item.category,
+            color: dominant.get(&item.id.identifier()).copied(),
+            added_on: user.user.time_zone.date_of(&item.created_at),
+            last_worn: last_worn.get(&item.id.identifier()).copied(),
+        })
+        .collect();
+    let today = user.user.today();
+    let candidates = declutter::rank(&kept, &today)
+        .into_iter()
+        .take(query.limit.unwrap_or(DEFAULT_LIMIT))
+        .filter_map(|c| {
+            let item = items.iter().find(|i| i.id == c.id)?.clone();
+            let (idle_days, duplicates, score, reasons) =
+                (c.idle_days, c.duplicates, c.score, c.reasons);
+            Some(DeclutterCandidate { item, idle_days, duplicates, score, reasons })
+        })
+        .collect();
+    Ok(ApiResponse::Success(candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_retirement_scenario() {
+        use entity::model::storage;
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::handler::outfit::OutfitDetail;
+        use crate::response::page::Page;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut ids = Vec::new();
+        for (name, worn_on) in [("old sweater", "2020-01-01"), ("tee", "2023-06-01"), ("cap", "")] {
+            let create = serde_json::json!({ "name": name, "category": "top" });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            let id = created.result().unwrap().item.id.clone();
+            if !worn_on.is_empty() {
+                let uri = format!("/items/{}/wear", id);
+                let wear = serde_json::json!({ "worn_on": worn_on });
+                call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(wear)).await;
+            }
+            ids.push(id);
+        }
+
+        let (_, candidates) = call_json::<Vec<DeclutterCandidate>>(
+            &api,
+            Method::GET,
+            "/items/declutter",
+            token,
+            None,
+        )
+        .await;
+        let candidates = candidates.result().unwrap();
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].item.id, ids[0], "idle the longest");
+        assert!(candidates[0].reasons[0].starts_with("not worn for"));
+        assert_eq!(candidates[2].item.id, ids[2], "just added");
+
+        let outfit = serde_json::json!({ "name": "weekend", "items": [ids[0], ids[1]] });
+        let (_, outfit) =
+            call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(outfit)).await;
+        let outfit_id = outfit.result().unwrap().outfit.id.clone();
+
+        let uri = format!("/items/{}/retire", ids[0]);
+        let donated = serde_json::json!({ "reason": "donated", "sale_price": "10.00 USD" });
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(donated)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "only sold items have a sale price");
+        let refunded = serde_json::json!({ "reason": "sold", "sale_price": "-10.00 USD" });
+        let (_, failure) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(refunded)).await;
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+        assert!(fields.0.contains_key("sale_price"), "{:?}", fields);
+        let sold = serde_json::json!({
+            "reason": "sold", "retired_on": "2023-07-01", "sale_price": "1500 JPY"
+        });
+        let (status, retired) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(sold.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        let retired = retired.result().unwrap();
+        assert_eq!(retired.item.retire_reason, Some(RetireReason::Sold));
+        assert_eq!(retired.item.sale_price().unwrap().to_string(), "1500 JPY");
+        let (status, _) =
+            call_json::<ItemDetail>(&api, Method::POST, &uri, token, Some(sold)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let boxed = serde_json::json!({ "kind": "box", "name": "keepsakes" });
+        let (_, boxed) =
+            call_json::<storage::Model>(&api, Method::POST, "/storage", token, Some(boxed)).await;
+        let boxed = boxed.result().unwrap().id.clone();
+        let outfit = serde_json::json!({ "name": "again", "items": [ids[0]] });
+        let conflicts = [
+            (format!("/items/{}/wear", ids[0]), serde_json::json!({})),
+            (format!("/items/{}/transition", ids[0]), serde_json::json!({ "to": "in_laundry" })),
+            (format!("/outfits/{}/wear", outfit_id), serde_json::json!({})),
+            ("/outfits".to_string(), outfit),
+            (format!("/storage/{}/items", boxed), serde_json::json!({ "items": [ids[0]] })),
+        ];
+        for (uri, body) in conflicts {
+            let (status, _) =
+                call_json::<serde_json::Value>(&api, Method::POST, &uri, token, Some(body)).await;
+            assert_eq!(status, StatusCode::CONFLICT, "{}", uri);
+        }
+
+        let (_, active) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items", token, None).await;
+        assert!(active.result().unwrap().items.iter().all(|d| d.item.id != ids[0]));
+        let (_, candidates) = call_json::<Vec<DeclutterCandidate>>(
+            &api,
+            Method::GET,
+            "/items/declutter",
+            token,
+            None,
+        )
+        .await;
+        assert_eq!(candidates.result().unwrap().len(), 2);
+        let uri = "/items/retired?reason=sold";
+        let (_, sold) = call_json::<Page<ItemDetail>>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(sold.result().unwrap().items[0].item.id, ids[0]);
+        let uri = format!("/items/{}/wears", ids[0]);
+        let (_, wears) =
+            call_json::<Page<item_wear::Model>>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(wears.result().unwrap().items.len(), 1, "history is kept");
+
+        let uri = format!("/items/{}/retire", ids[0]);
+        let (_, restored) = call_json::<ItemDetail>(&api, Method::DELETE, &uri, token, None).await;
+        let restored = restored.result().unwrap();
+        assert!(!restored.item.is_retired() && restored.item.sale_price().is_none());
+        let (_, active) =
+            call_json::<Page<ItemDetail>>(&api, Method::GET, "/items", token, None).await;
+        assert_eq!(active.result().unwrap().items.len(), 3);
+    }
+}
@@ -0,0 +1,344 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Router,
+};
+use entity::{
+    class::{availability::Availability, category::Category, color::Color, id::Id, season::Season},
+    model::{closet, item, item_color, item_tag, outfit, outfit_item},
+};
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, SimpleExpr},
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    analytics::count_distinct,
+    item::ItemDetail,
+    listing::{Listed, Listing},
+    outfit::OutfitDetail,
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, page::Page, result::ApiResponse, ApiResult},
+    service::search::{self, Facets, FullTextSearch},
+    AppState,
+};
+
+pub fn search_router() -> Router<AppState> {
+    axum::Router::new().route("/", axum::routing::get(search))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SearchQuery {
+    /// words that must each match a name, brand, notes, tag or category; empty matches all
+    #[serde(default)]
+    pub q: String,
+    pub closet: Option<Id<closet::Model>>,
+    pub category: Option<Category>,
+    pub color: Option<Color>,
+    /// ignoring case
+    pub brand: Option<String>,
+    pub season: Option<Season>,
+    pub availability: Option<Availability>,
+}
+impl SearchQuery {
+    fn is_faceted(&self) -> bool {
+        self.category.is_some()
+            || self.color.is_some()
+            || self.brand.is_some()
+            || self.season.is_some()
+            || self.availability.is_some()
+    }
+}
+
+/// outfits in a result at most, by name
+pub const OUTFIT_LIMIT: u64 = 20;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SearchResult {
+    /// by name by default, see [`Listing`]
+    pub items: Page<ItemDetail>,
+    /// outfits matching by name or occasion or by their items, and containing a matching item
+    /// if the items are narrowed by facets, the first [`OUTFIT_LIMIT`] of them
+    pub outfits: Vec<OutfitDetail>,
+    /// whether more outfits match than those given, for a narrower search
+    pub more_outfits: bool,
+    /// counts over all the matching items, not only the page
+    pub facets: Facets,
+}
+
+/// Items matching a search, by name by default.
+pub struct Matching;
+impl Listed for Matching {
+    type Entity = item::Entity;
+    const ID: item::Column = item::Column::Id;
+    const SORTABLE: &'static [item::Column] = &[item::Column::Name, item::Column::CreatedAt];
+    const FILTERABLE: &'static [item::Column] = &[];
+    const DEFAULT_SORT: &'static str = "name";
+}
+
+/// items with the term in the full-text index, a tag or their category
+fn item_condition(full_text: &dyn FullTextSearch, term: &str) -> Condition {
+    let tagged = item_tag::Entity::find()
+        .select_only()
+        .column(item_tag::Column::ItemId)
+        .filter(item_tag::Column::Tag.like(&format!("%{}%", term)));
+    let categories = Category::ALL.into_iter().filter(|c| c.to_string().starts_with(term));
+    Condition::any()
+        .add(item::Column::Id.in_subquery(full_text.items(term)))
+        .add(item::Column::Id.in_subquery(tagged.into_query()))
+        .add(item::Column::Category.is_in(categories))
+}
+
+/// brand without the surrounding whitespace, as counted by facets
+fn trimmed_brand() -> SimpleExpr {
+    Func::cust(Alias::new("TRIM")).arg(Expr::col((item::Entity, item::Column::Brand))).into()
+}
+
+/// counts of the facet values over the items of the select
+async fn facets<C: ConnectionTrait>(db: &C, items: &Select<item::Entity>) -> Result<Facets, DbErr> {
+    let matched = || items.clone().select_only().column(item::Column::Id).into_query();
+    let categories: Vec<(Option<Category>, i64)> = items
+        .clone()
+        .select_only()
+        .column(item::Column::Category)
+        .column_as(item::Column::Id.count(), "count")
+        .group_by(item::Column::Category)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let brands: Vec<(Option<String>, i64)> = items
+        .clone()
+        .select_only()
+        .column_as(trimmed_brand(), "brand")
+        .column_as(item::Column::Id.count(), "count")
+        .group_by(trimmed_brand())
+        .into_tuple()
+        .all(db)
+        .await?;
+    let availabilities: Vec<(Availability, i64)> = items
+        .clone()
+        .select_only()
+        .column(item::Column::Availability)
+        .column_as(item::Column::Id.count(), "count")
+        .group_by(item::Column::Availability)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let colors: Vec<(Color, i64)> = item_color::Entity::find()
+        .select_only()
+        .column(item_color::Column::Color)
+        .column_as(count_distinct(item_color::Column::ItemId), "count")
+        .filter(item_color::Column::ItemId.in_subquery(matched()))
+        .group_by(item_color::Column::Color)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let seasons: Vec<(String, i64)> = item_tag::Entity::find()
+        .select_only()
+        .column(item_tag::Column::Tag)
+        .column_as(count_distinct(item_tag::Column::ItemId), "count")
+        .filter(item_tag::Column::ItemId.in_subquery(matched()))
+        .filter(item_tag::Column::Tag.is_in(Season::ALL.map(|s| s.to_string())))
+        .group_by(item_tag::Column::Tag)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(Facets {
+        category: search::counts(
+            categories.into_iter().map(|(c, n)| (c.map(|c| c.to_string()), n)),
+        ),
+        color: search::counts(colors.into_iter().map(|(c, n)| (Some(c.to_string()), n))),
+        brand: search::counts(brands),
+        season: search::counts(seasons.into_iter().map(|(s, n)| (Some(s), n))),
+        availability: search::counts(
+            availabilities.into_iter().map(|(a, n)| (Some(a.to_string()), n)),
+        ),
+    })
+}
+
+/// Search items of closets the user is a member of, and outfits made of them.
+pub async fn search(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<SearchQuery>,
+    listing: Listing<Matching>,
+) -> ApiResult<SearchResult> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let full_text = search::for_backend(state.db.get_database_backend());
+    let members = || super::closet::member_closets(&user.user.id);
+    let mut items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(members()))
+        .filter(item::Column::RetiredOn.is_null());
+    let mut outfits = outfit::Entity::find()
+        .filter(outfit::Column::ClosetId.in_subquery(members()))
+        .order_by_asc(outfit::Column::Name)
+        .order_by_asc(outfit::Column::Id);
+    if let Some(closet) = &query.closet {
+        items = items.filter(item::Column::ClosetId.eq(closet.clone()));
+        outfits = outfits.filter(outfit::Column::ClosetId.eq(closet.clone()));
+    }
+    for term in search::terms(&query.q) {
+        let condition = item_condition(full_text.as_ref(), &term);
+        let matched =
+            item::Entity::find().select_only().column(item::Column::Id).filter(condition.clone());
+        let wearing = outfit_item::Entity::find()
+            .select_only()
+            .column(outfit_item::Column::OutfitId)
+            .filter(outfit_item::Column::ItemId.in_subquery(matched.into_query()));
+        items = items.filter(condition);
+        outfits = outfits.filter(
+            Condition::any()
+                .add(outfit::Column::Id.in_subquery(full_text.outfits(&term)))
+                .add(outfit::Column::Occasion.like(&format!("%{}%", term)))
+                .add(outfit::Column::Id.in_subquery(wearing.into_query())),
+        );
+    }
+    if let Some(category) = query.category {
+        items = items.filter(item::Column::Category.eq(category));
+    }
+    if let Some(availability) = query.availability {
+        items = items.filter(item::Column::Availability.eq(availability));
+    }
+    if let Some(brand) = &query.brand {
+        let brand = brand.trim().to_lowercase();
+        items = items.filter(Expr::expr(Func::lower(trimmed_brand())).eq(brand));
+    }
+    if let Some(color) = query.color {
+        let colored = item_color::Entity::find()
+            .select_only()
+            .column(item_color::Column::ItemId)
+            .filter(item_color::Column::Color.eq(color));
+        items = items.filter(item::Column::Id.in_subquery(colored.into_query()));
+    }
+    if let Some(season) = query.season {
+        let tagged = item_tag::Entity::find()
+            .select_only()
+            .column(item_tag::Column::ItemId)
+            .filter(item_tag::Column::Tag.eq(season.to_string()));
+        items = items.filter(item::Column::Id.in_subquery(tagged.into_query()));
+    }
+    if query.is_faceted() {
+        let matched = items.clone().select_only().column(item::Column::Id);
+        let wearing = outfit_item::Entity::find()
+            .select_only()
+            .column(outfit_item::Column::OutfitId)
+            .filter(outfit_item::Column::ItemId.in_subquery(matched.into_query()));
+        outfits = outfits.filter(outfit::Column::Id.in_subquery(wearing.into_query()));
+    }
+
+    let facets = facets(&state.db, &items).await?;
+    let page = listing.fetch(&state.db, items).await?;
+    let details = ItemDetail::load(&state.db, page.items.clone()).await?;
+    let mut outfits = outfits.limit(OUTFIT_LIMIT + 1).all(&state.db).await?;
+    let more_outfits = outfits.len() as u64 > OUTFIT_LIMIT;
+    outfits.truncate(OUTFIT_LIMIT as usize);
+    let outfits = OutfitDetail::load(&state.db, outfits).await?;
+    let items = page.with_items(details);
+    Ok(ApiResponse::Success(SearchResult { items, outfits, more_outfits, facets }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_search_scenario() {
+        use hyper::Method;
+
+        use super::*;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut ids = Vec::new();
+        for (name, category, brand, notes, tags) in [
+            ("navy wool coat", "outerwear", "Acme", "dry clean only", vec!["winter"]),
+            ("linen shirt", "top", "Acme", "navy stripes", vec!["summer"]),
+            ("running shoes", "shoes", "Zed", "", vec!["sport"]),
+        ] {
+            let create = serde_json::json!({
+                "name": name, "category": category, "brand": brand, "notes": notes, "tags": tags
+            });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+        let outfit = serde_json::json!({ "name": "commute", "items": [ids[0], ids[2]] });
+        call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(outfit)).await;
+
+        let search = |uri: &'static str| {
+            let api = api.clone();
+            async move {
+                let (_, result) =
+                    call_json::<SearchResult>(&api, Method::GET, uri, token, None).await;
+                result.result().unwrap().clone()
+            }
+        };
+        let navy = search("/search?q=navy").await;
+        assert_eq!(navy.items.items.len(), 2, "by name and by notes");
+        assert_eq!(navy.outfits.len(), 1, "by its coat");
+        let facet = |counts: &[search::FacetCount]| {
+            counts.iter().map(|c| (c.value.clone(), c.count)).collect::<Vec<_>>()
+        };
+        assert_eq!(facet(&navy.facets.brand), vec![("Acme".into(), 2)]);
+        assert_eq!(facet(&navy.facets.season), vec![("summer".into(), 1), ("winter".into(), 1)]);
+
+        assert_eq!(search("/search?q=NAV%20acme").await.items.items.len(), 2, "prefix, any case");
+        assert_eq!(search("/search?q=navy%20wool").await.items.items[0].item.id, ids[0]);
+        assert_eq!(search("/search?q=spor").await.items.items[0].item.id, ids[2], "by tag");
+        assert_eq!(search("/search?q=outer").await.items.items[0].item.id, ids[0], "by category");
+        assert_eq!(search("/search?q=commute").await.outfits.len(), 1);
+        assert!(
+            search("/search?q=%22*)%20OR%20xyzzy").await.items.items.is_empty(),
+            "syntax is ignored"
+        );
+        let all = search("/search").await;
+        assert_eq!((all.items.items.len(), all.outfits.len()), (3, 1));
+        assert!(!all.more_outfits);
+        assert_eq!(all.facets.category.len(), 3);
+        let first = search("/search?limit=2").await;
+        assert_eq!(first.items.items.len(), 2);
+        assert_eq!(first.facets.category.len(), 3, "over every matching item");
+        let uri = format!("/search?limit=2&cursor={}", first.items.next.unwrap());
+        let (_, rest) = call_json::<SearchResult>(&api, Method::GET, &uri, token, None).await;
+        assert_eq!(rest.result().unwrap().items.items[0].item.name, "running shoes", "by name");
+        let winter = search("/search?q=navy&season=winter").await;
+        assert_eq!(winter.items.items.len(), 1);
+        let zed = search("/search?brand=zed").await;
+        assert_eq!((zed.items.items.len(), zed.outfits.len()), (1, 1));
+        assert!(search("/search?category=top&q=coat").await.items.items.is_empty());
+
+        let uri = format!("/items/{}", ids[2]);
+        let rename = serde_json::json!({ "name": "trail runners", "category": "shoes" });
+        call_json::<ItemDetail>(&api, Method::PUT, &uri, token, Some(rename)).await;
+        assert!(search("/search?q=running").await.items.items.is_empty(), "reindexed");
+        assert_eq!(search("/search?q=trail").await.items.items.len(), 1);
+        call_json::<item::Model>(&api, Method::DELETE, &uri, token, None).await;
+        assert!(search("/search?q=trail").await.items.items.is_empty());
+        let first = search("/search?limit=1").await;
+        assert_eq!(first.items.items[0].item.id, ids[1]);
+        let uri = format!("/items/{}", ids[1]);
+        call_json::<item::Model>(&api, Method::DELETE, &uri, token, None).await;
+        let uri = format!("/search?limit=1&cursor={}", first.items.next.unwrap());
+        let (_, rest) = call_json::<SearchResult>(&api, Method::GET, &uri, token, None).await;
+        let rest = rest.result().unwrap().items.items.clone();
+        assert_eq!(rest[0].item.id, ids[0], "past a deleted cursor row");
+
+        let other = signup(&api, "other").await;
+        let (_, hidden) = call_json::<SearchResult>(
+            &api,
+            Method::GET,
+            "/search?q=navy",
+            other.token.as_deref(),
+            None,
+        )
+        .await;
+        assert!(hidden.result().unwrap().items.items.is_empty());
+    }
+}
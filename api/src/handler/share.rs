@@ -0,0 +1,409 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Extension, Router,
+};
+use entity::{
+    class::{category::Category, color::Color, id::Id, role::Role, tag::Tag},
+    error::FieldErrors,
+    model::{closet, closet_member, item, item_image, item_tag, outfit, share_link},
+};
+use hyper::{header, StatusCode};
+use rand::distributions::{Alphanumeric, DistString};
+use schemars::JsonSchema;
+use sea_orm::{
+    prelude::DateTimeWithTimeZone, sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait,
+    ConnectionTrait, DbErr, EntityTrait, IntoActiveModel, ModelTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{item::ItemDetail, json::Json};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    AppState,
+};
+
+pub fn share_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/:id", axum::routing::delete(revoke))
+}
+
+/// Routes for people without an account, authenticated by the token of a share link.
+pub fn public_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/:token", axum::routing::get(view))
+        .route("/:token/images/:image_id", axum::routing::get(image))
+}
+
+/// unguessable, about 190 bits
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ShareLinkDetail")]
+pub struct ShareLink {
+    pub link: share_link::Model,
+    /// viewable without authentication
+    pub path: String,
+}
+impl ShareLink {
+    fn new(state: &AppState, link: share_link::Model) -> Self {
+        let base_url = state.configuration.base_url();
+        let path = format!("{}/public/{}", base_url.trim_end_matches('/'), link.token);
+        Self { link, path }
+    }
+}
+
+/// Share links the user created, the most recent first.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+) -> ApiResult<Vec<ShareLink>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let links = share_link::Entity::find()
+        .filter(share_link::Column::OwnerId.eq(user.user.id))
+        .order_by_desc(share_link::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+    Ok(ApiResponse::Success(links.into_iter().map(|l| ShareLink::new(&state, l)).collect()))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ShareCreate {
+    /// share this outfit, otherwise items of the closet
+    pub outfit_id: Option<Id<outfit::Model>>,
+    /// defaults to the personal closet
+    pub closet_id: Option<Id<closet::Model>>,
+    /// narrow the shared closet to a category
+    pub category: Option<Category>,
+    /// narrow the shared closet to items with a tag
+    pub tag: Option<Tag>,
+    /// never expires if not given
+    pub expires_at: Option<DateTimeWithTimeZone>,
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<ShareCreate>,
+) -> ApiResult<ShareLink> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut errors = FieldErrors::default();
+    if let Some(expires_at) = schema.expires_at.filter(|e| e <= &chrono::Utc::now()) {
+        errors.push("expires_at", format!("{} is in the past", expires_at.to_rfc3339()));
+    }
+    if schema.outfit_id.is_some() {
+        if let Some(category) = schema.category {
+            errors.push("category", format!("{} cannot narrow an outfit", category));
+        }
+        if let Some(tag) = &schema.tag {
+            errors.push("tag", format!("{} cannot narrow an outfit", tag));
+        }
+    }
+    if !errors.is_empty() {
+        Err(errors)?
+    }
+    let closet_id = match &schema.outfit_id {
+        Some(outfit_id) => {
+            let outfit_id = outfit_id.clone();
+            super::outfit::find_accessible(&state.db, &user, outfit_id, Role::Editor)
+                .await?
+                .closet_id
+        }
+        None => super::closet::writable_closet(&state.db, &user, schema.closet_id).await?,
+    };
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+    let link = share_link::ActiveModel {
+        owner_id: ActiveValue::Set(user.user.id),
+        closet_id: ActiveValue::Set(closet_id),
+        outfit_id: ActiveValue::Set(schema.outfit_id),
+        token: ActiveValue::Set(token),
+        category: ActiveValue::Set(schema.category),
+        tag: ActiveValue::Set(schema.tag),
+        expires_at: ActiveValue::Set(schema.expires_at),
+        revoked_at: ActiveValue::Set(None),
+        views: ActiveValue::Set(0),
+        last_viewed_at: ActiveValue::Set(None),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+    Ok(ApiResponse::Success(ShareLink::new(&state, link)))
+}
+
+/// Stop the link from working, keeping it to show how often it was viewed.
+pub async fn revoke(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<share_link::Model>>,
+) -> ApiResult<ShareLink> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let link = share_link::Entity::find_by_id(id)
+        .filter(share_link::Column::OwnerId.eq(user.user.id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    if link.revoked_at.is_some() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("link is already revoked")))?
+    }
+    let mut active = link.into_active_model();
+    active.revoked_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+    let revoked = active.update(&state.db).await?;
+    Ok(ApiResponse::Success(ShareLink::new(&state, revoked)))
+}
+
+/// Item as seen through a share link, without what only its owner should know (notes, prices,
+/// storage, size and availability).
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PublicItem {
+    pub id: Id<item::Model>,
+    pub name: String,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    /// the most dominant first
+    pub colors: Vec<Color>,
+    pub tags: Vec<Tag>,
+    pub images: Vec<Id<item_image::Model>>,
+}
+impl From<ItemDetail> for PublicItem {
+    fn from(detail: ItemDetail) -> Self {
+        let ItemDetail { item, colors, tags, images } = detail;
+        let colors = colors.into_iter().map(|c| c.color).collect();
+        let (id, name, category, brand) = (item.id, item.name, item.category, item.brand);
+        Self { id, name, category, brand, colors, tags, images }
+    }
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SharedView {
+    /// of the outfit or closet
+    pub name: String,
+    pub occasion: Option<Tag>,
+    pub items: Vec<PublicItem>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+}
+
+/// the link of the token, if it still works: neither revoked nor expired, and its creator still
+/// an editor of the closet, as leaving or being demoted takes back what they shared
+async fn find_active<C: ConnectionTrait>(
+    db: &C,
+    token: String,
+) -> Result<share_link::Model, ApiError> {
+    let link = share_link::Entity::find()
+        .filter(share_link::Column::Token.eq(token))
+        .one(db)
+        .await?
+        .filter(|link| link.is_active(&chrono::Utc::now().into()))
+        .ok_or(ApiError::RecordNotFound)?;
+    let creator = closet_member::Entity::find()
+        .filter(closet_member::Column::ClosetId.eq(link.closet_id.clone()))
+        .filter(closet_member::Column::UserId.eq(link.owner_id.clone()))
+        .one(db)
+        .await?;
+    match creator {
+        Some(creator) if creator.role.satisfies(&Role::Editor) => Ok(link),
+        _ => Err(ApiError::RecordNotFound),
+    }
+}
+
+/// name and occasion of what is shared, and its active items
+async fn shared<C: ConnectionTrait>(
+    db: &C,
+    link: &share_link::Model,
+) -> Result<(String, Option<Tag>, Vec<item::Model>), ApiError> {
+    if let Some(outfit_id) = &link.outfit_id {
+        let outfit = outfit::Entity::find_by_id(outfit_id.clone())
+            .filter(outfit::Column::ClosetId.eq(link.closet_id.clone()))
+            .one(db)
+            .await?
+            .ok_or(ApiError::RecordNotFound)?;
+        let items = outfit.find_related(item::Entity).filter(item::Column::RetiredOn.is_null());
+        let items = items.order_by_asc(item::Column::Name).all(db).await?;
+        return Ok((outfit.name, outfit.occasion, items));
+    }
+    let closet = closet::Entity::find_by_id(link.closet_id.clone())
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    let mut items = item::Entity::find()
+        .filter(item::Column::ClosetId.eq(closet.id))
+        .filter(item::Column::RetiredOn.is_null())
+        .order_by_asc(item::Column::Name);
+    if let Some(category) = link.category {
+        items = items.filter(item::Column::Category.eq(category));
+    }
+    if let Some(tag) = &link.tag {
+        let tagged = item_tag::Entity::find()
+            .select_only()
+            .column(item_tag::Column::ItemId)
+            .filter(item_tag::Column::Tag.eq(tag.clone()));
+        items = items.filter(item::Column::Id.in_subquery(tagged.into_query()));
+    }
+    Ok((closet.name, None, items.all(db).await?))
+}
+
+async fn count_view<C: ConnectionTrait>(db: &C, link: &share_link::Model) -> Result<(), DbErr> {
+    let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+    share_link::Entity::update_many()
+        .col_expr(share_link::Column::Views, Expr::col(share_link::Column::Views).add(1))
+        .col_expr(share_link::Column::LastViewedAt, Expr::value(now))
+        .filter(share_link::Column::Id.eq(link.id.clone()))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// The shared outfit or closet, for anyone with the token.
+pub async fn view(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> ApiResult<SharedView> {
+    let link = find_active(&state.db, token).await?;
+    let (name, occasion, items) = shared(&state.db, &link).await?;
+    let items = ItemDetail::load(&state.db, items).await?;
+    count_view(&state.db, &link).await?;
+    let items = items.into_iter().map(PublicItem::from).collect();
+    Ok(ApiResponse::Success(SharedView { name, occasion, items, expires_at: link.expires_at }))
+}
+
+/// Photo of a shared item.
+pub async fn image(
+    State(state): State<AppState>,
+    Path((token, image_id)): Path<(String, Id<item_image::Model>)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let link = find_active(&state.db, token).await?;
+    let (_, _, items) = shared(&state.db, &link).await?;
+    let image = item_image::Entity::find_by_id(image_id)
+        .filter(item_image::Column::ItemId.is_in(items.into_iter().map(|i| i.id)))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    Ok(([(header::CONTENT_TYPE, image.content_type)], image.data))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_share_scenario() {
+        use entity::model::closet_invitation;
+        use hyper::{Body, Method, StatusCode};
+
+        use super::*;
+        use crate::handler::{closet::ClosetDetail, outfit::OutfitDetail};
+        use crate::{call, call_json, configuration::Configuration, request, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut ids = Vec::new();
+        for (name, category) in [("linen shirt", "top"), ("chinos", "bottom")] {
+            let create = serde_json::json!({
+                "name": name, "category": category, "notes": "stain on the cuff",
+                "purchase_price": "49.00 USD"
+            });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+        let outfit = serde_json::json!({ "name": "brunch", "occasion": "casual", "items": ids });
+        let (_, outfit) =
+            call_json::<OutfitDetail>(&api, Method::POST, "/outfits", token, Some(outfit)).await;
+        let outfit_id = outfit.result().unwrap().outfit.id.clone();
+
+        let expired =
+            serde_json::json!({ "outfit_id": outfit_id, "expires_at": "2020-01-01T00:00:00Z" });
+        let (status, _) =
+            call_json::<ShareLink>(&api, Method::POST, "/shares", token, Some(expired)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let narrowed = serde_json::json!({
+            "outfit_id": outfit_id, "category": "top", "expires_at": "2020-01-01T00:00:00Z"
+        });
+        let (status, failure) =
+            call_json::<ShareLink>(&api, Method::POST, "/shares", token, Some(narrowed)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "outfits are shared whole");
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else {
+            panic!("{:?}", failure)
+        };
+        assert_eq!(fields.0.keys().collect::<Vec<_>>(), ["category", "expires_at"]);
+        let share = serde_json::json!({ "outfit_id": outfit_id });
+        let (_, shared) =
+            call_json::<ShareLink>(&api, Method::POST, "/shares", token, Some(share)).await;
+        let shared = shared.result().unwrap().clone();
+        assert_eq!(shared.link.token.len(), TOKEN_LENGTH);
+        assert!(shared.path.ends_with(&format!("/public/{}", shared.link.token)));
+
+        let uri = format!("/public/{}", shared.link.token);
+        let (status, bytes) =
+            call(&api, request(Method::GET, &uri, None).body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(!body.contains("stain") && !body.contains("49.00"), "private fields are stripped");
+        let (_, view) = call_json::<SharedView>(&api, Method::GET, &uri, None, None).await;
+        let view = view.result().unwrap();
+        assert_eq!(view.name, "brunch");
+        assert_eq!(view.items.len(), 2);
+        let (_, links) =
+            call_json::<Vec<ShareLink>>(&api, Method::GET, "/shares", token, None).await;
+        let links = links.result().unwrap();
+        assert_eq!(links[0].link.views, 2);
+        assert!(links[0].link.last_viewed_at.is_some());
+
+        let closet = serde_json::json!({ "category": "bottom" });
+        let (_, closet) =
+            call_json::<ShareLink>(&api, Method::POST, "/shares", token, Some(closet)).await;
+        let uri = format!("/public/{}", closet.result().unwrap().link.token);
+        let (_, view) = call_json::<SharedView>(&api, Method::GET, &uri, None, None).await;
+        let items = &view.result().unwrap().items;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "chinos");
+
+        let other = signup(&api, "other").await;
+        let uri = format!("/shares/{}", shared.link.id);
+        let (status, _) =
+            call_json::<ShareLink>(&api, Method::DELETE, &uri, other.token.as_deref(), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, revoked) =
+            call_json::<ShareLink>(&api, Method::DELETE, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(revoked.result().unwrap().link.revoked_at.is_some());
+        let uri = format!("/public/{}", shared.link.token);
+        let (status, _) = call_json::<SharedView>(&api, Method::GET, &uri, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) =
+            call_json::<SharedView>(&api, Method::GET, "/public/guess", None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // a link of a member stops working as they are removed from the closet
+        let create = serde_json::json!({ "name": "household" });
+        let (_, household) =
+            call_json::<ClosetDetail>(&api, Method::POST, "/closets", token, Some(create)).await;
+        let household_id = household.result().unwrap().closet.id.clone();
+        let uri = format!("/closets/{}/invitations", household_id);
+        let invite = serde_json::json!({ "username": "other", "role": "editor" });
+        let (_, invitation) =
+            call_json::<closet_invitation::Model>(&api, Method::POST, &uri, token, Some(invite))
+                .await;
+        let uri = format!("/closets/invitations/{}/accept", invitation.result().unwrap().id);
+        call_json::<ClosetDetail>(&api, Method::POST, &uri, other.token.as_deref(), None).await;
+        let share = serde_json::json!({ "closet_id": household_id });
+        let (_, member_link) = call_json::<ShareLink>(
+            &api,
+            Method::POST,
+            "/shares",
+            other.token.as_deref(),
+            Some(share),
+        )
+        .await;
+        let public = format!("/public/{}", member_link.result().unwrap().link.token);
+        let (status, _) = call_json::<SharedView>(&api, Method::GET, &public, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let uri = format!("/closets/{}/members/{}", household_id, other.user.id);
+        let (status, _) =
+            call_json::<closet_member::Model>(&api, Method::DELETE, &uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call_json::<SharedView>(&api, Method::GET, &public, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "the creator is no longer a member");
+    }
+}
@@ -0,0 +1,562 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Router,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        availability::Availability, id::Id, role::Role, season::Season, storage_kind::StorageKind,
+    },
+    error::FieldErrors,
+    model::{closet, item, storage},
+};
+use hyper::{header, StatusCode};
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::ItemDetail,
+    json::Json,
+    trip::{ExportFormat, ExportQuery},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiResult},
+    service::rotation::{self, Direction, Stowed},
+    AppState,
+};
+
+pub fn storage_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/rotation", axum::routing::get(rotation))
+        .route("/:id", axum::routing::get(detail).put(update).delete(delete))
+        .route("/:id/items", axum::routing::post(store))
+        .route("/:id/items/:item_id", axum::routing::delete(unstore))
+        .route("/:id/manifest", axum::routing::get(manifest))
+}
+
+/// The storage, if the user has at least `required` role in its closet.
+pub(crate) async fn find_accessible<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<storage::Model>,
+    required: Role,
+) -> Result<storage::Model, ApiError> {
+    let storage = storage::Entity::find_by_id(id).one(db).await?.ok_or(ApiError::RecordNotFound)?;
+    super::closet::authorize(db, user, &storage.closet_id, required).await?;
+    Ok(storage)
+}
+
+/// Move the item into `storage`, or out of any; items put away are stored and those brought
+/// back are clean again.
+pub(crate) async fn place<C: ConnectionTrait>(
+    db: &C,
+    item: item::Model,
+    storage: Option<&storage::Model>,
+) -> Result<item::Model, ApiError> {
+    if storage.is_some_and(|s| s.closet_id != item.closet_id) {
+        let error = anyhow::anyhow!("{} belongs to another closet", item.name);
+        Err((StatusCode::CONFLICT, error))?
+    }
+    let active = storage.is_none_or(|s| s.active);
+    let item = match (active, item.availability) {
+        (true, Availability::Stored) => {
+            super::item::transition_item(db, item, Availability::Clean).await?
+        }
+        (false, availability) if availability != Availability::Stored => {
+            super::item::transition_item(db, item, Availability::Stored).await?
+        }
+        _ => item,
+    };
+    let mut active = item.into_active_model();
+    active.storage_id = ActiveValue::Set(storage.map(|s| s.id.clone()));
+    Ok(active.update(db).await?)
+}
+
+async fn stored_items<C: ConnectionTrait>(
+    db: &C,
+    id: &Id<storage::Model>,
+) -> Result<Vec<item::Model>, sea_orm::DbErr> {
+    item::Entity::find().filter(item::Column::StorageId.eq(id.clone())).all(db).await
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StorageSummary {
+    pub storage: storage::Model,
+    pub items: u64,
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StorageQuery {
+    pub closet: Option<Id<closet::Model>>,
+}
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<StorageQuery>,
+) -> ApiResult<Vec<StorageSummary>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = storage::Entity::find()
+        .filter(storage::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .order_by_asc(storage::Column::Kind)
+        .order_by_asc(storage::Column::Name);
+    if let Some(closet) = query.closet {
+        select = select.filter(storage::Column::ClosetId.eq(closet));
+    }
+    let mut summaries = Vec::new();
+    for storage in select.all(&state.db).await? {
+        let items = item::Entity::find()
+            .filter(item::Column::StorageId.eq(storage.id.clone()))
+            .count(&state.db)
+            .await?;
+        summaries.push(StorageSummary { storage, items });
+    }
+    Ok(ApiResponse::Success(summaries))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct StorageCreate {
+    /// defaults to the personal closet, ignored on update
+    pub closet_id: Option<Id<closet::Model>>,
+    pub parent_id: Option<Id<storage::Model>>,
+    pub kind: StorageKind,
+    pub name: String,
+    pub label: Option<String>,
+    /// defaults to whether the kind is usually at hand
+    pub active: Option<bool>,
+}
+async fn validate_parent<C: ConnectionTrait>(
+    db: &C,
+    closet_id: &Id<closet::Model>,
+    id: Option<&Id<storage::Model>>,
+    parent_id: Option<Id<storage::Model>>,
+    kind: StorageKind,
+) -> Result<(), ApiError> {
+    let Some(parent_id) = parent_id else { return Ok(()) };
+    let parent = storage::Entity::find_by_id(parent_id.clone()).one(db).await?;
+    let valid = parent.is_some_and(|parent| {
+        &parent.closet_id == closet_id && Some(&parent.id) != id && kind.fits_in(&parent.kind)
+    });
+    if !valid {
+        let error = format!("{} cannot hold a {} of this closet", parent_id, kind);
+        Err(FieldErrors::of("parent_id", error))?
+    }
+    Ok(())
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<StorageCreate>,
+) -> ApiResult<storage::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let closet_id = super::closet::writable_closet(&txn, &user, schema.closet_id).await?;
+    validate_parent(&txn, &closet_id, None, schema.parent_id.clone(), schema.kind).await?;
+    let active = storage::ActiveModel {
+        closet_id: ActiveValue::Set(closet_id),
+        parent_id: ActiveValue::Set(schema.parent_id.clone()),
+        kind: ActiveValue::Set(schema.kind),
+        name: ActiveValue::Set(schema.name),
+        label: ActiveValue::Set(schema.label),
+        active: ActiveValue::Set(schema.active.unwrap_or_else(|| schema.kind.is_active())),
+        ..Default::default()
+    };
+    let created = active.insert(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(created))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StorageDetail {
+    pub storage: storage::Model,
+    pub children: Vec<storage::Model>,
+    pub items: Vec<ItemDetail>,
+}
+impl StorageDetail {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        storage: storage::Model,
+    ) -> Result<Self, sea_orm::DbErr> {
+        let children = storage::Entity::find()
+            .filter(storage::Column::ParentId.eq(storage.id.clone()))
+            .order_by_asc(storage::Column::Name)
+            .all(db)
+            .await?;
+        let items = item::Entity::find()
+            .filter(item::Column::StorageId.eq(storage.id.clone()))
+            .order_by_asc(item::Column::Name)
+            .all(db)
+            .await?;
+        let items = ItemDetail::load(db, items).await?;
+        Ok(Self { storage, children, items })
+    }
+}
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<storage::Model>>,
+) -> ApiResult<StorageDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let storage = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    Ok(ApiResponse::Success(StorageDetail::load(&state.db, storage).await?))
+}
+
+/// Update the storage; items follow when it becomes active or not.
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<storage::Model>>,
+    Json(schema): Json<StorageCreate>,
+) -> ApiResult<StorageDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let storage = find_accessible(&txn, &user, id, Role::Editor).await?;
+    let parent_id = schema.parent_id.clone();
+    validate_parent(&txn, &storage.closet_id, Some(&storage.id), parent_id, schema.kind).await?;
+    let mut active = storage.into_active_model();
+    active.parent_id = ActiveValue::Set(schema.parent_id);
+    active.kind = ActiveValue::Set(schema.kind);
+    active.name = ActiveValue::Set(schema.name);
+    active.label = ActiveValue::Set(schema.label);
+    active.active = ActiveValue::Set(schema.active.unwrap_or_else(|| schema.kind.is_active()));
+    let updated = active.update(&txn).await?;
+    for item in stored_items(&txn, &updated.id).await? {
+        place(&txn, item, Some(&updated)).await?;
+    }
+    let detail = StorageDetail::load(&txn, updated).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(detail))
+}
+
+/// Delete the storage, which must be empty of other storages; its items are taken out.
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<storage::Model>>,
+) -> ApiResult<storage::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let storage = find_accessible(&txn, &user, id, Role::Editor).await?;
+    let children = storage::Entity::find()
+        .filter(storage::Column::ParentId.eq(storage.id.clone()))
+        .count(&txn)
+        .await?;
+    if children > 0 {
+        let error = anyhow::anyhow!("{} still contains {} storages", storage.name, children);
+        Err((StatusCode::CONFLICT, error))?
+    }
+    for item in stored_items(&txn, &storage.id).await? {
+        place(&txn, item, None).await?;
+    }
+    storage.clone().delete(&txn).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(storage))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct StorageItems {
+    pub items: Vec<Id<item::Model>>,
+}
+/// Move items of the same closet into the storage.
+pub async fn store(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<storage::Model>>,
+    Json(schema): Json<StorageItems>,
+) -> ApiResult<StorageDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let storage = find_accessible(&txn, &user, id, Role::Editor).await?;
+    for item_id in schema.items {
+        let item = super::item::lock_accessible(&txn, &user, item_id, Role::Editor).await?;
+        if item.is_retired() {
+            Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is retired", item.name)))?
+        }
+        place(&txn, item, Some(&storage)).await?;
+    }
+    let detail = StorageDetail::load(&txn, storage).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(detail))
+}
+
+/// Take the item out of the storage, back to the active closet.
+pub async fn unstore(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, item_id)): Path<(Id<storage::Model>, Id<item::Model>)>,
+) -> ApiResult<ItemDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let storage = find_accessible(&txn, &user, id, Role::Editor).await?;
+    let item = super::item::lock_accessible(&txn, &user, item_id, Role::Editor).await?;
+    if item.storage_id.as_ref() != Some(&storage.id) {
+        Err(ApiError::RecordNotFound)?
+    }
+    let item = place(&txn, item, None).await?;
+    let mut details = ItemDetail::load(&txn, vec![item]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ManifestRow<'a> {
+    name: &'a str,
+    category: Option<String>,
+    brand: Option<&'a str>,
+    tags: String,
+}
+/// Contents of the storage, e.g. to print and tape on a box.
+pub async fn manifest(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<storage::Model>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let storage = find_accessible(&state.db, &user, id, Role::Viewer).await?;
+    let detail = StorageDetail::load(&state.db, storage).await?;
+    let (content_type, extension, body) = match query.format {
+        ExportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string(&detail).map_err(anyhow::Error::from)?,
+        ),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", manifest_csv(&detail)?),
+        ExportFormat::Text => ("text/plain; charset=utf-8", "txt", manifest_text(&detail)),
+    };
+    let disposition = format!("attachment; filename=\"manifest.{}\"", extension);
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+fn manifest_csv(detail: &StorageDetail) -> anyhow::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for ItemDetail { item, tags, .. } in &detail.items {
+        writer.serialize(ManifestRow {
+            name: &item.name,
+            category: item.category.map(|c| c.to_string()),
+            brand: item.brand.as_deref(),
+            tags: tags.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(" "),
+        })?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn manifest_text(detail: &StorageDetail) -> String {
+    let StorageDetail { storage, children, items } = detail;
+    let title = storage.label.as_deref().unwrap_or(&storage.name);
+    let mut lines = vec![format!("{} ({})", title, storage.kind)];
+    if storage.label.is_some() {
+        lines.push(storage.name.clone());
+    }
+    lines.push(format!("{} items", items.len()));
+    lines.push(String::new());
+    for ItemDetail { item, tags, .. } in items {
+        let mut line = format!("[ ] {}", item.name);
+        if let Some(category) = item.category {
+            line += &format!(" - {}", category);
+        }
+        if !tags.is_empty() {
+            let tags: Vec<_> = tags.iter().map(|t| t.as_str()).collect();
+            line += &format!(" ({})", tags.join(", "));
+        }
+        lines.push(line);
+    }
+    for child in children {
+        lines.push(format!(
+            "[ ] {} ({})",
+            child.label.as_deref().unwrap_or(&child.name),
+            child.kind
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RotationQuery {
+    /// defaults to today
+    pub date: Option<NaiveDate>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotationMove {
+    pub item: item::Model,
+    /// where it is now, `None` for the active closet
+    pub storage: Option<storage::Model>,
+    pub reason: String,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Rotation {
+    pub seasons: Vec<Season>,
+    pub bring_in: Vec<RotationMove>,
+    pub put_away: Vec<RotationMove>,
+}
+/// Which items to bring out of and put into seasonal storage around the date.
+pub async fn rotation(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<RotationQuery>,
+) -> ApiResult<Rotation> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let date = query.date.unwrap_or_else(|| user.user.today());
+    let members = || super::closet::member_closets(&user.user.id);
+    let storages = storage::Entity::find()
+        .filter(storage::Column::ClosetId.in_subquery(members()))
+        .all(&state.db)
+        .await?;
+    let items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(members()))
+        .filter(item::Column::RetiredOn.is_null())
+        .filter(
+            item::Column::Availability.is_not_in([Availability::LentOut, Availability::InRepair]),
+        )
+        .order_by_asc(item::Column::Name)
+        .all(&state.db)
+        .await?;
+    let details = ItemDetail::load(&state.db, items).await?;
+    let storage_of = |item: &item::Model| {
+        item.storage_id.as_ref().and_then(|id| storages.iter().find(|s| &s.id == id))
+    };
+    let stowed: Vec<_> = details
+        .iter()
+        .map(|d| Stowed {
+            id: d.item.id.clone(),
+            seasons: d.tags.iter().filter_map(|t| t.as_str().parse().ok()).collect(),
+            active: storage_of(&d.item).is_none_or(|s| s.active),
+            clean: matches!(d.item.availability, Availability::Clean | Availability::Stored),
+        })
+        .collect();
+    let (mut bring_in, mut put_away) = (Vec::new(), Vec::new());
+    let hemisphere = user.user.hemisphere();
+    for proposal in rotation::rotate(&stowed, &date, hemisphere) {
+        let Some(detail) = details.iter().find(|d| d.item.id == proposal.id) else { continue };
+        let storage = storage_of(&detail.item).cloned();
+        let reason = proposal.reason;
+        let moved = RotationMove { item: detail.item.clone(), storage, reason };
+        match proposal.direction {
+            Direction::In => bring_in.push(moved),
+            Direction::Out => put_away.push(moved),
+        }
+    }
+    Ok(ApiResponse::Success(Rotation {
+        seasons: rotation::wanted(&date, hemisphere),
+        bring_in,
+        put_away,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_storage_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let mut ids = Vec::new();
+        for (name, tags) in [("coat", vec!["winter"]), ("tee", vec!["summer"]), ("jeans", vec![])] {
+            let create = serde_json::json!({ "name": name, "category": "top", "tags": tags });
+            let (_, created) =
+                call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+            ids.push(created.result().unwrap().item.id.clone());
+        }
+
+        let wardrobe = serde_json::json!({ "kind": "wardrobe", "name": "bedroom wardrobe" });
+        let (_, wardrobe) =
+            call_json::<storage::Model>(&api, Method::POST, "/storage", token, Some(wardrobe))
+                .await;
+        let wardrobe = wardrobe.result().unwrap().clone();
+        assert!(wardrobe.active);
+        let room = serde_json::json!({ "kind": "room", "name": "attic", "parent_id": wardrobe.id });
+        let (status, _) =
+            call_json::<storage::Model>(&api, Method::POST, "/storage", token, Some(room)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "a room does not fit in a wardrobe");
+        let (_, boxed) = call_json::<storage::Model>(
+            &api,
+            Method::POST,
+            "/storage",
+            token,
+            Some(serde_json::json!({
+                "kind": "box", "name": "top shelf box", "label": "WINTER 1", "parent_id": wardrobe.id
+            })),
+        )
+        .await;
+        let boxed = boxed.result().unwrap().clone();
+        assert!(!boxed.active);
+
+        let uri = format!("/storage/{}/items", boxed.id);
+        let store = serde_json::json!({ "items": [ids[0]] });
+        let (_, detail) =
+            call_json::<StorageDetail>(&api, Method::POST, &uri, token, Some(store)).await;
+        let detail = detail.result().unwrap();
+        assert_eq!(detail.items.len(), 1);
+        assert_eq!(detail.items[0].item.availability, Availability::Stored);
+
+        let uri = "/storage/rotation?date=2023-11-20";
+        let (_, rotation) = call_json::<Rotation>(&api, Method::GET, uri, token, None).await;
+        let rotation = rotation.result().unwrap();
+        assert_eq!(rotation.seasons, vec![Season::Autumn, Season::Winter]);
+        assert_eq!(rotation.bring_in.len(), 1);
+        assert_eq!(rotation.bring_in[0].item.id, ids[0]);
+        assert_eq!(rotation.bring_in[0].storage.as_ref().unwrap().id, boxed.id);
+        assert_eq!(rotation.put_away.len(), 1);
+        assert_eq!(rotation.put_away[0].item.id, ids[1]);
+        let uri = "/storage/rotation?date=2023-07-01";
+        let (_, rotation) = call_json::<Rotation>(&api, Method::GET, uri, token, None).await;
+        let rotation = rotation.result().unwrap();
+        assert!(rotation.bring_in.is_empty() && rotation.put_away.is_empty());
+        let sydney = serde_json::json!({ "home": "-33.8688,151.2093" });
+        call_json::<serde_json::Value>(&api, Method::PUT, "/profile/home", token, Some(sydney))
+            .await;
+        let (_, rotation) = call_json::<Rotation>(&api, Method::GET, uri, token, None).await;
+        assert_eq!(rotation.result().unwrap().seasons, vec![Season::Winter], "down under");
+
+        let uri = format!("/storage/{}/manifest?format=text", boxed.id);
+        let (status, body) = crate::call(
+            &api,
+            crate::request(Method::GET, &uri, token).body(hyper::Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.starts_with("WINTER 1 (box)\n"));
+        assert!(text.contains("[ ] coat - top (winter)\n"));
+
+        let uri = format!("/storage/{}", wardrobe.id);
+        let (status, _) =
+            call_json::<storage::Model>(&api, Method::DELETE, &uri, token, None).await;
+        assert_eq!(status, StatusCode::CONFLICT, "the box is still inside");
+
+        let uri = format!("/storage/{}", boxed.id);
+        let update = serde_json::json!({
+            "kind": "box", "name": "top shelf box", "parent_id": wardrobe.id, "active": true
+        });
+        let (_, detail) =
+            call_json::<StorageDetail>(&api, Method::PUT, &uri, token, Some(update)).await;
+        let detail = detail.result().unwrap();
+        assert_eq!(detail.items[0].item.availability, Availability::Clean, "unpacked");
+
+        let uri = format!("/storage/{}/items/{}", boxed.id, ids[0]);
+        let (_, unstored) = call_json::<ItemDetail>(&api, Method::DELETE, &uri, token, None).await;
+        assert!(unstored.result().unwrap().item.storage_id.is_none());
+        let (_, storages) =
+            call_json::<Vec<StorageSummary>>(&api, Method::GET, "/storage", token, None).await;
+        assert!(storages.result().unwrap().iter().all(|s| s.items == 0));
+    }
+}
@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Router,
+};
+use chrono::NaiveDate;
+use entity::{
+    class::{availability::Availability, category::Category, id::Id, season::Season, tag::Tag},
+    model::{item, packing_item, trip, trip_activity},
+};
+use hyper::header;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    IntoActiveModel, LoaderTrait, ModelTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::ItemDetail,
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    service::packing::{self, Plan, Shortage},
+    AppState,
+};
+
+pub fn trip_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/:id", axum::routing::get(detail).delete(delete))
+        .route("/:id/packing", axum::routing::get(packing_list).post(generate))
+        .route("/:id/packing/export", axum::routing::get(export))
+        .route("/:id/packing/:packing_id", axum::routing::put(check))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PackingEntry {
+    pub packing: packing_item::Model,
+    pub item: item::Model,
+}
+impl PackingEntry {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        trips: &[trip::Model],
+    ) -> Result<Vec<Vec<Self>>, DbErr> {
+        let packed = packing_item::Entity::find()
+            .filter(packing_item::Column::TripId.is_in(trips.iter().map(|t| t.id.clone())))
+            .find_also_related(item::Entity)
+            .order_by_asc(packing_item::Column::Id)
+            .all(db)
+            .await?;
+        Ok(trips
+            .iter()
+            .map(|trip| {
+                packed
+                    .iter()
+                    .filter(|(packing, _)| packing.trip_id == trip.id)
+                    .filter_map(|(packing, item)| {
+                        Some(Self { packing: packing.clone(), item: item.clone()? })
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TripDetail {
+    pub trip: trip::Model,
+    pub activities: Vec<Tag>,
+    pub packing: Vec<PackingEntry>,
+}
+impl TripDetail {
+    pub async fn load<C: ConnectionTrait>(
+        db: &C,
+        trips: Vec<trip::Model>,
+    ) -> Result<Vec<Self>, DbErr> {
+        if trips.is_empty() {
+            return Ok(Vec::new());
+        }
+        let activities = trips
+            .load_many(
+                trip_activity::Entity::find().order_by_asc(trip_activity::Column::Activity),
+                db,
+            )
+            .await?;
+        let packing = PackingEntry::load(db, &trips).await?;
+        Ok(trips
+            .into_iter()
+            .zip(activities)
+            .zip(packing)
+            .map(|((trip, activities), packing)| {
+                let activities = activities.into_iter().map(|a| a.activity).collect();
+                Self { trip, activities, packing }
+            })
+            .collect())
+    }
+}
+
+pub(crate) async fn find_owned<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<trip::Model>,
+) -> Result<trip::Model, ApiError> {
+    trip::Entity::find_by_id(id)
+        .filter(trip::Column::OwnerId.eq(user.user.id.clone()))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct TripCreate {
+    pub name: String,
+    pub destination: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    #[serde(default)]
+    pub activities: Vec<Tag>,
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<TripCreate>,
+) -> ApiResult<TripDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let TripCreate { name, destination, starts_on, ends_on, mut activities } = schema;
+    trip::Model::validate_period(&starts_on, &ends_on)?;
+    let txn = state.db.begin().await?;
+    let created = trip::ActiveModel {
+        owner_id: ActiveValue::Set(user.user.id),
+        name: ActiveValue::Set(name),
+        destination: ActiveValue::Set(destination),
+        starts_on: ActiveValue::Set(starts_on),
+        ends_on: ActiveValue::Set(ends_on),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    activities.sort();
+    activities.dedup();
+    for activity in activities {
+        let trip_id = ActiveValue::Set(created.id.clone());
+        let activity = ActiveValue::Set(activity);
+        trip_activity::ActiveModel { trip_id, activity, ..Default::default() }.insert(&txn).await?;
+    }
+    let mut details = TripDetail::load(&txn, vec![created]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+impl Listed for trip::Entity {
+    type Entity = Self;
+    const ID: trip::Column = trip::Column::Id;
+    const SORTABLE: &'static [trip::Column] =
+        &[trip::Column::StartsOn, trip::Column::EndsOn, trip::Column::CreatedAt];
+    const FILTERABLE: &'static [trip::Column] = &[];
+    const DEFAULT_SORT: &'static str = "starts_on";
+}
+/// Trips of the user, the earliest first by default.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    listing: Listing<trip::Entity>,
+) -> ApiPage<TripDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let select = trip::Entity::find().filter(trip::Column::OwnerId.eq(user.user.id));
+    let page = listing.fetch(&state.db, select).await?;
+    let details = TripDetail::load(&state.db, page.items.clone()).await?;
+    Ok(ApiResponse::Success(page.with_items(details)))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<trip::Model>>,
+) -> ApiResult<TripDetail> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let trip = find_owned(&state.db, &user, id).await?;
+    let mut details = TripDetail::load(&state.db, vec![trip]).await?;
+    Ok(ApiResponse::Success(details.remove(0)))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<trip::Model>>,
+) -> ApiResult<trip::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let trip = find_owned(&state.db, &user, id).await?;
+    trip.clone().delete(&state.db).await?;
+    Ok(ApiResponse::Success(trip))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PackingGenerate {
+    /// days a garment of the category can be worn, overrides [`packing::reuse_limit`]
+    #[serde(default)]
+    pub reuse: HashMap<Category, i64>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TripPacking {
+    pub entries: Vec<PackingEntry>,
+    pub shortages: Vec<Shortage>,
+}
+/// Replace the packing list of the trip with one generated from the closet.
+pub async fn generate(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<trip::Model>>,
+    Json(schema): Json<PackingGenerate>,
+) -> ApiResult<TripPacking> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let txn = state.db.begin().await?;
+    let trip = find_owned(&txn, &user, id).await?;
+    let activities = trip.find_related(trip_activity::Entity).all(&txn).await?;
+    let plan = Plan {
+        days: trip.days(),
+        season: Season::of(&trip.starts_on, user.user.hemisphere()),
+        activities: activities.into_iter().map(|a| a.activity).collect(),
+        reuse: schema.reuse,
+    };
+
+    // items away from the closet cannot be packed
+    let items = item::Entity::find()
+        .filter(item::Column::ClosetId.in_subquery(super::closet::member_closets(&user.user.id)))
+        .filter(
+            item::Column::Availability.is_not_in([Availability::LentOut, Availability::InRepair]),
+        )
+        .filter(item::Column::RetiredOn.is_null())
+        .order_by_asc(item::Column::Id)
+        .all(&txn)
+        .await?;
+    let details = ItemDetail::load(&txn, items).await?;
+    let garments: Vec<_> = details.iter().filter_map(|d| d.garment(None)).collect();
+    let list = packing::pack(&garments, &plan);
+
+    packing_item::Entity::delete_many()
+        .filter(packing_item::Column::TripId.eq(trip.id.clone()))
+        .exec(&txn)
+        .await?;
+    for packed in list.packed {
+        packing_item::ActiveModel {
+            trip_id: ActiveValue::Set(trip.id.clone()),
+            item_id: ActiveValue::Set(packed.id),
+            days: ActiveValue::Set(packed.days as i32),
+            reason: ActiveValue::Set(packed.reason),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+    let entries = PackingEntry::load(&txn, &[trip]).await?.remove(0);
+    txn.commit().await?;
+    Ok(ApiResponse::Success(TripPacking { entries, shortages: list.shortages }))
+}
+
+pub async fn packing_list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<trip::Model>>,
+) -> ApiResult<Vec<PackingEntry>> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let trip = find_owned(&state.db, &user, id).await?;
+    Ok(ApiResponse::Success(PackingEntry::load(&state.db, &[trip]).await?.remove(0)))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct PackingCheck {
+    pub packed: bool,
+}
+pub async fn check(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path((id, packing_id)): Path<(Id<trip::Model>, Id<packing_item::Model>)>,
+    Json(schema): Json<PackingCheck>,
+) -> ApiResult<PackingEntry> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let trip = find_owned(&state.db, &user, id).await?;
+    let (packing, item) = packing_item::Entity::find_by_id(packing_id)
+        .filter(packing_item::Column::TripId.eq(trip.id))
+        .find_also_related(item::Entity)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+    let mut active = packing.into_active_model();
+    active.packed = ActiveValue::Set(schema.packed);
+    let packing = active.update(&state.db).await?;
+    Ok(ApiResponse::Success(PackingEntry { packing, item: item.ok_or(ApiError::RecordNotFound)? }))
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Text,
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PackingRow<'a> {
+    name: &'a str,
+    category: Option<Category>,
+    brand: Option<&'a str>,
+    days: i32,
+    packed: bool,
+    reason: &'a str,
+}
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<trip::Model>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let trip = find_owned(&state.db, &user, id).await?;
+    let detail = TripDetail::load(&state.db, vec![trip]).await?.remove(0);
+    let (content_type, extension, body) = match query.format {
+        ExportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string(&detail).map_err(anyhow::Error::from)?,
+        ),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", to_csv(&detail)?),
+        ExportFormat::Text => ("text/plain; charset=utf-8", "txt", to_text(&detail)),
+    };
+    let disposition = format!("attachment; filename=\"packing.{}\"", extension);
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+fn to_csv(detail: &TripDetail) -> anyhow::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for PackingEntry { packing, item } in &detail.packing {
+        writer.serialize(PackingRow {
+            name: &item.name,
+            category: item.category,
+            brand: item.brand.as_deref(),
+            days: packing.days,
+            packed: packing.packed,
+            reason: &packing.reason,
+        })?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn to_text(detail: &TripDetail) -> String {
+    let TripDetail { trip, activities, packing } = detail;
+    let mut lines = vec![
+        format!("{} ({})", trip.name, trip.destination),
+        format!("{} to {}, {} days", trip.starts_on, trip.ends_on, trip.days()),
+    ];
+    if !activities.is_empty() {
+        let activities: Vec<_> = activities.iter().map(|a| a.as_str()).collect();
+        lines.push(format!("activities: {}", activities.join(", ")));
+    }
+    lines.push(String::new());
+    for PackingEntry { packing, item } in packing {
+        let check = if packing.packed { "[x]" } else { "[ ]" };
+        lines.push(format!("{} {} - {}", check, item.name, packing.reason));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_trip_packing_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::{call, call_json, configuration::Configuration, request, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let closet = [
+            ("white tee", "top", vec![]),
+            ("black tee", "top", vec![]),
+            ("jeans", "bottom", vec![]),
+            ("sneakers", "shoes", vec![]),
+            ("hiking boots", "shoes", vec!["hiking"]),
+        ];
+        for (name, category, tags) in closet {
+            let create = serde_json::json!({ "name": name, "category": category, "tags": tags });
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(create)).await;
+        }
+
+        let invalid = serde_json::json!({
+            "name": "time travel", "destination": "Kyoto",
+            "starts_on": "2023-07-03", "ends_on": "2023-07-01"
+        });
+        let (status, _) =
+            call_json::<TripDetail>(&api, Method::POST, "/trips", token, Some(invalid)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let create = serde_json::json!({
+            "name": "summer vacation", "destination": "Kyoto",
+            "starts_on": "2023-07-01", "ends_on": "2023-07-03", "activities": ["hiking"]
+        });
+        let (status, created) =
+            call_json::<TripDetail>(&api, Method::POST, "/trips", token, Some(create)).await;
+        assert_eq!(status, StatusCode::OK);
+        let created = created.result().unwrap();
+        assert_eq!(created.trip.days(), 3);
+        assert_eq!(created.activities, vec![Tag::parse("hiking").unwrap()]);
+        let other = signup(&api, "other").await;
+        let uri = format!("/trips/{}", created.trip.id);
+        let (status, _) =
+            call_json::<TripDetail>(&api, Method::GET, &uri, other.token.as_deref(), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let uri = format!("/trips/{}/packing", created.trip.id);
+        let generate = serde_json::json!({ "reuse": { "bottom": 2 } });
+        let (status, packing) =
+            call_json::<TripPacking>(&api, Method::POST, &uri, token, Some(generate)).await;
+        assert_eq!(status, StatusCode::OK);
+        let packing = packing.result().unwrap();
+        let mut packed: Vec<_> =
+            packing.entries.iter().map(|e| (e.item.name.as_str(), e.packing.days)).collect();
+        packed.sort();
+        assert_eq!(
+            packed,
+            vec![("black tee", 1), ("hiking boots", 3), ("jeans", 2), ("white tee", 1)]
+        );
+        let shortages = vec![
+            Shortage { category: Category::Top, days: 1 },
+            Shortage { category: Category::Bottom, days: 1 },
+        ];
+        assert_eq!(packing.shortages, shortages);
+
+        let check = serde_json::json!({ "packed": true });
+        let jeans = packing.entries.iter().find(|e| e.item.name == "jeans").unwrap();
+        let uri = format!("/trips/{}/packing/{}", created.trip.id, jeans.packing.id);
+        let (status, checked) =
+            call_json::<PackingEntry>(&api, Method::PUT, &uri, token, Some(check)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(checked.result().unwrap().packing.packed);
+
+        let uri = format!("/trips/{}/packing/export?format=csv", created.trip.id);
+        let (status, csv) =
+            call(&api, request(Method::GET, &uri, token).body(hyper::Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        let csv = String::from_utf8(csv.to_vec()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "name,category,brand,days,packed,reason");
+        assert!(lines.contains(&"jeans,bottom,,2,true,bottom for 2 of 3 days"));
+        assert!(lines.contains(&"white tee,top,,1,false,top for 1 of 3 days"));
+        assert_eq!(lines.len(), 5);
+
+        let uri = format!("/trips/{}/packing/export?format=text", created.trip.id);
+        let (_, text) =
+            call(&api, request(Method::GET, &uri, token).body(hyper::Body::empty()).unwrap()).await;
+        let text = String::from_utf8(text.to_vec()).unwrap();
+        assert!(text.starts_with("summer vacation (Kyoto)\n2023-07-01 to 2023-07-03, 3 days\n"));
+        assert!(text.contains("[x] jeans - bottom for 2 of 3 days\n"));
+        assert!(text.contains("[ ] hiking boots - for hiking\n"));
+
+        let uri = format!("/trips/{}/packing/export", created.trip.id);
+        let (_, json) =
+            call(&api, request(Method::GET, &uri, token).body(hyper::Body::empty()).unwrap()).await;
+        let exported: TripDetail = serde_json::from_slice(&json).unwrap();
+        assert_eq!(exported.packing.len(), 4);
+    }
+}
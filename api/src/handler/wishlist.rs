@@ -0,0 +1,381 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Router,
+};
+use chrono::{Datelike, NaiveDate};
+use entity::{
+    class::{category::Category, id::Id, money::Money, tag::Tag},
+    model::{
+        closet,
+        item::{self, InsertItem},
+        wish,
+    },
+};
+use hyper::StatusCode;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    ModelTrait, QueryFilter, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    item::ItemDetail,
+    json::Json,
+    listing::{Listed, Listing},
+};
+use crate::{
+    middleware::authorization::AuthUser,
+    response::{error::ApiError, result::ApiResponse, ApiPage, ApiResult},
+    AppState,
+};
+
+pub fn wishlist_router() -> Router<AppState> {
+    axum::Router::new()
+        .route("/", axum::routing::get(list).post(create))
+        .route("/:id", axum::routing::get(detail).put(update).delete(delete))
+        .route("/:id/purchase", axum::routing::post(purchase))
+}
+
+pub(crate) async fn find_owned<C: ConnectionTrait>(
+    db: &C,
+    user: &AuthUser,
+    id: Id<wish::Model>,
+) -> Result<wish::Model, ApiError> {
+    wish::Entity::find_by_id(id)
+        .filter(wish::Column::OwnerId.eq(user.user.id.clone()))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct WishCreate {
+    pub name: String,
+    pub link: Option<String>,
+    pub target_price: Option<Money>,
+    /// 1 to 5, defaults to 3
+    pub priority: Option<i32>,
+    pub category: Option<Category>,
+    pub gap: Option<String>,
+}
+impl WishCreate {
+    fn apply(self, active: &mut wish::ActiveModel) -> Result<(), ApiError> {
+        let priority = self.priority.unwrap_or(wish::Model::DEFAULT_PRIORITY);
+        active.priority = ActiveValue::Set(wish::Model::validate_priority(priority)?);
+        active.name = ActiveValue::Set(self.name);
+        active.link = ActiveValue::Set(self.link);
+        let target_price = super::item::validate_price("target_price", self.target_price)?;
+        active.set_target_price(target_price);
+        active.category = ActiveValue::Set(self.category);
+        active.gap = ActiveValue::Set(self.gap);
+        Ok(())
+    }
+}
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Json(schema): Json<WishCreate>,
+) -> ApiResult<wish::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut active = wish::ActiveModel {
+        owner_id: ActiveValue::Set(user.user.id),
+        item_id: ActiveValue::Set(None),
+        ..Default::default()
+    };
+    schema.apply(&mut active)?;
+    Ok(ApiResponse::Success(active.insert(&state.db).await?))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WishQuery {
+    /// include wishes already purchased
+    #[serde(default)]
+    pub all: bool,
+}
+impl Listed for wish::Entity {
+    type Entity = Self;
+    const ID: wish::Column = wish::Column::Id;
+    const SORTABLE: &'static [wish::Column] =
+        &[wish::Column::Priority, wish::Column::Name, wish::Column::CreatedAt];
+    const FILTERABLE: &'static [wish::Column] = &[wish::Column::Priority];
+    const DEFAULT_SORT: &'static str = "-priority";
+}
+/// Wishes in order of priority by default.
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<WishQuery>,
+    listing: Listing<wish::Entity>,
+) -> ApiPage<wish::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut select = wish::Entity::find().filter(wish::Column::OwnerId.eq(user.user.id));
+    if !query.all {
+        select = select.filter(wish::Column::ItemId.is_null());
+    }
+    Ok(ApiResponse::Success(listing.fetch(&state.db, select).await?))
+}
+
+pub async fn detail(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<wish::Model>>,
+) -> ApiResult<wish::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    Ok(ApiResponse::Success(find_owned(&state.db, &user, id).await?))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<wish::Model>>,
+    Json(schema): Json<WishCreate>,
+) -> ApiResult<wish::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let mut active = find_owned(&state.db, &user, id).await?.into_active_model();
+    schema.apply(&mut active)?;
+    Ok(ApiResponse::Success(active.update(&state.db).await?))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<wish::Model>>,
+) -> ApiResult<wish::Model> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let wish = find_owned(&state.db, &user, id).await?;
+    wish.clone().delete(&state.db).await?;
+    Ok(ApiResponse::Success(wish))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct WishPurchase {
+    pub price: Money,
+    /// defaults to today
+    pub purchased_on: Option<NaiveDate>,
+    /// defaults to the personal closet
+    pub closet_id: Option<Id<closet::Model>>,
+    pub brand: Option<String>,
+    pub warmth: Option<i32>,
+    #[serde(default)]
+    pub waterproof: bool,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Purchase {
+    pub wish: wish::Model,
+    pub item: ItemDetail,
+}
+/// Promote the wish into an item of the closet.
+pub async fn purchase(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Path(id): Path<Id<wish::Model>>,
+    Json(schema): Json<WishPurchase>,
+) -> ApiResult<Purchase> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let warmth = schema.warmth.unwrap_or(item::Model::DEFAULT_WARMTH);
+    let warmth = item::Model::validate_warmth(warmth)?;
+    let price = super::item::validate_price("price", Some(schema.price))?;
+    let txn = state.db.begin().await?;
+    let wish = find_owned(&txn, &user, id).await?;
+    if wish.is_purchased() {
+        Err((StatusCode::CONFLICT, anyhow::anyhow!("{} is already purchased", wish.name)))?
+    }
+    let closet_id = super::closet::writable_closet(&txn, &user, schema.closet_id).await?;
+    let insert_item = InsertItem {
+        owner_id: user.user.id.clone(),
+        closet_id,
+        name: wish.name.clone(),
+        category: wish.category,
+        brand: schema.brand,
+        notes: wish.gap.clone(),
+        warmth,
+        waterproof: schema.waterproof,
+    };
+    let mut active = insert_item.into_active_model();
+    let purchased_on = schema.purchased_on.unwrap_or_else(|| user.user.today());
+    active.set_purchase_price(price);
+    active.purchased_on = ActiveValue::Set(Some(purchased_on));
+    let created = active.insert(&txn).await?;
+    super::item::replace_tags(&txn, &created, schema.tags).await?;
+    let mut active = wish.into_active_model();
+    active.item_id = ActiveValue::Set(Some(created.id.clone()));
+    let wish = active.update(&txn).await?;
+    let mut details = ItemDetail::load(&txn, vec![created]).await?;
+    txn.commit().await?;
+    Ok(ApiResponse::Success(Purchase { wish, item: details.remove(0) }))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SpendingQuery {
+    /// defaults to this year
+    pub year: Option<i32>,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MonthlySpending {
+    /// 1 to 12
+    pub month: u32,
+    /// one total per currency
+    pub total: Vec<Money>,
+    pub purchases: usize,
+}
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SpendingSummary {
+    pub year: i32,
+    pub total: Vec<Money>,
+    pub months: Vec<MonthlySpending>,
+    /// target prices of the wishes not purchased yet
+    pub wishlist: Vec<Money>,
+}
+/// Spending on items the user added with a purchase price, by month of the year.
+pub async fn spending(
+    State(state): State<AppState>,
+    Extension(user): Extension<Option<AuthUser>>,
+    Query(query): Query<SpendingQuery>,
+) -> ApiResult<SpendingSummary> {
+    let user = user.ok_or(ApiError::LoginRequiredError)?;
+    let year = query.year.unwrap_or_else(|| user.user.today().year());
+    let first = NaiveDate::from_ymd_opt(year, 1, 1);
+    let last = NaiveDate::from_ymd_opt(year, 12, 31);
+    let (first, last) = first.zip(last).ok_or_else(|| {
+        (StatusCode::BAD_REQUEST, anyhow::anyhow!("year {} is out of range", year))
+    })?;
+    let purchases = item::Entity::find()
+        .filter(item::Column::OwnerId.eq(user.user.id.clone()))
+        .filter(item::Column::PurchasePriceAmount.is_not_null())
+        .filter(item::Column::PurchasedOn.between(first, last))
+        .all(&state.db)
+        .await?;
+    let prices: Vec<_> =
+        purchases.iter().filter_map(|i| Some((i.purchased_on, i.purchase_price()?))).collect();
+    let priced = |month: Option<u32>| {
+        prices
+            .iter()
+            .filter(move |(on, _)| month.is_none_or(|m| on.map(|d| d.month()) == Some(m)))
+            .map(|(_, price)| price)
+    };
+    let mut months = Vec::new();
+    for month in 1..=12 {
+        let total = Money::totals(priced(Some(month)))?;
+        months.push(MonthlySpending { month, total, purchases: priced(Some(month)).count() });
+    }
+    let wishes = wish::Entity::find()
+        .filter(wish::Column::OwnerId.eq(user.user.id))
+        .filter(wish::Column::ItemId.is_null())
+        .all(&state.db)
+        .await?;
+    let wishlist =
+        Money::totals(&wishes.iter().filter_map(|w| w.target_price()).collect::<Vec<_>>())?;
+    let total = Money::totals(priced(None))?;
+    Ok(ApiResponse::Success(SpendingSummary { year, total, months, wishlist }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_wishlist_scenario() {
+        use hyper::{Method, StatusCode};
+
+        use super::*;
+        use crate::response::page::Page;
+        use crate::with_auth;
+        use crate::{call_json, configuration::Configuration, signup};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let shopper = signup(&api, "shopper").await;
+        let token = shopper.token.as_deref();
+
+        let invalid = serde_json::json!({ "name": "boots", "priority": 9 });
+        let (status, _) =
+            call_json::<wish::Model>(&api, Method::POST, "/wishlist", token, Some(invalid)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let invalid = serde_json::json!({ "name": "boots", "target_price": "12.345 USD" });
+        let request = crate::request(Method::POST, "/wishlist", token)
+            .header(hyper::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+            .body(hyper::Body::from(invalid.to_string()))
+            .unwrap();
+        let (status, _) = crate::call(&api, request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "more digits than cents");
+        let invalid = serde_json::json!({ "name": "boots", "target_price": "-12.00 USD" });
+        let (_, failure) =
+            call_json::<wish::Model>(&api, Method::POST, "/wishlist", token, Some(invalid)).await;
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+        assert!(fields.0.contains_key("target_price"), "{:?}", fields);
+
+        let wishes = [
+            ("rain boots", "89.90 USD", 5, "shoes"),
+            ("linen shirt", "45.00 USD", 2, "top"),
+            ("wool scarf", "3500 JPY", 3, "accessory"),
+        ];
+        let mut ids = Vec::new();
+        for (name, price, priority, category) in wishes {
+            let create = serde_json::json!({
+                "name": name, "target_price": price, "priority": priority, "category": category,
+                "gap": "nothing for rainy days"
+            });
+            let (_, created) =
+                call_json::<wish::Model>(&api, Method::POST, "/wishlist", token, Some(create))
+                    .await;
+            ids.push(created.result().unwrap().id.clone());
+        }
+        let (_, listed) =
+            call_json::<Page<wish::Model>>(&api, Method::GET, "/wishlist", token, None).await;
+        let priorities: Vec<_> =
+            listed.result().unwrap().items.iter().map(|w| w.priority).collect();
+        assert_eq!(priorities, vec![5, 3, 2]);
+
+        let uri = format!("/wishlist/{}/purchase", ids[0]);
+        let refunded = serde_json::json!({ "price": "-79.99 USD" });
+        let (_, failure) =
+            call_json::<Purchase>(&api, Method::POST, &uri, token, Some(refunded)).await;
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+        assert!(fields.0.contains_key("price"), "{:?}", fields);
+        let bought = serde_json::json!({
+            "price": "79.99 USD", "purchased_on": "2023-06-10", "waterproof": true
+        });
+        let (status, purchase) =
+            call_json::<Purchase>(&api, Method::POST, &uri, token, Some(bought.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        let purchase = purchase.result().unwrap();
+        assert_eq!(purchase.wish.item_id.as_ref(), Some(&purchase.item.item.id));
+        assert_eq!(purchase.item.item.category, Some(Category::Shoes));
+        assert_eq!(purchase.item.item.purchase_price().unwrap().to_string(), "79.99 USD");
+        let (status, _) =
+            call_json::<Purchase>(&api, Method::POST, &uri, token, Some(bought)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let uri = format!("/wishlist/{}/purchase", ids[2]);
+        let bought = serde_json::json!({ "price": "2980 JPY", "purchased_on": "2023-06-20" });
+        call_json::<Purchase>(&api, Method::POST, &uri, token, Some(bought)).await;
+        let item = serde_json::json!({
+            "name": "belt", "purchase_price": "20.02 USD", "purchased_on": "2023-11-03"
+        });
+        call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(item)).await;
+        let refunded = serde_json::json!({ "name": "socks", "purchase_price": "-1.00 USD" });
+        let (_, failure) =
+            call_json::<ItemDetail>(&api, Method::POST, "/items", token, Some(refunded)).await;
+        let Err(ApiError::ValidationError { fields, .. }) = failure.result() else { panic!() };
+        assert!(fields.0.contains_key("purchase_price"), "{:?}", fields);
+
+        let (_, summary) =
+            call_json::<SpendingSummary>(&api, Method::GET, "/spending?year=2023", token, None)
+                .await;
+        let summary = summary.result().unwrap();
+        let money = |s| Money::parse(s).unwrap();
+        assert_eq!(summary.total, vec![money("2980 JPY"), money("100.01 USD")]);
+        assert_eq!(summary.months.len(), 12);
+        assert_eq!(summary.months[5].purchases, 2);
+        assert_eq!(summary.months[5].total, vec![money("2980 JPY"), money("79.99 USD")]);
+        assert_eq!(summary.months[10].total, vec![money("20.02 USD")]);
+        assert!(summary.months[0].total.is_empty());
+        assert_eq!(summary.wishlist, vec![money("45.00 USD")]);
+        let (_, other) =
+            call_json::<SpendingSummary>(&api, Method::GET, "/spending?year=2022", token, None)
+                .await;
+        assert!(other.result().unwrap().total.is_empty());
+    }
+}
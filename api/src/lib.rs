@@ -4,34 +4,71 @@ pub mod configuration;
 pub mod dev_debug;
 pub mod handler;
 pub mod middleware;
+pub mod openapi;
 pub mod response;
+pub mod service;
 
 pub fn api_router() -> axum::Router<AppState> {
-    axum::Router::new()
+    let router = axum::Router::new()
         .route("/", axum::routing::get(handler::health::health))
+        .route("/openapi.json", axum::routing::get(openapi::spec))
         .nest("/dev/debug", dev_debug::dev_debug_router())
         .nest("/health", handler::health::health_router())
+        .nest("/analytics", handler::analytics::analytics_router())
         .nest("/auth", handler::auth::auth_router())
-        .route("/*404", axum::routing::any(response::error::ApiError::handle_not_found))
+        .nest("/items", handler::item::item_router())
+        .route("/favorites", axum::routing::get(handler::feedback::favorites))
+        .nest("/laundry", handler::laundry::laundry_router())
+        .nest("/loans", handler::loan::loan_router())
+        .nest("/outfits", handler::outfit::outfit_router())
+        .nest("/profile", handler::profile::profile_router())
+        .nest("/search", handler::search::search_router())
+        .nest("/shares", handler::share::share_router())
+        .nest("/public", handler::share::public_router())
+        .nest("/storage", handler::storage::storage_router())
+        .nest("/trips", handler::trip::trip_router())
+        .nest("/calendar", handler::calendar::calendar_router())
+        .nest("/closets", handler::closet::closet_router())
+        .route("/calendar.ics", axum::routing::get(handler::calendar::feed))
+        .nest("/wishlist", handler::wishlist::wishlist_router())
+        .route("/spending", axum::routing::get(handler::wishlist::spending))
+        .route("/*404", axum::routing::any(response::error::ApiError::handle_not_found));
+    #[cfg(feature = "docs")]
+    let router = router.route("/docs", axum::routing::get(openapi::docs));
+    router
 }
 #[derive(Clone)]
 pub struct AppState {
     pub db: sea_orm::DatabaseConnection,
     pub configuration: configuration::Configuration,
+    pub weather: std::sync::Arc<dyn service::weather::WeatherProvider>,
 }
-pub async fn with_auth(
-    router: axum::Router<AppState>,
-    configuration: Configuration,
-) -> Result<axum::Router, sea_orm::DbErr> {
+/// State built from the configuration, whose database pool also serves the background tasks, e.g.
+/// [`handler::calendar::settle_periodically`].
+pub async fn app_state(configuration: Configuration) -> anyhow::Result<AppState> {
+    if configuration.settle_interval() <= chrono::Duration::zero() {
+        anyhow::bail!("{} must be positive", configuration::Config::SETTLE_INTERVAL);
+    }
     let db = sea_orm::Database::connect(configuration.database_url()).await?;
     if configuration.migrate() {
         use migration::{Migrator, MigratorTrait};
         Migrator::up(&db, None).await?;
     }
-    let state = AppState { db, configuration };
+    let weather = service::weather::from_config(&configuration)?;
+    Ok(AppState { db, configuration, weather })
+}
+
+pub async fn with_auth(
+    router: axum::Router<AppState>,
+    configuration: Configuration,
+) -> anyhow::Result<axum::Router> {
+    Ok(with_state(router, app_state(configuration).await?))
+}
+
+pub fn with_state(router: axum::Router<AppState>, state: AppState) -> axum::Router {
     let timeout = state.clone().configuration.timeout().to_std().unwrap(); // TODO error handling
 
-    Ok(axum::Router::new()
+    axum::Router::new()
         .nest(&state.configuration.base_url(), router)
         .with_state(state.clone())
         .layer(axum::middleware::from_fn_with_state(state, middleware::authorization::verification))
@@ -42,7 +79,9 @@ pub async fn with_auth(
                 ))
                 .timeout(timeout),
         )
-        .layer(axum::middleware::from_fn(middleware::logging::request_log)))
+        .layer(axum::middleware::from_fn(middleware::problem::negotiate))
+        .layer(axum::middleware::from_fn(middleware::logging::request_log))
+        .layer(axum::middleware::from_fn(middleware::request_id::request_id))
 }
 
 #[cfg(all(test, feature = "sqlite"))]
@@ -54,9 +93,77 @@ pub fn standalone() -> configuration::Config {
         secret_key: Some(Alphanumeric.sample_string(&mut rand::thread_rng(), 1024)),
         migrate: Some(true),
         timeout: Some("1d".into()),
+        weather_provider: Some(service::weather::FixtureWeather::NAME.into()),
         ..Default::default()
     }
 }
+#[cfg(all(test, feature = "sqlite"))]
+pub fn request(
+    method: hyper::Method,
+    uri: &str,
+    token: Option<&str>,
+) -> hyper::http::request::Builder {
+    let request = hyper::Request::builder().method(method).uri(uri);
+    match token {
+        Some(token) => request.header(hyper::header::AUTHORIZATION, format!("Bearer {}", token)),
+        None => request,
+    }
+}
+#[cfg(all(test, feature = "sqlite"))]
+pub async fn call(
+    api: &axum::Router,
+    request: hyper::Request<hyper::Body>,
+) -> (hyper::StatusCode, hyper::body::Bytes) {
+    use tower::ServiceExt;
+
+    let response = api.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    (status, hyper::body::to_bytes(response.into_body()).await.unwrap())
+}
+#[cfg(all(test, feature = "sqlite"))]
+pub async fn call_json<T: serde::de::DeserializeOwned>(
+    api: &axum::Router,
+    method: hyper::Method,
+    uri: &str,
+    token: Option<&str>,
+    json: Option<serde_json::Value>,
+) -> (hyper::StatusCode, response::result::ApiResponse<T>) {
+    let request = request(method, uri, token);
+    let request = match json {
+        Some(json) => request
+            .header(hyper::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+            .body(hyper::Body::from(json.to_string())),
+        None => request.body(hyper::Body::empty()),
+    };
+    let (status, bytes) = call(api, request.unwrap()).await;
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+#[cfg(all(test, feature = "sqlite"))]
+pub async fn signup(api: &axum::Router, username: &str) -> middleware::authorization::AuthUser {
+    use handler::auth::{UserCreate, UserLogin};
+
+    let (email, password) = (format!("{}@closet.test", username), format!("{}_password", username));
+    let create = UserCreate {
+        email,
+        username: username.into(),
+        password: password.clone(),
+        display_name: username.into(),
+    };
+    let uri = "/auth/create";
+    call_json::<serde_json::Value>(
+        api,
+        hyper::Method::POST,
+        uri,
+        None,
+        Some(serde_json::json!(create)),
+    )
+    .await;
+    let login = UserLogin { username: username.into(), password };
+    let uri = "/auth/login";
+    let (_, auth_user) =
+        call_json(api, hyper::Method::POST, uri, None, Some(serde_json::json!(login))).await;
+    Into::<Result<_, _>>::into(auth_user).unwrap()
+}
 
 #[cfg(test)]
 mod tests {
@@ -75,6 +182,7 @@ mod tests {
         let api = api_router().with_state(AppState {
             db: DatabaseConnection::Disconnected,
             configuration: Configuration::new(Default::default()),
+            weather: std::sync::Arc::new(service::weather::FixtureWeather::builtin()),
         });
         let request = Request::builder().uri(uri).body(body).unwrap();
         let response = api.oneshot(request).await.unwrap();
@@ -109,6 +217,17 @@ mod tests {
         assert_eq!(health.result().unwrap(), &RichHealth { status: Status::Ok });
     }
 
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_settle_interval_must_be_positive() {
+        let configuration = Configuration::new(configuration::Config {
+            settle_interval: Some("0s".into()),
+            ..standalone()
+        });
+        let error = with_auth(api_router(), configuration).await.err().unwrap();
+        assert_eq!(error.to_string(), "SETTLE_INTERVAL must be positive");
+    }
+
     #[tokio::test]
     #[cfg(feature = "sqlite")]
     async fn test_login_logout_scenario() {
@@ -268,4 +387,180 @@ mod tests {
             serde_json::from_slice(&to_bytes(no_auth_response.into_body()).await.unwrap()).unwrap();
         assert!(matches!(no_auth_whoami.result().unwrap(), None));
     }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_migrated_schema_matches_entities() {
+        use entity::model::*;
+        use migration::{Migrator, MigratorTrait};
+        use sea_orm::{ConnectionTrait, Database, DbBackend, QueryResult, Schema, Statement};
+
+        async fn pragma(db: &DatabaseConnection, sql: &str, table: &str) -> Vec<String> {
+            let statement =
+                Statement::from_sql_and_values(DbBackend::Sqlite, sql, [table.to_string().into()]);
+            let rows: Vec<QueryResult> = db.query_all(statement).await.unwrap();
+            let cell = |row: &QueryResult, i: usize| {
+                row.try_get_by_index::<Option<String>>(i)
+                    .map(Option::unwrap_or_default)
+                    .unwrap_or_else(|_| row.try_get_by_index::<i32>(i).unwrap().to_string())
+            };
+            let mut rows: Vec<_> = rows
+                .iter()
+                .map(|row| (0..4).map(|i| cell(row, i)).collect::<Vec<_>>().join(" "))
+                .collect();
+            rows.sort();
+            rows
+        }
+
+        let migrated = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&migrated, None).await.unwrap();
+        let declared = Database::connect("sqlite::memory:").await.unwrap();
+        let schema = Schema::new(DbBackend::Sqlite);
+        let tables = [
+            schema.create_table_from_entity(user::Entity),
+            schema.create_table_from_entity(health::Entity),
+            schema.create_table_from_entity(item::Entity),
+            schema.create_table_from_entity(item_image::Entity),
+            schema.create_table_from_entity(item_color::Entity),
+            schema.create_table_from_entity(item_transition::Entity),
+            schema.create_table_from_entity(item_tag::Entity),
+            schema.create_table_from_entity(item_wear::Entity),
+            schema.create_table_from_entity(outfit::Entity),
+            schema.create_table_from_entity(outfit_item::Entity),
+            schema.create_table_from_entity(trip::Entity),
+            schema.create_table_from_entity(trip_activity::Entity),
+            schema.create_table_from_entity(packing_item::Entity),
+            schema.create_table_from_entity(calendar_token::Entity),
+            schema.create_table_from_entity(planned_outfit::Entity),
+            schema.create_table_from_entity(closet::Entity),
+            schema.create_table_from_entity(closet_member::Entity),
+            schema.create_table_from_entity(closet_invitation::Entity),
+            schema.create_table_from_entity(loan::Entity),
+            schema.create_table_from_entity(wish::Entity),
+            schema.create_table_from_entity(storage::Entity),
+            schema.create_table_from_entity(measurement::Entity),
+            schema.create_table_from_entity(share_link::Entity),
+            schema.create_table_from_entity(favorite::Entity),
+            schema.create_table_from_entity(outfit_wear::Entity),
+            schema.create_table_from_entity(outfit_wear_tag::Entity),
+        ];
+        for table in tables {
+            declared.execute(DbBackend::Sqlite.build(&table)).await.unwrap();
+            let name = match table.get_table_name() {
+                Some(sea_orm::sea_query::TableRef::Table(name)) => name.to_string(),
+                other => panic!("{:?}", other),
+            };
+            // name, type, notnull and pk of the columns, then the foreign keys
+            for sql in [
+                "SELECT name, type, \"notnull\", pk FROM pragma_table_info(?)",
+                "SELECT \"from\", \"table\", \"to\", on_delete FROM pragma_foreign_key_list(?)",
+            ] {
+                let (migrated, declared) =
+                    (pragma(&migrated, sql, &name).await, pragma(&declared, sql, &name).await);
+                assert_eq!(migrated, declared, "{}", name);
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_migrate_to_personal_closets() {
+        use entity::{
+            class::{id::Id, role::Role},
+            model::{closet, closet_member, item, item_wear, user},
+        };
+        use migration::{Migrator, MigratorTrait};
+        use sea_orm::{sea_query::Query, ConnectionTrait, Database, EntityTrait};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let migrations = Migrator::migrations();
+        let closets = migrations.iter().position(|m| m.name().ends_with("_create_closet_table"));
+        Migrator::up(&db, closets.map(|p| p as u32)).await.unwrap();
+
+        let (user_id, item_id) = (Id::<user::Model>::create(), Id::<item::Model>::create());
+        let now = chrono::Utc::now().fixed_offset();
+        let insert_user = Query::insert()
+            .into_table(user::Entity)
+            .columns([
+                user::Column::Id,
+                user::Column::Username,
+                user::Column::Email,
+                user::Column::Password,
+                user::Column::DisplayName,
+                user::Column::Confirmed,
+                user::Column::IsActive,
+                user::Column::CreatedAt,
+                user::Column::UpdatedAt,
+            ])
+            .values_panic([
+                user_id.clone().into(),
+                "before".into(),
+                "before@closet.test".into(),
+                "hashed".into(),
+                "Before".into(),
+                true.into(),
+                true.into(),
+                now.into(),
+                now.into(),
+            ])
+            .to_owned();
+        db.execute(db.get_database_backend().build(&insert_user)).await.unwrap();
+        let insert_item = Query::insert()
+            .into_table(item::Entity)
+            .columns([
+                item::Column::Id,
+                item::Column::OwnerId,
+                item::Column::Name,
+                item::Column::Warmth,
+                item::Column::Waterproof,
+                item::Column::Availability,
+                item::Column::CreatedAt,
+                item::Column::UpdatedAt,
+            ])
+            .values_panic([
+                item_id.clone().into(),
+                user_id.clone().into(),
+                "coat".into(),
+                3.into(),
+                false.into(),
+                "clean".into(),
+                now.into(),
+                now.into(),
+            ])
+            .to_owned();
+        db.execute(db.get_database_backend().build(&insert_item)).await.unwrap();
+        let insert_wear = Query::insert()
+            .into_table(item_wear::Entity)
+            .columns([
+                item_wear::Column::Id,
+                item_wear::Column::ItemId,
+                item_wear::Column::WornOn,
+                item_wear::Column::CreatedAt,
+            ])
+            .values_panic([
+                Id::<item_wear::Model>::create().into(),
+                item_id.clone().into(),
+                now.date_naive().into(),
+                now.into(),
+            ])
+            .to_owned();
+        db.execute(db.get_database_backend().build(&insert_wear)).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let item = item::Entity::find_by_id(item_id).one(&db).await.unwrap().unwrap();
+        let closet = closet::Entity::find_by_id(item.closet_id).one(&db).await.unwrap().unwrap();
+        assert!(closet.personal);
+        assert_eq!(closet.name, "Before's closet");
+        let members = closet_member::Entity::find().all(&db).await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!((&members[0].user_id, members[0].role), (&user_id, Role::Owner));
+        // the rebuilt items kept what refers to them
+        assert_eq!(item_wear::Entity::find().all(&db).await.unwrap().len(), 1);
+
+        // down to before closets and up again
+        let steps = migrations.len() - closets.unwrap();
+        Migrator::down(&db, Some(steps as u32)).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        assert_eq!(item_wear::Entity::find().all(&db).await.unwrap().len(), 1);
+    }
 }
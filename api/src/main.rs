@@ -1,4 +1,4 @@
-use api::{api_router, configuration::Configuration, with_auth};
+use api::{api_router, app_state, configuration::Configuration, with_state};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -6,9 +6,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::subscriber::set_global_default(subscriber)?;
 
     let configuration = Configuration::new(Default::default());
+    let state = app_state(configuration.clone()).await?;
+    let period = configuration.settle_interval().to_std()?;
+    tokio::spawn(api::handler::calendar::settle_periodically(state.db.clone(), period));
+
     let (router, bind) = (api_router(), configuration.address());
     let app = axum::Server::bind(&bind)
-        .serve(with_auth(router, configuration.clone()).await?.into_make_service())
+        .serve(with_state(router, state).into_make_service())
         .with_graceful_shutdown(async {
             tokio::signal::ctrl_c().await.expect("expect tokio signal ctrl-c");
             tracing::info!("stopping app...");
@@ -8,6 +8,7 @@ use entity::{
 };
 use hyper::{header, http::HeaderValue, HeaderMap, Request};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
     QueryFilter,
@@ -34,7 +35,7 @@ pub struct TokenClaims {
     pub exp: i64,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AuthUser {
     pub token: Option<String>,
     pub user: user::Model,
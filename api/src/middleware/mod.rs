@@ -1,2 +1,4 @@
 pub mod authorization;
 pub mod logging;
+pub mod problem;
+pub mod request_id;
@@ -0,0 +1,65 @@
+use axum::response::{IntoResponse, Response};
+use hyper::Request;
+
+use crate::response::problem::Problem;
+
+/// Answer errors as `application/problem+json` to the clients accepting it,
+/// others keep receiving the `failure` envelope.
+pub async fn negotiate<B>(req: Request<B>, next: axum::middleware::Next<B>) -> Response {
+    let accepted = Problem::accepted(req.headers());
+    let mut response = next.run(req).await;
+    match response.extensions_mut().remove::<Problem>() {
+        Some(problem) if accepted => problem.into_response(),
+        _ => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_negotiate() {
+        use hyper::{header, Method, StatusCode};
+
+        use crate::{
+            call,
+            configuration::Configuration,
+            request,
+            response::problem::{ErrorCode, APPLICATION_PROBLEM_JSON},
+            with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let login = || {
+            request(Method::POST, "/auth/login", None)
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(crate::middleware::request_id::X_REQUEST_ID, "req-1")
+        };
+        let body = || hyper::Body::from(r#"{"username":"nobody","password":"secret"}"#);
+
+        let (status, bytes) = call(&api, login().body(body()).unwrap()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let envelope = r#"{"failure":"login_fail_error","request_id":"req-1"}"#;
+        assert_eq!(bytes, envelope, "envelope by default");
+
+        let accept = login().header(header::ACCEPT, APPLICATION_PROBLEM_JSON);
+        let response = tower::ServiceExt::oneshot(api.clone(), accept.body(body()).unwrap());
+        let response = response.await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], APPLICATION_PROBLEM_JSON);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let problem: super::Problem = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(problem.code, ErrorCode::LoginFailed);
+        assert_eq!(problem.kind, "urn:closet:error:login_failed");
+        assert_eq!((problem.status, problem.instance.as_deref()), (403, Some("req-1")));
+
+        let items = request(Method::GET, "/items", None)
+            .header(header::ACCEPT, APPLICATION_PROBLEM_JSON)
+            .body(hyper::Body::empty());
+        let (status, bytes) = call(&api, items.unwrap()).await;
+        let problem: super::Problem = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!((status, problem.code), (StatusCode::FORBIDDEN, ErrorCode::LoginRequired));
+        assert_eq!(problem.detail.as_deref(), Some("login required"));
+    }
+}
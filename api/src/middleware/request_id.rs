@@ -0,0 +1,83 @@
+use axum::response::Response;
+use entity::class::id::Id;
+use hyper::{http::HeaderValue, HeaderMap, Request};
+use tracing::Instrument;
+
+pub const X_REQUEST_ID: &str = "x-request-id";
+
+tokio::task_local! {
+    /// id of the request being handled, for error bodies built far from the request
+    static REQUEST_ID: String;
+}
+
+/// Accept the `X-Request-Id` of the client or generate one, and carry it through the request:
+/// in a span around every log line, in the response header and in error bodies.
+pub async fn request_id<B>(mut req: Request<B>, next: axum::middleware::Next<B>) -> Response {
+    let id = accepted(req.headers()).unwrap_or_else(|| Id::<()>::create().to_string());
+    let value = HeaderValue::from_str(&id).expect("accepted or generated ids are visible ascii");
+    req.headers_mut().insert(X_REQUEST_ID, value.clone());
+
+    let span = tracing::info_span!("request", request_id = %id);
+    let mut response = REQUEST_ID.scope(id, next.run(req).instrument(span)).await;
+    response.headers_mut().insert(X_REQUEST_ID, value);
+    response
+}
+
+/// id of the current request, if in one
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+fn accepted(headers: &HeaderMap) -> Option<String> {
+    let id = headers.get(X_REQUEST_ID)?.to_str().ok()?;
+    let valid = (1..=128).contains(&id.len()) && id.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_request_id() {
+        use hyper::{header, Method, StatusCode};
+        use tower::ServiceExt;
+
+        use super::X_REQUEST_ID;
+        use crate::{
+            configuration::Configuration, request, response::problem::APPLICATION_PROBLEM_JSON,
+            with_auth,
+        };
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let call = |request: hyper::http::request::Builder| async {
+            let response = api.clone().oneshot(request.body(hyper::Body::empty()).unwrap());
+            let response = response.await.unwrap();
+            let id = response.headers()[X_REQUEST_ID].to_str().unwrap().to_string();
+            let (status, body) = (response.status(), response.into_body());
+            let body: serde_json::Value =
+                serde_json::from_slice(&hyper::body::to_bytes(body).await.unwrap()).unwrap();
+            (status, id, body)
+        };
+
+        let (status, generated, body) = call(request(Method::GET, "/health", None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(generated.len(), 36, "uuid {}", generated);
+        assert_eq!(body.get("request_id"), None, "only errors carry it");
+        let (_, other, _) = call(request(Method::GET, "/health", None)).await;
+        assert_ne!(generated, other);
+
+        let given = request(Method::GET, "/items", None).header(X_REQUEST_ID, "trace-42");
+        let (status, id, body) = call(given).await;
+        assert_eq!((status, id.as_str()), (StatusCode::FORBIDDEN, "trace-42"));
+        assert_eq!(body["failure"], "login_required_error");
+        assert_eq!(body["request_id"], "trace-42");
+
+        let problem = request(Method::GET, "/items", None)
+            .header(X_REQUEST_ID, "trace 43")
+            .header(header::ACCEPT, APPLICATION_PROBLEM_JSON);
+        let (_, id, body) = call(problem).await;
+        assert_ne!(id, "trace 43", "ids with spaces are replaced");
+        assert_eq!(body["instance"], id);
+    }
+}
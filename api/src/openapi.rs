@@ -0,0 +1,797 @@
+use std::collections::BTreeMap;
+
+use axum::extract::State;
+use entity::{
+    class::status::Status,
+    model::{
+        closet, closet_invitation, closet_member, favorite, item, item_transition, item_wear,
+        outfit, planned_outfit, storage, trip, user, wish,
+    },
+};
+use schemars::{generate::SchemaSettings, JsonSchema, Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    handler::{
+        analytics::{AnalyticsQuery, CategorySpend, ColorCount, SeasonCount, Utilisation},
+        auth::{UserCreate, UserLogin},
+        bulk::{ImportReport, ItemExportQuery, ItemImport},
+        calendar::{CalendarFeed, FeedQuery, MonthQuery, OutfitPlan, PlannedOutfit},
+        closet::{ClosetCreate, ClosetDetail, ClosetInvite, InvitationDetail, RoleChange},
+        duplicate::{Duplicate, DuplicateQuery, ItemMerge},
+        feedback::{Favorites, OutfitWear, OutfitWearCreate, WearFeedback},
+        health::RichHealth,
+        item::{ItemCreate, ItemDetail, ItemQuery, ItemTransition, ItemWear},
+        laundry::LaundryPlan,
+        loan::{LoanCreate, LoanDetail, LoanQuery, ReturnConfirm},
+        outfit::{OutfitCreate, OutfitDetail, OutfitSuggestion, SuggestQuery},
+        profile::{
+            ForecastQuery, HomeUpdate, MeasurementQuery, Measurements, Preferences,
+            PreferencesUpdate, SizeQuery,
+        },
+        retirement::{DeclutterCandidate, DeclutterQuery, ItemRetire, RetiredQuery},
+        search::{SearchQuery, SearchResult},
+        share::{ShareCreate, ShareLink, SharedView},
+        storage::{
+            Rotation, RotationQuery, StorageCreate, StorageDetail, StorageItems, StorageQuery,
+            StorageSummary,
+        },
+        trip::{
+            ExportQuery, PackingCheck, PackingEntry, PackingGenerate, TripCreate, TripDetail,
+            TripPacking,
+        },
+        wishlist::{Purchase, SpendingQuery, SpendingSummary, WishCreate, WishPurchase, WishQuery},
+    },
+    middleware::authorization::AuthUser,
+    response::{
+        error::ApiError,
+        page::Page,
+        problem::{Problem, APPLICATION_PROBLEM_JSON},
+    },
+    service::{
+        analytics::{Concentration, MonthlyCount},
+        sizing::BrandSize,
+        weather::Forecast,
+    },
+    AppState,
+};
+
+/// who may call an operation
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Access {
+    Public,
+    /// the bearer token is optional, e.g. `whoami`
+    Optional,
+    Login,
+}
+
+/// schema of a type, a `$ref` into the components unless the type is inlined
+pub type Describe = fn(&mut SchemaGenerator) -> Schema;
+
+/// An operation of `api_router`, documented by the types of its json bodies.
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    pub method: &'static str,
+    /// relative to the base url, with path parameters as `{name}`
+    pub path: &'static str,
+    pub summary: &'static str,
+    pub access: Access,
+    pub body: Option<Describe>,
+    /// object whose properties are the query parameters
+    pub query: Option<Describe>,
+    /// `success` of the json envelope
+    pub data: Option<Describe>,
+    /// content type of the response if it is not the json envelope
+    pub content: Option<&'static str>,
+}
+impl Operation {
+    const fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            access: Access::Login,
+            body: None,
+            query: None,
+            data: None,
+            content: None,
+        }
+    }
+    const fn public(self) -> Self {
+        Self { access: Access::Public, ..self }
+    }
+    const fn optional(self) -> Self {
+        Self { access: Access::Optional, ..self }
+    }
+    const fn body<T: JsonSchema>(self) -> Self {
+        Self { body: Some(SchemaGenerator::subschema_for::<T>), ..self }
+    }
+    const fn query<T: JsonSchema>(self) -> Self {
+        Self { query: Some(T::json_schema), ..self }
+    }
+    const fn data<T: JsonSchema>(self) -> Self {
+        Self { data: Some(SchemaGenerator::subschema_for::<T>), ..self }
+    }
+    const fn content(self, content_type: &'static str) -> Self {
+        Self { content: Some(content_type), ..self }
+    }
+
+    pub fn tag(&self) -> &'static str {
+        let segment = self.path.trim_start_matches('/').split(['/', '.']).next();
+        segment.filter(|s| !s.is_empty()).unwrap_or("health")
+    }
+    pub fn parameters(&self) -> impl Iterator<Item = &'static str> {
+        self.path.split('/').filter_map(|s| s.strip_prefix('{')?.strip_suffix('}'))
+    }
+    pub fn operation_id(&self) -> String {
+        let segments = self.path.split(['/', '.', '{', '}']).filter(|s| !s.is_empty());
+        std::iter::once(self.method).chain(segments).collect::<Vec<_>>().join("_")
+    }
+}
+
+/// every operation routed by `api_router`, keep in sync with the routers
+pub const OPERATIONS: &[Operation] = &[
+    Operation::new("get", "/", "health check").public().data::<Status>(),
+    Operation::new("get", "/openapi.json", "this document").public().content("application/json"),
+    Operation::new("get", "/dev/debug/wait/{time}", "wait for a duration").public(),
+    Operation::new("get", "/health", "health check").public().data::<Status>(),
+    Operation::new("get", "/health/rich", "health check with database")
+        .public()
+        .data::<RichHealth>(),
+    Operation::new("get", "/analytics/added", "items added per month")
+        .query::<AnalyticsQuery>()
+        .data::<Vec<MonthlyCount>>(),
+    Operation::new("get", "/analytics/spend", "spending per month")
+        .query::<AnalyticsQuery>()
+        .data::<Vec<CategorySpend>>(),
+    Operation::new("get", "/analytics/colors", "color distribution")
+        .query::<AnalyticsQuery>()
+        .data::<Vec<ColorCount>>(),
+    Operation::new("get", "/analytics/utilisation", "cost per wear and idle items")
+        .query::<AnalyticsQuery>()
+        .data::<Utilisation>(),
+    Operation::new("get", "/analytics/seasons", "season coverage")
+        .query::<AnalyticsQuery>()
+        .data::<Vec<SeasonCount>>(),
+    Operation::new("get", "/analytics/brands", "brand concentration")
+        .query::<AnalyticsQuery>()
+        .data::<Concentration>(),
+    Operation::new("post", "/auth/create", "sign up")
+        .public()
+        .body::<UserCreate>()
+        .data::<AuthUser>(),
+    Operation::new("post", "/auth/login", "issue a token")
+        .public()
+        .body::<UserLogin>()
+        .data::<AuthUser>(),
+    Operation::new("get", "/auth/whoami", "the logged in user, if any")
+        .optional()
+        .data::<Option<AuthUser>>(),
+    Operation::new("post", "/auth/logout", "invalidate issued tokens").data::<AuthUser>(),
+    Operation::new("post", "/auth/deactivate", "deactivate the account").data::<AuthUser>(),
+    Operation::new("get", "/items", "list items").query::<ItemQuery>().data::<Page<ItemDetail>>(),
+    Operation::new("post", "/items", "create an item").body::<ItemCreate>().data::<ItemDetail>(),
+    Operation::new("post", "/items/import", "import items from csv or json")
+        .body::<ItemImport>()
+        .data::<ImportReport>(),
+    Operation::new("get", "/items/export", "export items")
+        .query::<ItemExportQuery>()
+        .content("text/csv"),
+    Operation::new("get", "/items/retired", "list retired items")
+        .query::<RetiredQuery>()
+        .data::<Page<ItemDetail>>(),
+    Operation::new("get", "/items/declutter", "suggest items to retire")
+        .query::<DeclutterQuery>()
+        .data::<Vec<DeclutterCandidate>>(),
+    Operation::new("get", "/items/duplicates", "find likely duplicate items")
+        .query::<DuplicateQuery>()
+        .data::<Vec<Duplicate>>(),
+    Operation::new("get", "/items/{id}", "item detail").data::<ItemDetail>(),
+    Operation::new("put", "/items/{id}", "update an item")
+        .body::<ItemCreate>()
+        .data::<ItemDetail>(),
+    Operation::new("delete", "/items/{id}", "delete an item").data::<item::Model>(),
+    Operation::new("post", "/items/{id}/images", "upload an image").data::<ItemDetail>(),
+    Operation::new("get", "/items/{id}/images/{image_id}", "download an image").content("image/*"),
+    Operation::new("post", "/items/{id}/transition", "change the state of an item")
+        .body::<ItemTransition>()
+        .data::<ItemDetail>(),
+    Operation::new("get", "/items/{id}/transitions", "state history of an item")
+        .data::<Page<item_transition::Model>>(),
+    Operation::new("post", "/items/{id}/wear", "record a wear")
+        .body::<ItemWear>()
+        .data::<ItemDetail>(),
+    Operation::new("get", "/items/{id}/wears", "wear history of an item")
+        .data::<Page<item_wear::Model>>(),
+    Operation::new("post", "/items/{id}/retire", "retire an item")
+        .body::<ItemRetire>()
+        .data::<ItemDetail>(),
+    Operation::new("delete", "/items/{id}/retire", "restore a retired item").data::<ItemDetail>(),
+    Operation::new("post", "/items/{id}/merge", "merge duplicates into an item")
+        .body::<ItemMerge>()
+        .data::<ItemDetail>(),
+    Operation::new("put", "/items/{id}/favorite", "favorite an item").data::<favorite::Model>(),
+    Operation::new("delete", "/items/{id}/favorite", "unfavorite an item")
+        .data::<favorite::Model>(),
+    Operation::new("get", "/favorites", "favorite items and outfits").data::<Favorites>(),
+    Operation::new("get", "/laundry/plan", "plan laundry loads").data::<LaundryPlan>(),
+    Operation::new("post", "/loans", "lend an item").body::<LoanCreate>().data::<LoanDetail>(),
+    Operation::new("get", "/loans/lent", "items lent to others")
+        .query::<LoanQuery>()
+        .data::<Page<LoanDetail>>(),
+    Operation::new("get", "/loans/borrowed", "items borrowed from others")
+        .query::<LoanQuery>()
+        .data::<Page<LoanDetail>>(),
+    Operation::new("get", "/loans/{id}", "loan detail").data::<LoanDetail>(),
+    Operation::new("post", "/loans/{id}/return", "confirm a return")
+        .body::<ReturnConfirm>()
+        .data::<LoanDetail>(),
+    Operation::new("get", "/outfits", "list outfits").data::<Page<OutfitDetail>>(),
+    Operation::new("post", "/outfits", "create an outfit")
+        .body::<OutfitCreate>()
+        .data::<OutfitDetail>(),
+    Operation::new("get", "/outfits/suggest", "suggest outfits for the weather")
+        .query::<SuggestQuery>()
+        .data::<Vec<OutfitSuggestion>>(),
+    Operation::new("get", "/outfits/{id}", "outfit detail").data::<OutfitDetail>(),
+    Operation::new("delete", "/outfits/{id}", "delete an outfit").data::<outfit::Model>(),
+    Operation::new("post", "/outfits/{id}/wear", "record a wear with feedback")
+        .body::<OutfitWearCreate>()
+        .data::<OutfitWear>(),
+    Operation::new("get", "/outfits/{id}/wears", "wear history of an outfit")
+        .data::<Page<OutfitWear>>(),
+    Operation::new("put", "/outfits/{id}/wears/{wear_id}", "rate a wear")
+        .body::<WearFeedback>()
+        .data::<OutfitWear>(),
+    Operation::new("put", "/outfits/{id}/favorite", "favorite an outfit").data::<favorite::Model>(),
+    Operation::new("delete", "/outfits/{id}/favorite", "unfavorite an outfit")
+        .data::<favorite::Model>(),
+    Operation::new("get", "/profile", "profile of the user").data::<user::Model>(),
+    Operation::new("put", "/profile/home", "set the home location")
+        .body::<HomeUpdate>()
+        .data::<user::Model>(),
+    Operation::new("get", "/profile/preferences", "time zone, locale, units and week start")
+        .data::<Preferences>(),
+    Operation::new("put", "/profile/preferences", "update preferences")
+        .body::<PreferencesUpdate>()
+        .data::<Preferences>(),
+    Operation::new("get", "/profile/forecast", "weather forecast at home")
+        .query::<ForecastQuery>()
+        .data::<Forecast>(),
+    Operation::new("get", "/profile/measurements", "body measurements")
+        .query::<MeasurementQuery>()
+        .data::<Measurements>(),
+    Operation::new("put", "/profile/measurements", "update body measurements")
+        .body::<Measurements>()
+        .data::<Measurements>(),
+    Operation::new("get", "/profile/sizes", "sizes derived from measurements")
+        .query::<SizeQuery>()
+        .data::<Vec<BrandSize>>(),
+    Operation::new("get", "/search", "search items and outfits")
+        .query::<SearchQuery>()
+        .data::<SearchResult>(),
+    Operation::new("get", "/shares", "list share links").data::<Vec<ShareLink>>(),
+    Operation::new("post", "/shares", "create a share link")
+        .body::<ShareCreate>()
+        .data::<ShareLink>(),
+    Operation::new("delete", "/shares/{id}", "revoke a share link").data::<ShareLink>(),
+    Operation::new("get", "/public/{token}", "view a shared outfit or closet")
+        .public()
+        .data::<SharedView>(),
+    Operation::new("get", "/public/{token}/images/{image_id}", "download a shared image")
+        .public()
+        .content("image/*"),
+    Operation::new("get", "/storage", "list storages")
+        .query::<StorageQuery>()
+        .data::<Vec<StorageSummary>>(),
+    Operation::new("post", "/storage", "create a storage")
+        .body::<StorageCreate>()
+        .data::<storage::Model>(),
+    Operation::new("get", "/storage/rotation", "seasonal rotation plan")
+        .query::<RotationQuery>()
+        .data::<Rotation>(),
+    Operation::new("get", "/storage/{id}", "storage detail").data::<StorageDetail>(),
+    Operation::new("put", "/storage/{id}", "update a storage")
+        .body::<StorageCreate>()
+        .data::<StorageDetail>(),
+    Operation::new("delete", "/storage/{id}", "delete a storage").data::<storage::Model>(),
+    Operation::new("post", "/storage/{id}/items", "store items")
+        .body::<StorageItems>()
+        .data::<StorageDetail>(),
+    Operation::new("delete", "/storage/{id}/items/{item_id}", "take an item out")
+        .data::<ItemDetail>(),
+    Operation::new("get", "/storage/{id}/manifest", "printable manifest")
+        .query::<ExportQuery>()
+        .content("text/plain"),
+    Operation::new("get", "/trips", "list trips").data::<Page<TripDetail>>(),
+    Operation::new("post", "/trips", "create a trip").body::<TripCreate>().data::<TripDetail>(),
+    Operation::new("get", "/trips/{id}", "trip detail").data::<TripDetail>(),
+    Operation::new("delete", "/trips/{id}", "delete a trip").data::<trip::Model>(),
+    Operation::new("get", "/trips/{id}/packing", "packing list").data::<Vec<PackingEntry>>(),
+    Operation::new("post", "/trips/{id}/packing", "generate a packing list")
+        .body::<PackingGenerate>()
+        .data::<TripPacking>(),
+    Operation::new("get", "/trips/{id}/packing/export", "export a packing list")
+        .query::<ExportQuery>()
+        .content("text/plain"),
+    Operation::new("put", "/trips/{id}/packing/{packing_id}", "check a packing entry")
+        .body::<PackingCheck>()
+        .data::<PackingEntry>(),
+    Operation::new("get", "/calendar", "planned outfits of a month")
+        .query::<MonthQuery>()
+        .data::<Vec<PlannedOutfit>>(),
+    Operation::new("post", "/calendar/token", "rotate the feed token").data::<CalendarFeed>(),
+    Operation::new("post", "/calendar/settle", "log outfits planned for past days as worn")
+        .data::<Vec<planned_outfit::Model>>(),
+    Operation::new("put", "/calendar/{date}", "plan an outfit")
+        .body::<OutfitPlan>()
+        .data::<PlannedOutfit>(),
+    Operation::new("delete", "/calendar/{date}", "unplan an outfit")
+        .data::<planned_outfit::Model>(),
+    Operation::new("get", "/calendar.ics", "iCalendar feed")
+        .public()
+        .query::<FeedQuery>()
+        .content("text/calendar"),
+    Operation::new("get", "/closets", "list closets").data::<Page<ClosetDetail>>(),
+    Operation::new("post", "/closets", "create a closet")
+        .body::<ClosetCreate>()
+        .data::<ClosetDetail>(),
+    Operation::new("get", "/closets/invitations", "pending invitations")
+        .data::<Vec<InvitationDetail>>(),
+    Operation::new("post", "/closets/invitations/{invitation_id}/accept", "accept an invitation")
+        .data::<ClosetDetail>(),
+    Operation::new("post", "/closets/invitations/{invitation_id}/decline", "decline an invitation")
+        .data::<closet_invitation::Model>(),
+    Operation::new("get", "/closets/{id}", "closet detail").data::<ClosetDetail>(),
+    Operation::new("put", "/closets/{id}", "rename a closet")
+        .body::<ClosetCreate>()
+        .data::<ClosetDetail>(),
+    Operation::new("delete", "/closets/{id}", "delete a closet").data::<closet::Model>(),
+    Operation::new("post", "/closets/{id}/invitations", "invite a user")
+        .body::<ClosetInvite>()
+        .data::<closet_invitation::Model>(),
+    Operation::new("put", "/closets/{id}/members/{user_id}", "change the role of a member")
+        .body::<RoleChange>()
+        .data::<closet_member::Model>(),
+    Operation::new("delete", "/closets/{id}/members/{user_id}", "remove a member")
+        .data::<closet_member::Model>(),
+    Operation::new("get", "/wishlist", "list wishes")
+        .query::<WishQuery>()
+        .data::<Page<wish::Model>>(),
+    Operation::new("post", "/wishlist", "add a wish").body::<WishCreate>().data::<wish::Model>(),
+    Operation::new("get", "/wishlist/{id}", "wish detail").data::<wish::Model>(),
+    Operation::new("put", "/wishlist/{id}", "update a wish")
+        .body::<WishCreate>()
+        .data::<wish::Model>(),
+    Operation::new("delete", "/wishlist/{id}", "delete a wish").data::<wish::Model>(),
+    Operation::new("post", "/wishlist/{id}/purchase", "purchase a wish into the closet")
+        .body::<WishPurchase>()
+        .data::<Purchase>(),
+    Operation::new("get", "/spending", "spending against the budget")
+        .query::<SpendingQuery>()
+        .data::<SpendingSummary>(),
+    #[cfg(feature = "docs")]
+    Operation::new("get", "/docs", "this document rendered by redoc").public().content("text/html"),
+];
+
+pub const BEARER: &str = "bearer";
+
+const COMPONENTS: &str = "/components/schemas";
+
+/// OpenAPI 3.1 document of `api_router`, paths are relative to the base url
+pub fn document() -> Value {
+    // requests are documented as they are deserialized, e.g. fields with defaults are optional,
+    // and responses as they are serialized
+    let settings = SchemaSettings::draft2020_12().with(|s| s.definitions_path = COMPONENTS.into());
+    let mut requests = settings.clone().for_deserialize().into_generator();
+    let mut responses = settings.for_serialize().into_generator();
+
+    let mut paths = Map::new();
+    let failure = json!({
+        "type": "object",
+        "required": ["failure"],
+        "properties": {
+            "failure": responses.subschema_for::<ApiError>(),
+            "request_id": { "type": "string", "description": "also in the X-Request-Id header" },
+        },
+    });
+    let problem = responses.subschema_for::<Problem>();
+    for operation in OPERATIONS {
+        let mut described = describe(operation, &mut requests, &mut responses);
+        described["responses"]["default"] = json!({ "description": "failure", "content": {
+            "application/json": { "schema": failure },
+            APPLICATION_PROBLEM_JSON: { "schema": problem },
+        } });
+        let path = paths.entry(operation.path).or_insert_with(|| json!({}));
+        path[operation.method] = described;
+    }
+
+    let mut schemas = responses.take_definitions(true);
+    // a type may differ by direction, e.g. a field with a default is optional only in requests,
+    // then the request schema is documented apart as `<name>Input`
+    let mut inputs = BTreeMap::new();
+    let mut requested = Value::Object(requests.take_definitions(true));
+    for (name, schema) in requested.as_object().into_iter().flatten() {
+        if schemas.get(name).is_some_and(|s| s != schema) {
+            inputs.insert(format!("#{}/{}", COMPONENTS, name), format!("{}Input", name));
+        }
+    }
+    rename(&mut requested, &inputs);
+    for described in
+        paths.values_mut().filter_map(Value::as_object_mut).flat_map(|p| p.values_mut())
+    {
+        for key in ["requestBody", "parameters"] {
+            described.get_mut(key).into_iter().for_each(|v| rename(v, &inputs));
+        }
+    }
+    for (name, schema) in requested.as_object_mut().into_iter().flatten() {
+        let input = inputs.get(&format!("#{}/{}", COMPONENTS, name));
+        schemas.entry(input.unwrap_or(name)).or_insert_with(|| schema.take());
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "closet",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                BEARER: { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+            },
+            "schemas": schemas,
+        },
+    })
+}
+
+fn describe(
+    operation: &Operation,
+    requests: &mut SchemaGenerator,
+    responses: &mut SchemaGenerator,
+) -> Value {
+    let mut described = json!({
+        "operationId": operation.operation_id(),
+        "summary": operation.summary,
+        "tags": [operation.tag()],
+    });
+    let mut parameters: Vec<_> = operation
+        .parameters()
+        .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+        .collect();
+    if let Some(query) = operation.query.map(|query| query(requests)) {
+        let required = query.get("required").and_then(Value::as_array);
+        let properties = query.get("properties").and_then(Value::as_object);
+        parameters.extend(properties.into_iter().flatten().map(|(name, schema)| {
+            let required = required.is_some_and(|r| r.contains(&json!(name)));
+            json!({ "name": name, "in": "query", "required": required, "schema": schema })
+        }));
+    }
+    if !parameters.is_empty() {
+        described["parameters"] = json!(parameters);
+    }
+    match operation.access {
+        Access::Public => (),
+        Access::Optional => described["security"] = json!([{}, { BEARER: [] }]),
+        Access::Login => described["security"] = json!([{ BEARER: [] }]),
+    }
+    if let Some(body) = operation.body {
+        described["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": body(requests) } },
+        });
+    }
+    let success = match operation.content {
+        Some(content_type) => json!({ "description": "success", "content": { content_type: {} } }),
+        None => {
+            let data = operation.data.map_or(json!({}), |data| data(responses).to_value());
+            json!({ "description": "success", "content": { "application/json": { "schema": {
+                "type": "object",
+                "required": ["success"],
+                "properties": { "success": data },
+            } } } })
+        }
+    };
+    described["responses"] = json!({ "200": success });
+    described
+}
+
+/// points the `$ref`s into the components to the renamed schemas
+fn rename(value: &mut Value, renamed: &BTreeMap<String, String>) {
+    match value {
+        Value::Object(object) => match object.get("$ref").and_then(Value::as_str) {
+            Some(reference) if renamed.contains_key(reference) => {
+                object["$ref"] = json!(format!("#{}/{}", COMPONENTS, renamed[reference]));
+            }
+            _ => object.values_mut().for_each(|v| rename(v, renamed)),
+        },
+        Value::Array(array) => array.iter_mut().for_each(|v| rename(v, renamed)),
+        _ => (),
+    }
+}
+
+pub async fn spec(State(state): State<AppState>) -> axum::Json<Value> {
+    let mut document = document();
+    document["servers"] = json!([{ "url": state.configuration.base_url() }]);
+    axum::Json(document)
+}
+
+/// Redoc page rendering this document, the script is loaded from a CDN
+#[cfg(feature = "docs")]
+pub async fn docs() -> axum::response::Html<&'static str> {
+    axum::response::Html(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>closet api</title>
+    <meta charset="utf-8"/>
+  </head>
+  <body>
+    <redoc spec-url="openapi.json"></redoc>
+    <script src="https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js"></script>
+  </body>
+</html>
+"#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "docs"))]
+    const SNAPSHOT: &str = include_str!("../openapi.json");
+
+    #[test]
+    // the snapshot is of the default features, without `/docs`
+    #[cfg(not(feature = "docs"))]
+    fn test_document_snapshot() {
+        let document = serde_json::to_string_pretty(&document()).unwrap() + "\n";
+        if std::env::var("UPDATE_OPENAPI").is_ok() {
+            std::fs::write(concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json"), &document)
+                .unwrap();
+        } else {
+            assert!(document == SNAPSHOT, "openapi.json drifted, rerun with UPDATE_OPENAPI=1");
+        }
+    }
+
+    #[test]
+    fn test_operations() {
+        let mut seen = std::collections::HashSet::new();
+        for operation in OPERATIONS {
+            assert!(seen.insert((operation.method, operation.path)), "{:?}", operation);
+            assert!(!operation.path.contains(':'), "{:?}", operation);
+        }
+        let login = OPERATIONS.iter().find(|o| o.path == "/auth/login").unwrap();
+        assert_eq!(login.tag(), "auth");
+        assert_eq!(login.operation_id(), "post_auth_login");
+        let image = OPERATIONS.iter().find(|o| o.path == "/items/{id}/images/{image_id}").unwrap();
+        assert_eq!(image.parameters().collect::<Vec<_>>(), ["id", "image_id"]);
+    }
+
+    #[test]
+    fn test_components() {
+        fn references<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+            match value {
+                Value::Object(object) => {
+                    found.extend(object.get("$ref").and_then(Value::as_str));
+                    object.values().for_each(|v| references(v, found));
+                }
+                Value::Array(array) => array.iter().for_each(|v| references(v, found)),
+                _ => (),
+            }
+        }
+        let document = document();
+        let mut found = Vec::new();
+        references(&document, &mut found);
+        for reference in found {
+            let pointer = reference.strip_prefix('#').unwrap();
+            assert!(document.pointer(pointer).is_some(), "{} is not documented", reference);
+        }
+        // schemars suffixes a number to distinct types of the same name
+        for name in document["components"]["schemas"].as_object().unwrap().keys() {
+            assert!(!name.ends_with(|c: char| c.is_ascii_digit()), "{} is ambiguous", name);
+        }
+        let measurements = &document["components"]["schemas"]["MeasurementsInput"];
+        assert!(measurements["required"].as_array().is_none(), "{}", measurements);
+    }
+
+    /// `(method, path)` of every route of `api_router`, read from its `Debug` as axum has no other
+    /// way to list them
+    fn routes() -> Vec<(String, String)> {
+        let router = format!("{:?}", crate::api_router());
+        // the fallback router numbers its routes apart
+        let (router, _) = router.split_once("fallback_router").unwrap();
+        let (endpoints, paths) = router.split_once("node: Node").unwrap();
+        let methods: std::collections::HashMap<_, _> = endpoints
+            .split("RouteId(")
+            .filter_map(|endpoint| {
+                let (id, endpoint) = endpoint.split_once(')')?;
+                let allowed = endpoint.split_once("allow_header: Bytes(b\"")?.1.split_once('"')?.0;
+                Some((id, allowed))
+            })
+            .collect();
+        let paths = paths.split("RouteId(").filter_map(|path| {
+            let (id, path) = path.split_once(')')?;
+            Some((id, path.split('"').nth(1)?))
+        });
+        let paths = paths.filter(|(_, path)| !path.contains('*')).map(|(id, path)| {
+            let segments = path.split('/').map(|segment| match segment.strip_prefix(':') {
+                Some(parameter) => format!("{{{}}}", parameter),
+                None => segment.to_string(),
+            });
+            (methods.get(id).copied().unwrap_or_default(), segments.collect::<Vec<_>>().join("/"))
+        });
+        paths
+            .flat_map(|(methods, path)| {
+                let methods = methods.split(',').filter(|m| !m.is_empty() && *m != "HEAD");
+                methods.map(move |method| (method.to_lowercase(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_routes_are_documented() {
+        let routes = routes();
+        assert!(routes.len() >= OPERATIONS.len() - 1, "{:?}", routes);
+        for (method, path) in routes {
+            assert!(
+                OPERATIONS.iter().any(|o| o.method == method && o.path == path),
+                "{} {} is not documented",
+                method,
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_operations_are_routed() {
+        use crate::{call, configuration::Configuration, request, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        for operation in OPERATIONS {
+            let path = operation.path.split('/').map(|segment| match segment.starts_with('{') {
+                true => "01H2XZ6N1ZVB3DPRXKMFS9QJ6T",
+                false => segment,
+            });
+            let uri = path.collect::<Vec<_>>().join("/");
+            let method = operation.method.to_uppercase().parse().unwrap();
+            let (status, body) =
+                call(&api, request(method, &uri, None).body(hyper::Body::empty()).unwrap()).await;
+            assert_ne!(status, hyper::StatusCode::METHOD_NOT_ALLOWED, "{:?}", operation);
+            let unmatched =
+                serde_json::to_vec(&crate::response::result::ApiResponse::<()>::Failure(
+                    crate::response::error::ApiError::UnmatchedPathError,
+                ))
+                .unwrap();
+            assert_ne!(body, unmatched, "{:?}", operation);
+        }
+    }
+
+    /// whether the value is valid against the schema, for the keywords schemars emits, and has no
+    /// property the schema does not document
+    #[cfg(feature = "sqlite")]
+    fn conforms(document: &Value, schema: &Value, value: &Value) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let referenced = document.pointer(reference.trim_start_matches('#'));
+            return conforms(document, referenced.ok_or(reference)?, value);
+        }
+        let invalid = || format!("{} is not valid against {}", value, schema);
+        if let Some(variants) =
+            schema.get("anyOf").or(schema.get("oneOf")).and_then(Value::as_array)
+        {
+            variants.iter().find(|v| conforms(document, v, value).is_ok()).ok_or_else(invalid)?;
+        }
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_f64() => "number",
+            Value::Number(_) => "integer",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        if let Some(types) = schema.get("type") {
+            let types = types.as_array().cloned().unwrap_or_else(|| vec![types.clone()]);
+            let number = kind == "integer" && types.contains(&json!("number"));
+            (types.contains(&json!(kind)) || number).then_some(()).ok_or_else(invalid)?;
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            values.contains(value).then_some(()).ok_or_else(invalid)?;
+        }
+        if let Some(constant) = schema.get("const") {
+            (constant == value).then_some(()).ok_or_else(invalid)?;
+        }
+        if let (Value::Array(values), Some(items)) = (value, schema.get("items")) {
+            values.iter().try_for_each(|v| conforms(document, items, v))?;
+        }
+        let Value::Object(object) = value else { return Ok(()) };
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            let required = required.as_str().unwrap_or_default();
+            object.contains_key(required).then_some(()).ok_or_else(invalid)?;
+        }
+        let properties = schema.get("properties");
+        for (key, v) in object {
+            match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                (Some(property), _) | (None, Some(property)) => conforms(document, property, v)?,
+                (None, None) if properties.is_some() => Err(format!("{} is not documented", key))?,
+                (None, None) => (),
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "sqlite")]
+    async fn test_responses_conform() {
+        use hyper::Method;
+
+        use crate::{call_json, configuration::Configuration, signup, with_auth};
+
+        let api =
+            with_auth(crate::api_router(), Configuration::new(crate::standalone())).await.unwrap();
+        let owner = signup(&api, "owner").await;
+        let token = owner.token.as_deref();
+        let document = document();
+        let conform = |method: Method, path: &str, body: &Value| {
+            let described = &document["paths"][path][method.as_str().to_lowercase()];
+            let schema = &described["responses"]["200"]["content"]["application/json"]["schema"];
+            conforms(&document, schema, body).map_err(|e| format!("{} {}: {}", method, path, e))
+        };
+
+        let page = json!({ "success": { "items": [], "next": null, "prev": null } });
+        conform(Method::GET, "/items", &page).unwrap();
+        let undocumented =
+            json!({ "success": { "items": [], "next": null, "prev": null, "x": 1 } });
+        assert!(conform(Method::GET, "/items", &undocumented).is_err());
+        assert!(conform(Method::GET, "/items", &json!({ "success": { "items": [] } })).is_err());
+
+        let create = json!({ "name": "navy coat", "brand": "closet", "notes": null });
+        let (_, created) =
+            call_json::<Value>(&api, Method::POST, "/items", token, Some(create)).await;
+        let created = json!(created);
+        conform(Method::POST, "/items", &created).unwrap();
+        let id = created["success"]["item"]["id"].as_str().unwrap().to_string();
+        let outfit = json!({ "name": "commute", "items": [id] });
+        let (_, outfit) =
+            call_json::<Value>(&api, Method::POST, "/outfits", token, Some(outfit)).await;
+        let outfit = json!(outfit);
+        conform(Method::POST, "/outfits", &outfit).unwrap();
+        let outfit_id = outfit["success"]["outfit"]["id"].as_str().unwrap().to_string();
+        let wear = json!({ "worn_on": "2023-06-01" });
+        let uri = format!("/items/{}/wear", id);
+        let (_, worn) = call_json::<Value>(&api, Method::POST, &uri, token, Some(wear)).await;
+        conform(Method::POST, "/items/{id}/wear", &json!(worn)).unwrap();
+
+        let reads = [
+            ("/auth/whoami", "/auth/whoami".to_string()),
+            ("/profile", "/profile".to_string()),
+            ("/profile/preferences", "/profile/preferences".to_string()),
+            ("/items", "/items".to_string()),
+            ("/items/{id}", format!("/items/{}", id)),
+            ("/items/{id}/wears", format!("/items/{}/wears", id)),
+            ("/items/{id}/transitions", format!("/items/{}/transitions", id)),
+            ("/items/declutter", "/items/declutter".to_string()),
+            ("/outfits", "/outfits".to_string()),
+            ("/outfits/{id}", format!("/outfits/{}", outfit_id)),
+            ("/closets", "/closets".to_string()),
+            ("/favorites", "/favorites".to_string()),
+            ("/laundry/plan", "/laundry/plan".to_string()),
+            ("/search", "/search?q=navy".to_string()),
+            ("/analytics/added", "/analytics/added".to_string()),
+            ("/analytics/utilisation", "/analytics/utilisation".to_string()),
+            ("/storage", "/storage".to_string()),
+            ("/trips", "/trips".to_string()),
+            ("/wishlist", "/wishlist".to_string()),
+            ("/spending", "/spending".to_string()),
+        ];
+        for (path, uri) in reads {
+            let (_, read) = call_json::<Value>(&api, Method::GET, &uri, token, None).await;
+            let read = json!(read);
+            assert!(read.get("success").is_some(), "{}: {}", uri, read);
+            conform(Method::GET, path, &read).unwrap();
+        }
+    }
+}
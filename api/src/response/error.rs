@@ -1,25 +1,31 @@
 use axum::{response::IntoResponse, BoxError, Json};
 use chrono::Duration;
-use entity::error::EntityError;
+use entity::error::{EntityError, FieldErrors};
 use hyper::StatusCode;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use serde_with::{serde_as, DisplayFromStr, DurationNanoSeconds};
+use serde_with::{serde_as, DurationNanoSeconds};
 
-use crate::configuration::Config;
+use crate::{configuration::Config, middleware::request_id};
 
-use super::result::ApiResponse;
+use super::{
+    problem::{ErrorCode, Problem},
+    result::ApiResponse,
+};
 
 #[serde_as]
-#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
+#[derive(Debug, thiserror::Error, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ApiError {
     #[error("{}", source)]
     AnyhowError {
-        #[serde_as(as = "DisplayFromStr")]
+        #[serde(with = "self::serde_status_code")]
+        #[schemars(with = "String", extend("examples" = ["404 Not Found"]))]
         code: StatusCode,
         #[source]
         #[serde(with = "self::serde_anyhow")]
+        #[schemars(with = "String")]
         source: anyhow::Error,
     },
 
@@ -29,21 +35,34 @@ pub enum ApiError {
     RecordNotFound,
     #[error("unexpected database error")]
     UnexpectedDatabaseError {
-        #[serde_as(as = "DisplayFromStr")]
+        #[serde(with = "self::serde_status_code")]
+        #[schemars(with = "String", extend("examples" = ["404 Not Found"]))]
         code: StatusCode,
     },
 
     #[error("{}", source)]
     EntityError {
-        #[serde_as(as = "DisplayFromStr")]
+        #[serde(with = "self::serde_status_code")]
+        #[schemars(with = "String", extend("examples" = ["404 Not Found"]))]
         code: StatusCode,
         #[source]
+        #[schemars(with = "serde_json::Value")]
         source: EntityError,
     },
 
+    /// every invalid field of the request body
+    #[error("{}", fields)]
+    ValidationError {
+        #[serde(with = "self::serde_status_code")]
+        #[schemars(with = "String", extend("examples" = ["404 Not Found"]))]
+        code: StatusCode,
+        fields: FieldErrors,
+    },
+
     #[error("request timeout {:?}", nanos)]
     TimeoutError {
         #[serde_as(as = "DurationNanoSeconds<i64>")]
+        #[schemars(with = "i64")]
         nanos: Duration,
     },
 
@@ -65,6 +84,7 @@ impl ApiError {
             Self::RecordNotFound => &StatusCode::NOT_FOUND,
             Self::UnexpectedDatabaseError { code, .. } => code,
             Self::EntityError { code, .. } => code,
+            Self::ValidationError { code, .. } => code,
             Self::TimeoutError { .. } => &StatusCode::REQUEST_TIMEOUT,
             Self::UnmatchedPathError => &StatusCode::NOT_FOUND,
             Self::LoginFailError => &StatusCode::FORBIDDEN,
@@ -72,6 +92,44 @@ impl ApiError {
             Self::LoginRequiredError => &StatusCode::FORBIDDEN,
         }
     }
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::AnyhowError { code, .. } => match *code {
+                StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+                StatusCode::CONFLICT => ErrorCode::Conflict,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+                StatusCode::BAD_GATEWAY => ErrorCode::BadGateway,
+                code if code.is_server_error() => ErrorCode::InternalError,
+                _ => ErrorCode::BadRequest,
+            },
+            Self::CannotConnectDatabase => ErrorCode::DatabaseUnavailable,
+            Self::RecordNotFound => ErrorCode::NotFound,
+            Self::UnexpectedDatabaseError { .. } => ErrorCode::DatabaseError,
+            Self::EntityError { code, .. } if code.is_server_error() => ErrorCode::InternalError,
+            Self::EntityError { .. } => ErrorCode::ValidationFailed,
+            Self::ValidationError { .. } => ErrorCode::ValidationFailed,
+            Self::TimeoutError { .. } => ErrorCode::Timeout,
+            Self::UnmatchedPathError => ErrorCode::UnknownEndpoint,
+            Self::LoginFailError => ErrorCode::LoginFailed,
+            Self::InactiveUserError => ErrorCode::InactiveUser,
+            Self::LoginRequiredError => ErrorCode::LoginRequired,
+        }
+    }
+    /// problem without `instance`, internal messages of server errors are not disclosed
+    pub fn problem(&self) -> Problem {
+        let code = self.code();
+        let detail = match code {
+            ErrorCode::InternalError | ErrorCode::DatabaseError | ErrorCode::BadGateway => None,
+            _ => Some(self.to_string()),
+        };
+        let problem = Problem::new(code, *self.status_code(), detail);
+        match self {
+            Self::ValidationError { fields, .. } => {
+                Problem { errors: Some(fields.clone()), ..problem }
+            }
+            _ => problem,
+        }
+    }
     pub async fn handle_timeout(error: BoxError) -> impl IntoResponse {
         if error.is::<tower::timeout::error::Elapsed>() {
             // TODO state
@@ -87,8 +145,15 @@ impl ApiError {
 }
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
-        let (code, response) = (self.status_code().clone(), ApiResponse::<()>::Failure(self));
-        (code, Json(json!(response))).into_response()
+        let (code, problem) = (*self.status_code(), self.problem());
+        let mut body = json!(ApiResponse::<()>::Failure(self));
+        if let Some(request_id) = request_id::current() {
+            body[ApiResponse::<()>::REQUEST_ID] = json!(request_id);
+        }
+        let mut response = (code, Json(body)).into_response();
+        // swapped for the body by `middleware::problem::negotiate` if the client accepts it
+        response.extensions_mut().insert(problem);
+        response
     }
 }
 
@@ -109,6 +174,7 @@ impl From<sea_orm::DbErr> for ApiError {
 }
 impl From<(StatusCode, sea_orm::DbErr)> for ApiError {
     fn from((status, error): (StatusCode, sea_orm::DbErr)) -> Self {
+        tracing::error!("database error: {}", error);
         match error {
             sea_orm::DbErr::Conn(_) => Self::CannotConnectDatabase,
             sea_orm::DbErr::RecordNotFound(_) => Self::RecordNotFound,
@@ -118,7 +184,19 @@ impl From<(StatusCode, sea_orm::DbErr)> for ApiError {
 }
 impl From<EntityError> for ApiError {
     fn from(error: EntityError) -> Self {
-        (StatusCode::BAD_REQUEST, error).into()
+        match error {
+            EntityError::InvalidFields(fields) => fields.into(),
+            EntityError::ValidateError(invalid) => match invalid.field() {
+                Some(field) => FieldErrors::of(field, &invalid).into(),
+                None => (StatusCode::BAD_REQUEST, EntityError::from(invalid)).into(),
+            },
+            _ => (StatusCode::BAD_REQUEST, error).into(),
+        }
+    }
+}
+impl From<FieldErrors> for ApiError {
+    fn from(fields: FieldErrors) -> Self {
+        Self::ValidationError { code: StatusCode::UNPROCESSABLE_ENTITY, fields }
     }
 }
 impl From<(StatusCode, EntityError)> for ApiError {
@@ -145,3 +223,25 @@ mod serde_anyhow {
         Ok(anyhow::Error::msg(msg))
     }
 }
+
+mod serde_status_code {
+    use hyper::StatusCode;
+    use serde::{de::Error, Deserialize, Serialize};
+
+    pub fn serialize<S>(code: &StatusCode, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        code.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<StatusCode, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // displayed as "404 Not Found", so the canonical reason is ignored
+        let displayed = <String>::deserialize(deserializer)?;
+        let code = displayed.split_whitespace().next().unwrap_or_default();
+        code.parse().map_err(D::Error::custom)
+    }
+}
@@ -1,4 +1,7 @@
 pub mod error;
+pub mod page;
+pub mod problem;
 pub mod result;
 
 pub type ApiResult<T> = Result<result::ApiResponse<T>, error::ApiError>;
+pub type ApiPage<T> = ApiResult<page::Page<T>>;
@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// One page of a list, with the cursors of the pages around it.
+/// Returned as `ApiResponse<Page<T>>`, see [`crate::handler::listing::Listing`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "{T}Page")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `cursor` of the following page, null if this is the last one
+    pub next: Option<String>,
+    /// `cursor` of the preceding page, null if this is the first one
+    pub prev: Option<String>,
+}
+impl<T> Page<T> {
+    /// the same page of other items, e.g. the models loaded with their relations
+    pub fn with_items<U>(self, items: Vec<U>) -> Page<U> {
+        Page { items, next: self.next, prev: self.prev }
+    }
+}
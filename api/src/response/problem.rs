@@ -0,0 +1,186 @@
+use axum::{response::IntoResponse, Json};
+use entity::error::FieldErrors;
+use hyper::{header, HeaderMap, StatusCode};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::middleware::request_id;
+
+pub const APPLICATION_PROBLEM_JSON: &str = "application/problem+json";
+
+/// Stable machine-readable codes of errors, the catalog clients may match on.
+/// Variants of `ApiError` may be renamed, these may not.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// unexpected failure in the server, the detail is not disclosed
+    InternalError,
+    /// the request is rejected, e.g. malformed
+    BadRequest,
+    /// the user may not do this, e.g. a viewer writing to a closet
+    Forbidden,
+    /// the request conflicts with the state of the resource, e.g. lending an item already lent
+    Conflict,
+    /// the body is not of a supported content type
+    UnsupportedMediaType,
+    /// an upstream service failed, e.g. the weather forecast, the detail is not disclosed
+    BadGateway,
+    /// a field of the request is invalid
+    ValidationFailed,
+    /// the resource does not exist or is not visible to the user
+    NotFound,
+    /// no endpoint for the method and path
+    UnknownEndpoint,
+    DatabaseUnavailable,
+    DatabaseError,
+    Timeout,
+    LoginFailed,
+    InactiveUser,
+    LoginRequired,
+}
+impl ErrorCode {
+    pub const ALL: [Self; 15] = [
+        Self::InternalError,
+        Self::BadRequest,
+        Self::Forbidden,
+        Self::Conflict,
+        Self::UnsupportedMediaType,
+        Self::BadGateway,
+        Self::ValidationFailed,
+        Self::NotFound,
+        Self::UnknownEndpoint,
+        Self::DatabaseUnavailable,
+        Self::DatabaseError,
+        Self::Timeout,
+        Self::LoginFailed,
+        Self::InactiveUser,
+        Self::LoginRequired,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::InternalError => "internal error",
+            Self::BadRequest => "bad request",
+            Self::Forbidden => "forbidden",
+            Self::Conflict => "conflict",
+            Self::UnsupportedMediaType => "unsupported media type",
+            Self::BadGateway => "bad gateway",
+            Self::ValidationFailed => "validation failed",
+            Self::NotFound => "not found",
+            Self::UnknownEndpoint => "unknown endpoint",
+            Self::DatabaseUnavailable => "database unavailable",
+            Self::DatabaseError => "database error",
+            Self::Timeout => "timeout",
+            Self::LoginFailed => "invalid username or password",
+            Self::InactiveUser => "inactive user",
+            Self::LoginRequired => "login required",
+        }
+    }
+    /// `type` of the problem, an URN as there is no page per code to dereference
+    pub fn uri(&self) -> String {
+        format!("urn:closet:error:{}", self)
+    }
+}
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+
+/// RFC 7807 representation of `ApiError`, served when the client accepts `application/problem+json`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub detail: Option<String>,
+    /// id of the request the problem occurred in
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub instance: Option<String>,
+    pub code: ErrorCode,
+    /// invalid fields, for `validation_failed`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub errors: Option<FieldErrors>,
+}
+impl Problem {
+    /// problem in the current request, if any
+    pub fn new(code: ErrorCode, status: StatusCode, detail: Option<String>) -> Self {
+        let (kind, title, instance) = (code.uri(), code.title().to_string(), request_id::current());
+        Self { kind, title, status: status.as_u16(), detail, instance, code, errors: None }
+    }
+
+    /// whether the `Accept` headers ask for problems rather than the `failure` envelope
+    pub fn accepted(headers: &HeaderMap) -> bool {
+        let ranges = headers.get_all(header::ACCEPT).into_iter().filter_map(|v| v.to_str().ok());
+        ranges.flat_map(|v| v.split(',')).any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media = params.next().unwrap_or_default();
+            let rejected = params.any(|p| {
+                p.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()).is_some_and(|q| q <= 0.0)
+            });
+            media.eq_ignore_ascii_case(APPLICATION_PROBLEM_JSON) && !rejected
+        })
+    }
+}
+impl IntoResponse for Problem {
+    fn into_response(self) -> axum::response::Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let content_type = [(header::CONTENT_TYPE, APPLICATION_PROBLEM_JSON)];
+        (status, content_type, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_error_code_catalog() {
+        let codes: std::collections::HashSet<_> =
+            ErrorCode::ALL.iter().map(ToString::to_string).collect();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert_eq!(ErrorCode::LoginFailed.to_string(), "login_failed");
+        assert_eq!(ErrorCode::NotFound.uri(), "urn:closet:error:not_found");
+    }
+
+    #[test]
+    fn test_problem_of_api_error() {
+        use crate::response::error::ApiError;
+
+        let internal = ApiError::from(anyhow::anyhow!("secret internal message")).problem();
+        assert_eq!((internal.code, internal.status), (ErrorCode::InternalError, 500));
+        assert_eq!(internal.detail, None);
+        let rejected = ApiError::from((StatusCode::CONFLICT, anyhow::anyhow!("already lent")));
+        let rejected = rejected.problem();
+        assert_eq!(
+            (rejected.code, rejected.detail.as_deref()),
+            (ErrorCode::Conflict, Some("already lent"))
+        );
+        let rejected = ApiError::from((StatusCode::BAD_REQUEST, anyhow::anyhow!("malformed")));
+        assert_eq!(rejected.problem().code, ErrorCode::BadRequest);
+        let upstream =
+            ApiError::from((StatusCode::BAD_GATEWAY, anyhow::anyhow!("secret upstream")));
+        let upstream = upstream.problem();
+        assert_eq!((upstream.code, upstream.detail), (ErrorCode::BadGateway, None));
+        assert_eq!(ApiError::RecordNotFound.problem().code, ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_accepted() {
+        let accepted = |accept: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, HeaderValue::from_str(accept).unwrap());
+            Problem::accepted(&headers)
+        };
+        assert!(!Problem::accepted(&HeaderMap::new()));
+        assert!(!accepted("application/json"));
+        assert!(!accepted("*/*"));
+        assert!(accepted("application/problem+json"));
+        assert!(accepted("application/json;q=0.5, Application/Problem+JSON"));
+        assert!(!accepted("application/problem+json; q=0"));
+    }
+}
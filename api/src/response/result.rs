@@ -1,17 +1,39 @@
 use axum::{response::IntoResponse, Json};
 use hyper::StatusCode;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::json;
 
 use super::{error::ApiError, ApiResult};
 
-#[derive(Serialize, Deserialize, Debug)]
+/// `{"success": T}` or `{"failure": ApiError, "request_id": String}`
+#[derive(Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ApiResponse<T> {
     Success(T),
     Failure(ApiError),
 }
+impl<'de, T: DeserializeOwned> Deserialize<'de> for ApiResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum Tagged<T> {
+            Success(T),
+            Failure(ApiError),
+        }
+        // the request id is beside the tag, so the tagged enum is deserialized without it
+        let mut envelope = serde_json::Map::deserialize(deserializer)?;
+        envelope.remove(Self::REQUEST_ID);
+        let tagged = Tagged::deserialize(serde_json::Value::Object(envelope))
+            .map_err(serde::de::Error::custom)?;
+        Ok(match tagged {
+            Tagged::Success(ok) => Self::Success(ok),
+            Tagged::Failure(err) => Self::Failure(err),
+        })
+    }
+}
 impl<T> ApiResponse<T> {
+    pub const REQUEST_ID: &'static str = "request_id";
+
     pub fn result(&self) -> Result<&T, &ApiError> {
         match self {
             Self::Success(ok) => Ok(ok),
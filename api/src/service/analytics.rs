@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+use entity::class::time_zone::TimeZone;
+use schemars::JsonSchema;
+use sea_orm::sea_query::{CaseStatement, Expr, IntoColumnRef, SimpleExpr};
+use serde::{Deserialize, Serialize};
+
+/// `YYYY-MM` of the month starting on one of `first_days` in the time zone which the UTC timestamp
+/// column falls in, null out of them. The bounds of each month are in UTC by the offset of their
+/// own, so that the months are right across the daylight saving time.
+pub fn month<C: IntoColumnRef + Clone>(
+    column: C,
+    first_days: &[NaiveDate],
+    time_zone: &TimeZone,
+) -> SimpleExpr {
+    let mut case = CaseStatement::new();
+    for first in first_days {
+        let next = *first + Months::new(1);
+        let (start, end) = (time_zone.start_of(*first), time_zone.start_of(next));
+        let within = Expr::col(column.clone())
+            .gte(start.fixed_offset())
+            .and(Expr::col(column.clone()).lt(end.fixed_offset()));
+        case = case.case(within, first.format("%Y-%m").to_string());
+    }
+    SimpleExpr::Case(Box::new(case))
+}
+
+/// First days of the `months` months up to the one of `today`, oldest first.
+pub fn first_days(today: NaiveDate, months: u32) -> Vec<NaiveDate> {
+    let current = today.with_day(1).expect("every month has a first day");
+    (0..months).rev().filter_map(|back| current.checked_sub_months(Months::new(back))).collect()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MonthlyCount {
+    /// `YYYY-MM`
+    pub month: String,
+    pub count: i64,
+}
+
+/// Counts of the `months` months up to the one of `today`, oldest first, including empty months.
+pub fn monthly(counts: &[MonthlyCount], today: NaiveDate, months: u32) -> Vec<MonthlyCount> {
+    first_days(today, months)
+        .into_iter()
+        .map(|first| {
+            let month = first.format("%Y-%m").to_string();
+            let count = counts.iter().filter(|c| c.month == month).map(|c| c.count).sum();
+            MonthlyCount { month, count }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Share {
+    pub value: String,
+    pub count: i64,
+    /// 0 to 1
+    pub share: f64,
+}
+
+/// How much of the closet a few values, e.g. brands, account for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Concentration {
+    /// the most frequent first
+    pub shares: Vec<Share>,
+    /// share of the most frequent value
+    pub top: f64,
+    /// Herfindahl-Hirschman index, from `1 / n` when evenly spread to 1 when all the same
+    pub index: f64,
+}
+impl Concentration {
+    pub fn of(counts: impl IntoIterator<Item = (String, i64)>) -> Self {
+        let mut merged: HashMap<String, i64> = HashMap::new();
+        for (value, count) in counts {
+            *merged.entry(value).or_default() += count;
+        }
+        let total: i64 = merged.values().sum();
+        let mut shares: Vec<_> = merged
+            .into_iter()
+            .map(|(value, count)| Share { value, count, share: count as f64 / total as f64 })
+            .collect();
+        shares.sort_by(|x, y| y.count.cmp(&x.count).then_with(|| x.value.cmp(&y.value)));
+        let top = shares.first().map(|s| s.share).unwrap_or_default();
+        let index = shares.iter().map(|s| s.share * s.share).sum();
+        Self { shares, top, index }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sea_query::{Alias, Query, SqliteQueryBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_month() {
+        let new_york: TimeZone = "America/New_York".parse().unwrap();
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let first_days = [ymd(2023, 3, 1), ymd(2023, 4, 1)];
+        let select = Query::select()
+            .expr(month(Alias::new("created_at"), &first_days, &new_york))
+            .to_owned();
+        assert_eq!(
+            select.to_string(SqliteQueryBuilder),
+            "SELECT (CASE \
+             WHEN ((\"created_at\" >= '2023-03-01 05:00:00 +00:00') \
+             AND (\"created_at\" < '2023-04-01 04:00:00 +00:00')) THEN '2023-03' \
+             WHEN ((\"created_at\" >= '2023-04-01 04:00:00 +00:00') \
+             AND (\"created_at\" < '2023-05-01 04:00:00 +00:00')) THEN '2023-04' END)"
+        );
+    }
+
+    #[test]
+    fn test_monthly() {
+        let count = |month: &str, count| MonthlyCount { month: month.into(), count };
+        let counts = [count("2022-12", 2), count("2023-02", 3), count("2021-01", 9)];
+        let today = NaiveDate::from_ymd_opt(2023, 2, 14).unwrap();
+        assert_eq!(
+            monthly(&counts, today, 4),
+            vec![
+                count("2022-11", 0),
+                count("2022-12", 2),
+                count("2023-01", 0),
+                count("2023-02", 3)
+            ]
+        );
+        assert!(monthly(&counts, today, 0).is_empty());
+    }
+
+    #[test]
+    fn test_concentration() {
+        let brands = [("Acme".into(), 3), ("Zed".into(), 1), ("Acme".into(), 0)];
+        let concentration = Concentration::of(brands);
+        assert_eq!(concentration.shares[0].value, "Acme");
+        assert_eq!(concentration.top, 0.75);
+        assert_eq!(concentration.index, 0.75 * 0.75 + 0.25 * 0.25);
+        let empty = Concentration::of([]);
+        assert!(empty.shares.is_empty());
+        assert_eq!((empty.top, empty.index), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, HashMap};
+
+use entity::{
+    class::{category::Category, tag::Tag},
+    error::{EntityError, ValidateError},
+    model::item,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// separators of multiple tags in a single cell
+pub const TAG_SEPARATORS: [char; 3] = [';', ',', '|'];
+
+/// Item attribute that an imported column can be mapped to.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportField {
+    Name,
+    Category,
+    Brand,
+    Notes,
+    Warmth,
+    Waterproof,
+    Tags,
+}
+impl ImportField {
+    /// the field a spreadsheet column is likely meant for, by its header
+    pub fn guess(column: &str) -> Option<Self> {
+        let normalized = column.trim().to_lowercase().replace([' ', '-'], "_");
+        match &normalized[..] {
+            "name" | "item" | "item_name" | "title" => Some(Self::Name),
+            "category" | "type" | "kind" => Some(Self::Category),
+            "brand" | "maker" | "label" => Some(Self::Brand),
+            "notes" | "note" | "comment" | "comments" | "description" => Some(Self::Notes),
+            "warmth" => Some(Self::Warmth),
+            "waterproof" | "water_proof" => Some(Self::Waterproof),
+            "tags" | "tag" => Some(Self::Tags),
+            _ => None,
+        }
+    }
+}
+
+/// raw cells of an imported row by column
+pub type Record = BTreeMap<String, String>;
+
+/// Columns in order of appearance and records of a CSV with a header row.
+pub fn csv_records(data: &str) -> Result<(Vec<String>, Vec<Record>), csv::Error> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(data.as_bytes());
+    let columns: Vec<String> = reader.headers()?.iter().map(String::from).collect();
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        records.push(columns.iter().cloned().zip(row.iter().map(String::from)).collect());
+    }
+    Ok((columns, records))
+}
+
+/// Columns in order of appearance and records of JSON objects, with arrays joined like tags.
+pub fn json_records(
+    rows: Vec<serde_json::Map<String, serde_json::Value>>,
+) -> (Vec<String>, Vec<Record>) {
+    fn cell(value: serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s,
+            serde_json::Value::Array(values) => {
+                values.into_iter().map(cell).collect::<Vec<_>>().join(";")
+            }
+            value => value.to_string(),
+        }
+    }
+    let mut columns: Vec<String> = Vec::new();
+    let mut records = Vec::new();
+    for row in rows {
+        for column in row.keys() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+        records.push(row.into_iter().map(|(k, v)| (k, cell(v))).collect());
+    }
+    (columns, records)
+}
+
+/// Explicitly mapped columns, and guessed ones for the rest.
+pub fn mapping(
+    columns: &[String],
+    explicit: &HashMap<String, ImportField>,
+) -> BTreeMap<String, ImportField> {
+    columns
+        .iter()
+        .filter_map(|c| {
+            Some((c.clone(), explicit.get(c).copied().or_else(|| ImportField::guess(c))?))
+        })
+        .collect()
+}
+
+/// An item validated from an imported row.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImportedItem {
+    pub name: String,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub notes: Option<String>,
+    pub warmth: i32,
+    pub waterproof: bool,
+    pub tags: Vec<Tag>,
+}
+
+/// Validate a record, collecting every error of the row instead of stopping at the first.
+pub fn parse(
+    record: &Record,
+    mapping: &BTreeMap<String, ImportField>,
+) -> Result<ImportedItem, Vec<EntityError>> {
+    let mut cells: HashMap<ImportField, &str> = HashMap::new();
+    for (column, value) in record {
+        match mapping.get(column) {
+            Some(field) if !value.trim().is_empty() => {
+                cells.insert(*field, value.trim());
+            }
+            _ => {}
+        }
+    }
+    let mut errors = Vec::new();
+    let invalid = |field: &str, value: &str| -> EntityError {
+        let (field, invalid_value) = (field.to_string(), value.to_string());
+        ValidateError::CannotValidateField { field, invalid_value }.into()
+    };
+
+    let name = cells.get(&ImportField::Name).map(|s| s.to_string());
+    if name.is_none() {
+        errors.push(ValidateError::MissingField { missing_field: "name".into() }.into());
+    }
+    let category = match cells.get(&ImportField::Category) {
+        Some(c) => c.to_lowercase().parse().map_err(|e| errors.push(e)).ok(),
+        None => None,
+    };
+    let warmth = match cells.get(&ImportField::Warmth) {
+        Some(w) => match w.parse::<i32>() {
+            Ok(w) => item::Model::validate_warmth(w).map_err(|e| errors.push(e)).ok(),
+            Err(_) => {
+                errors.push(invalid("warmth", w));
+                None
+            }
+        },
+        None => Some(item::Model::DEFAULT_WARMTH),
+    };
+    let waterproof = match cells.get(&ImportField::Waterproof).map(|w| w.to_lowercase()) {
+        None => Some(false),
+        Some(w) if ["true", "yes", "y", "1"].contains(&&w[..]) => Some(true),
+        Some(w) if ["false", "no", "n", "0"].contains(&&w[..]) => Some(false),
+        Some(w) => {
+            errors.push(invalid("waterproof", &w));
+            None
+        }
+    };
+    let mut tags = Vec::new();
+    for tag in cells.get(&ImportField::Tags).iter().flat_map(|t| t.split(TAG_SEPARATORS)) {
+        if !tag.trim().is_empty() {
+            Tag::parse(tag).map(|t| tags.push(t)).unwrap_or_else(|e| errors.push(e));
+        }
+    }
+
+    match (name, warmth, waterproof) {
+        (Some(name), Some(warmth), Some(waterproof)) if errors.is_empty() => Ok(ImportedItem {
+            name,
+            category,
+            brand: cells.get(&ImportField::Brand).map(|s| s.to_string()),
+            notes: cells.get(&ImportField::Notes).map(|s| s.to_string()),
+            warmth,
+            waterproof,
+            tags,
+        }),
+        _ => Err(errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess() {
+        assert_eq!(ImportField::guess(" Item Name "), Some(ImportField::Name));
+        assert_eq!(ImportField::guess("Type"), Some(ImportField::Category));
+        assert_eq!(ImportField::guess("water-proof"), Some(ImportField::Waterproof));
+        assert_eq!(ImportField::guess("price"), None);
+    }
+
+    #[test]
+    fn test_records() {
+        let csv = "Item, Type ,Tags\nshirt,top,\"casual;summer\"\njeans,bottom,\n";
+        let (columns, records) = csv_records(csv).unwrap();
+        assert_eq!(columns, vec!["Item", "Type", "Tags"]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["Tags"], "casual;summer");
+        assert!(csv_records("name\nshirt,top\n").is_err(), "row length must match header");
+
+        let json = serde_json::json!([
+            { "name": "shirt", "tags": ["casual", "summer"], "warmth": 2 },
+            { "name": "jeans", "brand": null }
+        ]);
+        let (columns, records) = json_records(serde_json::from_value(json).unwrap());
+        assert_eq!(columns, vec!["name", "tags", "warmth", "brand"]);
+        assert_eq!((&records[0]["tags"][..], &records[0]["warmth"][..]), ("casual;summer", "2"));
+        assert_eq!(records[1]["brand"], "");
+    }
+
+    #[test]
+    fn test_parse() {
+        let columns: Vec<String> =
+            ["Item", "Kind", "Cozy", "Rain", "Price"].map(String::from).into();
+        let explicit = HashMap::from([("Cozy".to_string(), ImportField::Warmth)]);
+        let mapping = mapping(&columns, &explicit);
+        assert_eq!(mapping.len(), 3, "Rain and Price are not mapped");
+        let record = |cells: [&str; 5]| -> Record {
+            columns.iter().cloned().zip(cells.map(String::from)).collect()
+        };
+
+        let parsed = parse(&record(["Shirt", "TOP", "3", "", "10"]), &mapping).unwrap();
+        assert_eq!(parsed.name, "Shirt");
+        assert_eq!((parsed.category, parsed.warmth), (Some(Category::Top), 3));
+
+        let errors = parse(&record(["", "hat", "hot", "", ""]), &mapping).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].contains("name is required"));
+        assert!(messages[2].contains("warmth hot is invalid"));
+        let errors = parse(&record(["Shirt", "", "9", "", ""]), &mapping).unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use entity::{
+    class::{category::Category, color::Color, id::Id},
+    model::item,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// days without wearing after which a garment is fully idle
+pub const IDLE_DAYS: i64 = 365;
+/// similar garments after which a garment is fully redundant
+pub const DUPLICATES: usize = 3;
+
+/// An active garment as seen by the declutter ranking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kept {
+    pub id: Id<item::Model>,
+    pub name: String,
+    pub category: Option<Category>,
+    /// named color of the most dominant swatch
+    pub color: Option<Color>,
+    pub added_on: NaiveDate,
+    pub last_worn: Option<NaiveDate>,
+}
+impl Kept {
+    /// garments of the same category and color are interchangeable
+    pub fn is_similar(&self, other: &Kept) -> bool {
+        self.id != other.id
+            && self.category.is_some()
+            && self.color.is_some()
+            && (self.category, self.color) == (other.category, other.color)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Candidate {
+    pub id: Id<item::Model>,
+    /// days since last worn, or since added if never worn
+    pub idle_days: i64,
+    pub duplicates: usize,
+    /// 0 to 1000, higher is a better candidate
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+/// Rank garments by how long they have been idle and by how many similar ones there are.
+pub fn rank(kept: &[Kept], today: &NaiveDate) -> Vec<Candidate> {
+    let mut candidates: Vec<_> = kept
+        .iter()
+        .map(|garment| {
+            let since = garment.last_worn.unwrap_or(garment.added_on);
+            let idle_days = (*today - since).num_days().max(0);
+            let duplicates = kept.iter().filter(|other| garment.is_similar(other)).count();
+            let idle = (idle_days as f64 / IDLE_DAYS as f64).min(1.0);
+            let redundant = (duplicates as f64 / DUPLICATES as f64).min(1.0);
+            let score = ((0.7 * idle + 0.3 * redundant) * 1000.0).round() as u32;
+
+            let mut reasons = Vec::new();
+            match garment.last_worn {
+                Some(_) => reasons.push(format!("not worn for {} days", idle_days)),
+                None => reasons.push(format!("never worn since added {} days ago", idle_days)),
+            }
+            if let (Some(category), Some(color), true) =
+                (garment.category, garment.color, duplicates > 0)
+            {
+                reasons.push(format!("{} similar {} in {}", duplicates, category, color));
+            }
+            Candidate { id: garment.id.clone(), idle_days, duplicates, score, reasons }
+        })
+        .collect();
+    candidates.sort_by(|x, y| y.score.cmp(&x.score).then(y.idle_days.cmp(&x.idle_days)));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(name: &str, color: Option<Color>, added: u32, worn: Option<u32>) -> Kept {
+        let day = |d| NaiveDate::from_ymd_opt(2023, 1, 1).unwrap() + chrono::Duration::days(d);
+        Kept {
+            id: Id::create(),
+            name: name.into(),
+            category: Some(Category::Top),
+            color,
+            added_on: day(added as i64),
+            last_worn: worn.map(|w| day(w as i64)),
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let closet = [
+            kept("favorite", Some(Color::Navy), 0, Some(360)),
+            kept("forgotten", Some(Color::Red), 0, Some(10)),
+            kept("unworn", None, 100, None),
+            kept("navy twin", Some(Color::Navy), 0, Some(355)),
+        ];
+        let ranked = rank(&closet, &today);
+        let order: Vec<_> = ranked
+            .iter()
+            .map(|c| closet.iter().find(|k| k.id == c.id).unwrap().name.as_str())
+            .collect();
+        assert_eq!(order, vec!["forgotten", "unworn", "navy twin", "favorite"]);
+        assert_eq!(ranked[0].idle_days, 355);
+        assert_eq!(ranked[1].reasons, vec!["never worn since added 265 days ago".to_string()]);
+        assert_eq!(ranked[2].duplicates, 1);
+        assert!(ranked[2].reasons.contains(&"1 similar top in navy".to_string()));
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_duplicates_raise_score() {
+        let today = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+        let alone = rank(&[kept("white tee", Some(Color::White), 0, Some(20))], &today);
+        let tees: Vec<_> =
+            (0..4).map(|i| kept("white tee", Some(Color::White), 0, Some(20 + i))).collect();
+        let crowded = rank(&tees, &today);
+        assert_eq!(crowded.iter().map(|c| c.duplicates).max(), Some(3));
+        assert!(crowded.iter().all(|c| c.score > alone[0].score));
+    }
+}
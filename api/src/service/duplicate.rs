@@ -0,0 +1,153 @@
+use entity::{
+    class::{category::Category, color::Color, id::Id},
+    model::item,
+};
+use image::{imageops::FilterType, DynamicImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// photos whose hashes differ in at most this many bits look alike
+pub const PHOTO_DISTANCE: u32 = 10;
+/// pairs scoring below this are not reported by default
+pub const THRESHOLD: i32 = 500;
+
+/// Difference hash: whether each pixel of a 9x8 grayscale thumbnail is brighter than its right
+/// neighbour, robust to scaling and compression.
+pub fn perceptual_hash(image: &DynamicImage) -> u64 {
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            hash |= (gray.get_pixel(x, y)[0] > gray.get_pixel(x + 1, y)[0]) as u64;
+        }
+    }
+    hash
+}
+
+/// number of differing bits
+pub fn distance(x: u64, y: u64) -> u32 {
+    (x ^ y).count_ones()
+}
+
+/// A garment as compared by the detector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Garment {
+    pub id: Id<item::Model>,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub colors: Vec<Color>,
+    pub hashes: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Similarity {
+    pub id: Id<item::Model>,
+    pub other: Id<item::Model>,
+    /// 0 to 1000, higher is more likely the same garment
+    pub score: i32,
+    pub reasons: Vec<String>,
+}
+
+/// Score how likely two garments are the same or near-identical; garments of different
+/// categories never are.
+pub fn compare(x: &Garment, y: &Garment) -> Option<Similarity> {
+    if x.id == y.id || matches!((x.category, y.category), (Some(c), Some(d)) if c != d) {
+        return None;
+    }
+    let (mut score, mut reasons) = (0.0, Vec::new());
+    if let (Some(category), Some(_)) = (x.category, y.category) {
+        score += 200.0;
+        reasons.push(format!("both {}", category));
+    }
+    let brand = |g: &Garment| g.brand.as_deref().map(|b| b.trim().to_lowercase());
+    if brand(x).is_some_and(|b| !b.is_empty()) && brand(x) == brand(y) {
+        score += 150.0;
+        reasons.push(format!("both by {}", x.brand.as_deref().unwrap_or_default().trim()));
+    }
+    let distinct = |colors: &[Color]| {
+        colors.iter().fold(Vec::new(), |mut distinct, c| {
+            if !distinct.contains(c) {
+                distinct.push(*c);
+            }
+            distinct
+        })
+    };
+    let (xc, yc) = (distinct(&x.colors), distinct(&y.colors));
+    let shared: Vec<_> = xc.iter().filter(|c| yc.contains(c)).collect();
+    let union = xc.len() + yc.len() - shared.len();
+    if !shared.is_empty() {
+        score += 250.0 * shared.len() as f64 / union as f64;
+        let names: Vec<_> = shared.iter().map(|c| c.to_string()).collect();
+        reasons.push(format!("shared colors {}", names.join(", ")));
+    }
+    let closest = x.hashes.iter().flat_map(|h| y.hashes.iter().map(|o| distance(*h, *o))).min();
+    if let Some(closest) = closest.filter(|d| *d <= PHOTO_DISTANCE) {
+        score += 400.0 * (1.0 - closest as f64 / (PHOTO_DISTANCE + 1) as f64);
+        reasons.push(format!("photos differ in {} of 64 bits", closest));
+    }
+    let score = score.round() as i32;
+    Some(Similarity { id: x.id.clone(), other: y.id.clone(), score, reasons })
+}
+
+/// Pairs scoring at least `threshold`, the most similar first.
+pub fn detect(garments: &[Garment], threshold: i32) -> Vec<Similarity> {
+    let mut pairs: Vec<_> = garments
+        .iter()
+        .enumerate()
+        .flat_map(|(i, x)| garments[i + 1..].iter().filter_map(move |y| compare(x, y)))
+        .filter(|s| s.score >= threshold)
+        .collect();
+    pairs.sort_by_key(|s| std::cmp::Reverse(s.score));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+
+    use super::*;
+
+    fn gradient(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let v = ((x * 255 / width) ^ (y * 255 / height)) as u8;
+            Rgb(if flip { [255 - v; 3] } else { [v; 3] })
+        }))
+    }
+
+    #[test]
+    fn test_perceptual_hash() {
+        let original = perceptual_hash(&gradient(120, 100, false));
+        let resized = perceptual_hash(&gradient(60, 50, false));
+        let inverted = perceptual_hash(&gradient(120, 100, true));
+        assert!(distance(original, resized) <= 4, "robust to scaling");
+        assert!(distance(original, inverted) > 32);
+        assert_eq!(distance(0, u64::MAX), 64);
+    }
+
+    #[test]
+    fn test_detect() {
+        let garment = |category, brand: &str, colors: &[Color], hashes: &[u64]| Garment {
+            id: Id::create(),
+            category: Some(category),
+            brand: Some(brand.into()),
+            colors: colors.to_vec(),
+            hashes: hashes.to_vec(),
+        };
+        let garments = [
+            garment(Category::Top, "Acme", &[Color::Navy, Color::White], &[0xff00]),
+            garment(Category::Top, "acme", &[Color::Navy, Color::White], &[0xff01]),
+            garment(Category::Top, "Zed", &[Color::Navy], &[u64::MAX]),
+            garment(Category::Bottom, "Acme", &[Color::Navy, Color::White], &[0xff00]),
+        ];
+        let pairs = detect(&garments, THRESHOLD);
+        assert_eq!(pairs.len(), 1, "other categories are never duplicates");
+        let pair = &pairs[0];
+        assert_eq!((&pair.id, &pair.other), (&garments[0].id, &garments[1].id));
+        assert_eq!(pair.score, 200 + 150 + 250 + 364);
+        assert_eq!(pair.reasons.len(), 4);
+        let weak = compare(&garments[0], &garments[2]).unwrap();
+        assert_eq!(weak.score, 200 + 125);
+        assert!(detect(&garments, 0).len() > 1);
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// content lines longer than this many octets are folded
+pub const LINE_OCTETS: usize = 75;
+pub const PRODID: &str = "-//closet//planned outfits//EN";
+
+/// All-day event of an RFC 5545 calendar.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Event {
+    /// globally unique and stable across exports
+    pub uid: String,
+    /// last modification
+    pub stamp: DateTime<Utc>,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+}
+
+/// Render `events` as an iCalendar (RFC 5545) document.
+pub fn calendar(name: &str, events: &[Event]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", event.stamp.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")));
+        let end = event.date.succ_opt().unwrap_or(event.date);
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// escape TEXT property values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// split a content line into lines of at most [`LINE_OCTETS`] octets, never inside a character
+pub fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > LINE_OCTETS {
+            // the leading space of the continuation counts towards its length
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("shirt, jeans; boots"), "shirt\\, jeans\\; boots");
+        assert_eq!(escape("a\\b\r\nc"), "a\\\\b\\nc");
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("SUMMARY:short"), "SUMMARY:short");
+        let long = format!("SUMMARY:{}", "a".repeat(100));
+        let folded = fold(&long);
+        let lines: Vec<_> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), long);
+
+        let multibyte = format!("SUMMARY:{}", "あ".repeat(40));
+        let folded = fold(&multibyte);
+        assert!(folded.split("\r\n").all(|l| l.len() <= LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), multibyte);
+    }
+
+    #[test]
+    fn test_calendar() {
+        let event = Event {
+            uid: "01h2x@closet".into(),
+            stamp: DateTime::parse_from_rfc3339("2023-06-30T12:34:56+09:00").unwrap().into(),
+            date: NaiveDate::from_ymd_opt(2023, 7, 1).unwrap(),
+            summary: "weekend".into(),
+            description: Some("shirt, jeans".into()),
+        };
+        let ics = calendar("outfits", &[event]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nDTSTAMP:20230630T033456Z\r\n"));
+        assert!(ics.contains("\r\nDTSTART;VALUE=DATE:20230701\r\nDTEND;VALUE=DATE:20230702\r\n"));
+        assert!(ics.contains("\r\nDESCRIPTION:shirt\\, jeans\r\n"));
+        assert!(!ics.replace("\r\n", "").contains('\n'));
+    }
+}
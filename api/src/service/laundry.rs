@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use entity::{
+    class::{
+        care::{Bleach, Care, Process, Professional, TumbleDry, Wash},
+        color::Color,
+        id::Id,
+    },
+    model::item,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// machine wash assumed for garments without a care label
+pub const ASSUMED_WASH: Wash = Wash::Machine { temperature: 40, process: Process::Normal };
+
+/// Colors washed together without dyes bleeding onto lighter garments.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Shade {
+    Light,
+    Colored,
+    Dark,
+}
+impl Shade {
+    pub fn of(color: &Color) -> Self {
+        match color {
+            Color::White | Color::Beige | Color::LightBlue | Color::Pink | Color::Yellow => {
+                Self::Light
+            }
+            Color::Black | Color::Navy | Color::Brown | Color::Burgundy | Color::Olive => {
+                Self::Dark
+            }
+            _ => Self::Colored,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    Machine,
+    Hand,
+    /// cannot be washed at home
+    Professional,
+}
+
+/// A worn garment waiting for the laundry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dirty {
+    pub id: Id<item::Model>,
+    pub care: Option<Care>,
+    /// named color of the most dominant swatch
+    pub color: Option<Color>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Load {
+    pub method: Method,
+    pub shade: Option<Shade>,
+    /// warmest temperature every garment of the load allows, for machine and hand wash
+    pub temperature: Option<u8>,
+    /// gentlest process any garment of the load needs
+    pub process: Option<Process>,
+    /// treatment of a professional load, unknown if the garments have no such symbol
+    pub professional: Option<Professional>,
+    /// most restrictive of the garments, unknown symbols count as the most restrictive
+    pub bleach: Bleach,
+    pub dry: TumbleDry,
+    pub items: Vec<Id<item::Model>>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Plan {
+    pub loads: Vec<Load>,
+    /// garments which can be neither washed nor dry cleaned, so no load takes them
+    pub unwashable: Vec<Id<item::Model>>,
+}
+
+/// Group garments into loads that can be washed together: by method, shade and process,
+/// washed at the lowest temperature and with the most restrictive bleach and drying.
+/// Professional loads are grouped by their treatment instead.
+pub fn plan(dirty: &[Dirty]) -> Plan {
+    type Key = (Method, Option<Shade>, Option<Process>, Option<Professional>);
+    let mut loads: BTreeMap<Key, Load> = BTreeMap::new();
+    let mut unwashable = Vec::new();
+    for garment in dirty {
+        let care = garment.care.unwrap_or_default();
+        let (method, temperature, process) = match care.wash.unwrap_or(ASSUMED_WASH) {
+            Wash::Machine { temperature, process } => {
+                (Method::Machine, Some(temperature), Some(process))
+            }
+            Wash::Hand => (Method::Hand, Some(Wash::HAND_TEMPERATURE), None),
+            Wash::DoNotWash if care.professional == Some(Professional::DoNotDryClean) => {
+                unwashable.push(garment.id.clone());
+                continue;
+            }
+            Wash::DoNotWash => (Method::Professional, None, None),
+        };
+        let (shade, professional) = match method {
+            Method::Professional => (None, care.professional),
+            _ => (Some(garment.color.as_ref().map_or(Shade::Colored, Shade::of)), None),
+        };
+        let key = (method, shade, process, professional);
+        let load = loads.entry(key).or_insert_with(|| Load {
+            method,
+            shade,
+            temperature,
+            process,
+            professional,
+            bleach: Bleach::Any,
+            dry: TumbleDry::Normal,
+            items: Vec::new(),
+            notes: Vec::new(),
+        });
+        load.temperature = load.temperature.min(temperature);
+        load.bleach = load.bleach.max(care.bleach.unwrap_or(Bleach::DoNotBleach));
+        load.dry = load.dry.max(care.dry.unwrap_or(TumbleDry::DoNotTumbleDry));
+        load.items.push(garment.id.clone());
+        let assumed = "some garments have no wash symbol and are assumed to take 40°C";
+        if care.wash.is_none() && !load.notes.iter().any(|n| n == assumed) {
+            load.notes.push(assumed.into());
+        }
+    }
+    Plan { loads: loads.into_values().collect(), unwashable }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dirty(label: &str, color: Option<Color>) -> Dirty {
+        Dirty { id: Id::create(), care: Some(Care::parse(label).unwrap()), color }
+    }
+
+    #[test]
+    fn test_plan() {
+        let garments = [
+            dirty("wash_60,bleach,tumble_dry", Some(Color::White)),
+            dirty("wash_40,non_chlorine_bleach,tumble_dry_low", Some(Color::Beige)),
+            dirty("wash_40,tumble_dry", Some(Color::Navy)),
+            dirty("wash_30_mild,do_not_tumble_dry", Some(Color::Black)),
+            dirty("hand_wash", Some(Color::Red)),
+            dirty("do_not_wash,dry_clean_p", Some(Color::Gray)),
+            Dirty { id: Id::create(), care: None, color: None },
+            dirty("do_not_wash,wet_clean", Some(Color::Gray)),
+            dirty("do_not_wash,dry_clean_p", Some(Color::Black)),
+            dirty("do_not_wash,do_not_dry_clean", Some(Color::Red)),
+        ];
+        let Plan { loads, unwashable } = plan(&garments);
+        assert_eq!(loads.len(), 7);
+        let load_of = |g: &Dirty| loads.iter().find(|l| l.items.contains(&g.id)).unwrap();
+
+        let whites = load_of(&garments[0]);
+        assert_eq!(whites.items, vec![garments[0].id.clone(), garments[1].id.clone()]);
+        assert_eq!(whites.temperature, Some(40), "as warm as the most delicate allows");
+        assert_eq!((whites.bleach, whites.dry), (Bleach::NonChlorine, TumbleDry::Low));
+
+        let darks = load_of(&garments[2]);
+        assert_eq!(darks.items.len(), 1, "mild process is a separate load");
+        assert_eq!(darks.bleach, Bleach::DoNotBleach, "unknown bleach symbol");
+        assert_eq!(load_of(&garments[3]).process, Some(Process::Mild));
+        assert_eq!(load_of(&garments[4]).method, Method::Hand);
+        let professional = load_of(&garments[5]);
+        assert_eq!((professional.method, professional.temperature), (Method::Professional, None));
+        assert_eq!(professional.professional, Some(Professional::DryCleanP));
+        assert_eq!(professional.items, vec![garments[5].id.clone(), garments[8].id.clone()]);
+        assert_eq!(load_of(&garments[7]).professional, Some(Professional::WetClean));
+        assert_eq!(unwashable, vec![garments[9].id.clone()]);
+
+        let unlabeled = load_of(&garments[6]);
+        assert_eq!((unlabeled.shade, unlabeled.temperature), (Some(Shade::Colored), Some(40)));
+        assert_eq!(unlabeled.notes.len(), 1);
+    }
+}
@@ -0,0 +1,54 @@
+/// rating assumed for garments and outfits nobody rated yet
+pub const NEUTRAL_RATING: f64 = 3.0;
+
+/// How much a user loves a garment or an outfit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Love {
+    pub favorite: bool,
+    /// ratings of the outfits worn, 1 to 5
+    pub ratings: Vec<i32>,
+    pub wears: usize,
+}
+impl Love {
+    pub fn average(&self) -> Option<f64> {
+        let sum: i32 = self.ratings.iter().sum();
+        (!self.ratings.is_empty()).then(|| sum as f64 / self.ratings.len() as f64)
+    }
+
+    /// Ascending sort keys, the most loved first: favorites, then the best rated (average in
+    /// thousandths, rounded down as the integer division of the listing query does), then the
+    /// most worn.
+    pub fn keys(&self) -> Vec<i64> {
+        let sum: i64 = self.ratings.iter().map(|r| *r as i64).sum();
+        let rating = match self.ratings.len() as i64 {
+            0 => (NEUTRAL_RATING * 1000.0) as i64,
+            count => sum * 1000 / count,
+        };
+        vec![(!self.favorite).into(), -rating, -(self.wears as i64)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let love =
+            |favorite, ratings: &[i32], wears| Love { favorite, ratings: ratings.to_vec(), wears };
+        let mut loved = [
+            ("unrated", love(false, &[], 0)),
+            ("disliked", love(false, &[1, 2], 9)),
+            ("favorite", love(true, &[], 0)),
+            ("liked", love(false, &[5, 4], 1)),
+            ("worn", love(false, &[], 4)),
+            ("also unrated", love(false, &[], 0)),
+        ];
+        loved.sort_by_key(|(_, love)| love.keys());
+        let order: Vec<_> = loved.iter().map(|(name, _)| *name).collect();
+        assert_eq!(order, ["favorite", "liked", "worn", "unrated", "also unrated", "disliked"]);
+        assert_eq!(love(false, &[5, 4], 0).average(), Some(4.5));
+        assert_eq!(love(false, &[], 0).average(), None);
+        assert_eq!(love(false, &[5, 4, 4], 2).keys(), vec![1, -4333, -2]);
+    }
+}
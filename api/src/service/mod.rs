@@ -0,0 +1,14 @@
+pub mod analytics;
+pub mod bulk;
+pub mod declutter;
+pub mod duplicate;
+pub mod icalendar;
+pub mod laundry;
+pub mod loved;
+pub mod packing;
+pub mod palette;
+pub mod rotation;
+pub mod search;
+pub mod sizing;
+pub mod suggestion;
+pub mod weather;
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use entity::{
+    class::{category::Category, id::Id, season::Season, tag::Tag},
+    model::item,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::suggestion::Garment;
+
+/// days a garment of the category can be worn before it needs washing, `None` for the whole trip
+pub fn reuse_limit(category: &Category) -> Option<i64> {
+    match category {
+        Category::Top => Some(1),
+        Category::Bottom => Some(3),
+        Category::Outerwear | Category::Shoes | Category::Accessory => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub days: i64,
+    pub season: Season,
+    pub activities: Vec<Tag>,
+    /// overrides of [`reuse_limit`]
+    pub reuse: HashMap<Category, i64>,
+}
+impl Plan {
+    /// categories that must be covered every day of the trip
+    pub fn slots(&self) -> Vec<Category> {
+        let mut slots = vec![Category::Top, Category::Bottom, Category::Shoes];
+        if matches!(self.season, Season::Autumn | Season::Winter) {
+            slots.push(Category::Outerwear);
+        }
+        slots
+    }
+    /// days a garment of the category can be worn during the trip
+    pub fn limit(&self, category: &Category) -> i64 {
+        let limit = self.reuse.get(category).copied().or_else(|| reuse_limit(category));
+        limit.unwrap_or(self.days).clamp(1, self.days.max(1))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Packed {
+    pub id: Id<item::Model>,
+    pub days: i64,
+    pub reason: String,
+}
+
+/// days of the trip that cannot be covered by the closet
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Shortage {
+    pub category: Category,
+    pub days: i64,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PackingList {
+    pub packed: Vec<Packed>,
+    pub shortages: Vec<Shortage>,
+}
+
+/// Pick garments that cover every day of the trip.
+///
+/// Garments tagged with a planned activity are packed first, one per activity and category,
+/// then the remaining days of each slot are covered by wearing every garment up to its limit.
+pub fn pack(garments: &[Garment], plan: &Plan) -> PackingList {
+    let slots = plan.slots();
+    let mut list = PackingList::default();
+    for category in Category::ALL {
+        let matches = |g: &Garment| plan.activities.iter().filter(|a| g.tags.contains(a)).count();
+        let mut candidates: Vec<_> = garments
+            .iter()
+            .filter(|g| g.category == category)
+            .filter(|g| g.seasons().is_empty() || g.seasons().contains(&plan.season))
+            .collect();
+        candidates.sort_by(|x, y| {
+            matches(y).cmp(&matches(x)).then_with(|| x.id.identifier().cmp(&y.id.identifier()))
+        });
+
+        let (limit, covered) = (plan.limit(&category), slots.contains(&category));
+        let mut remaining = if covered { plan.days } else { 0 };
+        let mut picked: Vec<&Garment> = Vec::new();
+        for activity in &plan.activities {
+            let found =
+                candidates.iter().find(|g| g.tags.contains(activity) && !picked.contains(g));
+            if let Some(garment) = found {
+                let days = limit.min(remaining.max(1));
+                remaining = (remaining - days).max(0);
+                let reason = format!("for {}", activity);
+                list.packed.push(Packed { id: garment.id.clone(), days, reason });
+                picked.push(garment);
+            }
+        }
+        for garment in candidates.iter().filter(|g| !picked.contains(g)) {
+            if remaining <= 0 {
+                break;
+            }
+            let days = limit.min(remaining);
+            remaining -= days;
+            let reason = format!("{} for {} of {} days", category, days, plan.days);
+            list.packed.push(Packed { id: garment.id.clone(), days, reason });
+        }
+        if remaining > 0 {
+            list.shortages.push(Shortage { category, days: remaining });
+        }
+    }
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn garment(name: &str, category: Category, tags: &[&str]) -> Garment {
+        Garment {
+            id: Id::create(),
+            name: name.into(),
+            category,
+            color: None,
+            tags: tags.iter().map(|t| Tag::parse(t).unwrap()).collect(),
+            last_worn: None,
+            warmth: 1,
+            waterproof: false,
+        }
+    }
+    fn plan(days: i64, activities: &[&str]) -> Plan {
+        Plan {
+            days,
+            season: Season::Summer,
+            activities: activities.iter().map(|t| Tag::parse(t).unwrap()).collect(),
+            reuse: HashMap::new(),
+        }
+    }
+    fn days_of(list: &PackingList, garment: &Garment) -> Option<i64> {
+        list.packed.iter().find(|p| p.id == garment.id).map(|p| p.days)
+    }
+
+    #[test]
+    fn test_cover_days() {
+        let closet = [
+            garment("white tee", Category::Top, &[]),
+            garment("black tee", Category::Top, &[]),
+            garment("gray tee", Category::Top, &[]),
+            garment("jeans", Category::Bottom, &[]),
+            garment("chinos", Category::Bottom, &[]),
+            garment("sneakers", Category::Shoes, &[]),
+            garment("loafers", Category::Shoes, &[]),
+            garment("wool coat", Category::Outerwear, &["winter"]),
+        ];
+        let list = pack(&closet, &plan(5, &[]));
+        let tops: i64 = closet[..3].iter().filter_map(|g| days_of(&list, g)).sum();
+        assert_eq!(tops, 3, "each top is worn only once");
+        let mut bottoms: Vec<_> = closet[3..5].iter().filter_map(|g| days_of(&list, g)).collect();
+        bottoms.sort();
+        assert_eq!(bottoms, vec![2, 3]);
+        let shoes: Vec<_> = closet[5..7].iter().filter_map(|g| days_of(&list, g)).collect();
+        assert_eq!(shoes, vec![5], "one pair of shoes is enough");
+        assert_eq!(days_of(&list, &closet[7]), None, "coat is for winter");
+        assert_eq!(list.shortages, vec![Shortage { category: Category::Top, days: 2 }]);
+
+        let reuse = HashMap::from([(Category::Top, 2)]);
+        let list = pack(&closet, &Plan { reuse, ..plan(5, &[]) });
+        assert!(list.shortages.is_empty());
+    }
+
+    #[test]
+    fn test_activities() {
+        let closet = [
+            garment("tee", Category::Top, &[]),
+            garment("shorts", Category::Bottom, &[]),
+            garment("sneakers", Category::Shoes, &[]),
+            garment("hiking boots", Category::Shoes, &["hiking"]),
+            garment("swimsuit", Category::Accessory, &["beach"]),
+            garment("tie", Category::Accessory, &["formal"]),
+        ];
+        let list = pack(&closet, &plan(1, &["hiking", "beach"]));
+        let reasons: Vec<_> = list.packed.iter().map(|p| p.reason.as_str()).collect();
+        assert!(reasons.contains(&"for hiking") && reasons.contains(&"for beach"));
+        assert_eq!(days_of(&list, &closet[3]), Some(1));
+        assert_eq!(days_of(&list, &closet[2]), None, "hiking boots already cover the day");
+        assert_eq!(days_of(&list, &closet[5]), None, "no formal activity planned");
+        assert!(list.shortages.is_empty());
+    }
+}
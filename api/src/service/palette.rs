@@ -0,0 +1,158 @@
+use entity::class::color::Rgb;
+use image::DynamicImage;
+
+/// photos are downscaled to this edge length before extraction
+pub const THUMBNAIL: u32 = 64;
+/// number of swatches in an extracted palette
+pub const PALETTE_SIZE: usize = 5;
+/// delta E below which two swatches look the same
+pub const JUST_NOTICEABLE: f64 = 2.3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Swatch {
+    pub rgb: Rgb,
+    /// share of the opaque pixels, in per mille
+    pub ratio: i32,
+}
+
+/// Extract up to `size` dominant colors with median cut, most dominant first.
+/// Transparent pixels (e.g. removed background) are ignored.
+pub fn dominant_colors(image: &DynamicImage, size: usize) -> Vec<Swatch> {
+    let rgba = if image.width() > THUMBNAIL || image.height() > THUMBNAIL {
+        image.thumbnail(THUMBNAIL, THUMBNAIL).to_rgba8()
+    } else {
+        image.to_rgba8()
+    };
+    let pixels: Vec<[u8; 3]> =
+        rgba.pixels().filter(|p| p[3] >= 128).map(|p| [p[0], p[1], p[2]]).collect();
+    let total = pixels.len();
+    if total == 0 || size == 0 {
+        return Vec::new();
+    }
+
+    let mut buckets = vec![pixels];
+    while buckets.len() < size {
+        let Some((index, channel, _)) = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                let (channel, range) = widest_channel(bucket);
+                (i, channel, range)
+            })
+            .filter(|&(_, _, range)| range > 0)
+            .max_by_key(|&(i, _, range)| (range, std::cmp::Reverse(i)))
+        else {
+            break;
+        };
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_unstable_by_key(|p| (p[channel], p[(channel + 1) % 3], p[(channel + 2) % 3]));
+        let upper = bucket.split_off(bucket.len() / 2);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    // median cut may split a single flat color, so merge what cannot be told apart
+    let mut merged: Vec<(Rgb, usize)> = Vec::new();
+    for bucket in buckets {
+        let rgb = average(&bucket);
+        match merged.iter_mut().find(|(m, _)| m.lab().delta_e(&rgb.lab()) < JUST_NOTICEABLE) {
+            Some((m, count)) => {
+                let weighted = |x: u8, y: u8| {
+                    ((x as usize * *count + y as usize * bucket.len()) / (*count + bucket.len()))
+                        as u8
+                };
+                *m = Rgb(weighted(m.0, rgb.0), weighted(m.1, rgb.1), weighted(m.2, rgb.2));
+                *count += bucket.len();
+            }
+            None => merged.push((rgb, bucket.len())),
+        }
+    }
+    let mut swatches: Vec<_> = merged
+        .into_iter()
+        .map(|(rgb, count)| Swatch { rgb, ratio: (count * 1000 / total) as i32 })
+        .collect();
+    swatches.sort_by_key(|s| (std::cmp::Reverse(s.ratio), s.rgb.to_string()));
+    swatches
+}
+
+fn widest_channel(bucket: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (min, max) = bucket
+                .iter()
+                .fold((u8::MAX, u8::MIN), |(min, max), p| (min.min(p[c]), max.max(p[c])));
+            (c, max.saturating_sub(min))
+        })
+        .max_by_key(|&(c, range)| (range, std::cmp::Reverse(c)))
+        .expect("three channels")
+}
+
+fn average(bucket: &[[u8; 3]]) -> Rgb {
+    let len = bucket.len().max(1) as u64;
+    let sum = bucket.iter().fold([0u64; 3], |mut sum, p| {
+        (0..3).for_each(|c| sum[c] += p[c] as u64);
+        sum
+    });
+    let channel = |c: usize| ((sum[c] + len / 2) / len) as u8;
+    Rgb(channel(0), channel(1), channel(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::class::color::Color;
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    fn striped(colors: &[([u8; 4], u32)]) -> DynamicImage {
+        let height = colors.iter().map(|(_, rows)| rows).sum();
+        let mut image = RgbaImage::new(10, height);
+        let mut y = 0;
+        for &(color, rows) in colors {
+            for row in y..y + rows {
+                for x in 0..10 {
+                    image.put_pixel(x, row, Rgba(color));
+                }
+            }
+            y += rows;
+        }
+        DynamicImage::ImageRgba8(image)
+    }
+
+    #[test]
+    fn test_dominant_colors() {
+        let image = striped(&[([30, 40, 70, 255], 30), ([245, 245, 245, 255], 10)]);
+        let palette = dominant_colors(&image, PALETTE_SIZE);
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette[0], Swatch { rgb: Rgb(30, 40, 70), ratio: 750 });
+        assert_eq!(palette[1], Swatch { rgb: Rgb(245, 245, 245), ratio: 250 });
+        assert_eq!(Color::nearest(&palette[0].rgb), Color::Navy);
+        assert_eq!(Color::nearest(&palette[1].rgb), Color::White);
+    }
+
+    #[test]
+    fn test_transparent_ignored() {
+        let image = striped(&[([200, 20, 30, 255], 10), ([0, 0, 0, 0], 30)]);
+        let palette = dominant_colors(&image, PALETTE_SIZE);
+        assert_eq!(palette, vec![Swatch { rgb: Rgb(200, 20, 30), ratio: 1000 }]);
+
+        let empty = striped(&[([0, 0, 0, 0], 10)]);
+        assert!(dominant_colors(&empty, PALETTE_SIZE).is_empty());
+    }
+
+    #[test]
+    fn test_palette_size() {
+        let image = striped(&[
+            ([20, 20, 20, 255], 8),
+            ([240, 210, 60, 255], 8),
+            ([40, 120, 60, 255], 8),
+            ([190, 30, 45, 255], 8),
+            ([40, 90, 180, 255], 8),
+            ([240, 160, 180, 255], 8),
+        ]);
+        assert_eq!(dominant_colors(&image, 3).len(), 3);
+        assert_eq!(dominant_colors(&image, 6).len(), 6);
+        let total: i32 = dominant_colors(&image, 6).iter().map(|s| s.ratio).sum();
+        assert!((990..=1000).contains(&total));
+    }
+}
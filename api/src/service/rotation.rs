@@ -0,0 +1,130 @@
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        id::Id,
+        season::{Hemisphere, Season},
+    },
+    model::item,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// clothes of the next season are brought out this many days before it starts
+pub const LEAD_DAYS: i64 = 21;
+
+/// seasons whose clothes should be at hand on `date` in the hemisphere
+pub fn wanted(date: &NaiveDate, hemisphere: Hemisphere) -> Vec<Season> {
+    let soon = *date + chrono::Duration::days(LEAD_DAYS);
+    let (now, soon) = (Season::of(date, hemisphere), Season::of(&soon, hemisphere));
+    if now == soon {
+        vec![now]
+    } else {
+        vec![now, soon]
+    }
+}
+
+/// A garment and where it is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stowed {
+    pub id: Id<item::Model>,
+    /// empty means all seasons
+    pub seasons: Vec<Season>,
+    /// whether it is at hand, rather than put away
+    pub active: bool,
+    /// whether it can be put away right now
+    pub clean: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// bring into active storage
+    In,
+    /// put away
+    Out,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Proposal {
+    pub id: Id<item::Model>,
+    pub direction: Direction,
+    pub reason: String,
+}
+
+/// Propose bringing in garments of the wanted seasons and putting away clean ones of others.
+pub fn rotate(stowed: &[Stowed], date: &NaiveDate, hemisphere: Hemisphere) -> Vec<Proposal> {
+    let wanted = wanted(date, hemisphere);
+    let names = |seasons: &[Season]| {
+        seasons.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" and ")
+    };
+    stowed
+        .iter()
+        .filter(|garment| !garment.seasons.is_empty())
+        .filter_map(|garment| {
+            let needed: Vec<_> =
+                wanted.iter().copied().filter(|s| garment.seasons.contains(s)).collect();
+            let (direction, reason) = match (garment.active, needed.is_empty()) {
+                (false, false) => (Direction::In, format!("needed for {}", names(&needed))),
+                (true, true) if garment.clean => {
+                    (Direction::Out, format!("only for {}", names(&garment.seasons)))
+                }
+                _ => return None,
+            };
+            Some(Proposal { id: garment.id.clone(), direction, reason })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wanted() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+        let northern = |m, d| wanted(&date(m, d), Hemisphere::Northern);
+        assert_eq!(northern(7, 1), vec![Season::Summer]);
+        assert_eq!(northern(8, 20), vec![Season::Summer, Season::Autumn]);
+        assert_eq!(northern(12, 31), vec![Season::Winter]);
+        let southern = |m, d| wanted(&date(m, d), Hemisphere::Southern);
+        assert_eq!(southern(7, 1), vec![Season::Winter]);
+        assert_eq!(southern(8, 20), vec![Season::Winter, Season::Spring]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let stowed = |seasons: &[Season], active, clean| Stowed {
+            id: Id::create(),
+            seasons: seasons.to_vec(),
+            active,
+            clean,
+        };
+        let closet = [
+            stowed(&[Season::Winter], true, true),
+            stowed(&[Season::Winter], true, false),
+            stowed(&[Season::Summer], false, true),
+            stowed(&[Season::Summer, Season::Winter], true, true),
+            stowed(&[], false, true),
+            stowed(&[Season::Autumn], false, true),
+        ];
+        let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+        let proposals = rotate(&closet, &date(6, 15), Hemisphere::Northern);
+        let direction =
+            |i: usize| proposals.iter().find(|p| p.id == closet[i].id).map(|p| p.direction);
+        assert_eq!(direction(0), Some(Direction::Out));
+        assert_eq!(direction(1), None, "worn clothes are not put away");
+        assert_eq!(direction(2), Some(Direction::In));
+        assert_eq!(direction(3), None, "still in season");
+        assert_eq!(direction(4), None, "all season clothes stay where they are");
+        assert_eq!(direction(5), None, "autumn is not close yet");
+        assert_eq!(proposals[0].reason, "only for winter");
+        assert_eq!(proposals[1].reason, "needed for summer");
+
+        let late_summer = rotate(&closet, &date(8, 25), Hemisphere::Northern);
+        assert!(late_summer.iter().any(|p| p.id == closet[5].id && p.direction == Direction::In));
+        let southern = rotate(&closet, &date(6, 15), Hemisphere::Southern);
+        let direction =
+            |i: usize| southern.iter().find(|p| p.id == closet[i].id).map(|p| p.direction);
+        assert_eq!((direction(0), direction(2)), (None, None), "winter down under");
+    }
+}
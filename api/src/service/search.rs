@@ -0,0 +1,184 @@
+use entity::model::{item, outfit};
+use schemars::JsonSchema;
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, LikeExpr, Query, SelectStatement},
+    Condition, DbBackend,
+};
+use serde::{Deserialize, Serialize};
+
+/// Full-text matching of the text columns of items (name, brand and notes) and outfits (name).
+///
+/// Statements select the `id` of rows with a word starting with the term, to be used as subqueries.
+pub trait FullTextSearch: Send + Sync {
+    fn items(&self, term: &str) -> SelectStatement;
+    fn outfits(&self, term: &str) -> SelectStatement;
+}
+
+/// Build the search for the database, falling back to `LIKE` without a full-text index.
+pub fn for_backend(backend: DbBackend) -> Box<dyn FullTextSearch> {
+    match backend {
+        DbBackend::MySql => Box::new(MySqlFullText),
+        DbBackend::Sqlite => Box::new(SqliteFts5),
+        DbBackend::Postgres => Box::new(Like),
+    }
+}
+
+/// InnoDB `FULLTEXT` indexes in boolean mode; words shorter than `innodb_ft_min_token_size` and
+/// stopwords are not indexed.
+pub struct MySqlFullText;
+impl MySqlFullText {
+    fn statement<T: sea_orm::Iden + 'static>(
+        table: T,
+        columns: &str,
+        term: &str,
+    ) -> SelectStatement {
+        Query::select()
+            .column(Alias::new("id"))
+            .from(table)
+            .and_where(Expr::cust_with_values(
+                &format!("MATCH ({}) AGAINST (? IN BOOLEAN MODE)", columns),
+                [format!("{}*", term)],
+            ))
+            .to_owned()
+    }
+}
+impl FullTextSearch for MySqlFullText {
+    fn items(&self, term: &str) -> SelectStatement {
+        Self::statement(item::Entity, "name, brand, notes", term)
+    }
+    fn outfits(&self, term: &str) -> SelectStatement {
+        Self::statement(outfit::Entity, "name", term)
+    }
+}
+
+/// FTS5 virtual tables `items_fts` and `outfits_fts`, kept in sync by triggers.
+pub struct SqliteFts5;
+impl SqliteFts5 {
+    fn statement(table: &str, term: &str) -> SelectStatement {
+        let fts = format!("{}_fts", table);
+        Query::select()
+            .column(Alias::new("id"))
+            .from(Alias::new(&fts))
+            .and_where(Expr::cust_with_values(
+                &format!("{} MATCH ?", fts),
+                [format!("\"{}\"*", term.replace('"', "\"\""))],
+            ))
+            .to_owned()
+    }
+}
+impl FullTextSearch for SqliteFts5 {
+    fn items(&self, term: &str) -> SelectStatement {
+        Self::statement("items", term)
+    }
+    fn outfits(&self, term: &str) -> SelectStatement {
+        Self::statement("outfits", term)
+    }
+}
+
+/// Substring match, for databases without a full-text index.
+pub struct Like;
+impl Like {
+    fn statement<T: sea_orm::Iden + 'static>(
+        table: T,
+        columns: &[&str],
+        term: &str,
+    ) -> SelectStatement {
+        let pattern =
+            format!("%{}%", term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        let mut condition = Condition::any();
+        for column in columns {
+            let lower = Func::lower(Expr::col(Alias::new(column)));
+            let like = LikeExpr::new(pattern.clone()).escape('\\');
+            condition = condition.add(Expr::expr(lower).like(like));
+        }
+        Query::select().column(Alias::new("id")).from(table).cond_where(condition).to_owned()
+    }
+}
+impl FullTextSearch for Like {
+    fn items(&self, term: &str) -> SelectStatement {
+        Self::statement(item::Entity, &["name", "brand", "notes"], term)
+    }
+    fn outfits(&self, term: &str) -> SelectStatement {
+        Self::statement(outfit::Entity, &["name"], term)
+    }
+}
+
+/// lower-cased words of the query, which must all match
+pub fn terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split(|c: char| !c.is_alphanumeric()).filter(|t| !t.is_empty()) {
+        let term = term.to_lowercase();
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Number of matching items per value, the most frequent first.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Facets {
+    pub category: Vec<FacetCount>,
+    pub color: Vec<FacetCount>,
+    pub brand: Vec<FacetCount>,
+    pub season: Vec<FacetCount>,
+    pub availability: Vec<FacetCount>,
+}
+
+/// Counts of the values as grouped by the database, without the items lacking one, the most
+/// frequent first.
+pub fn counts(rows: impl IntoIterator<Item = (Option<String>, i64)>) -> Vec<FacetCount> {
+    let mut counts: Vec<_> = rows
+        .into_iter()
+        .filter_map(|(value, count)| Some(FacetCount { value: value?, count: count as usize }))
+        .collect();
+    counts.sort_by(|x, y| y.count.cmp(&x.count).then_with(|| x.value.cmp(&y.value)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(terms("  Navy  wool-coat navy "), vec!["navy", "wool", "coat"]);
+        assert_eq!(terms("\"*) OR (x"), vec!["or", "x"]);
+        assert!(terms(" - ").is_empty());
+    }
+
+    #[test]
+    fn test_statements() {
+        use sea_orm::sea_query::{MysqlQueryBuilder, PostgresQueryBuilder, SqliteQueryBuilder};
+
+        let (sql, values) = for_backend(DbBackend::Sqlite).items("navy").build(SqliteQueryBuilder);
+        assert_eq!(sql, r#"SELECT "id" FROM "items_fts" WHERE items_fts MATCH ?"#);
+        assert_eq!(values.0, vec!["\"navy\"*".into()]);
+        let (sql, _) = for_backend(DbBackend::MySql).outfits("navy").build(MysqlQueryBuilder);
+        assert!(sql.contains("MATCH (name) AGAINST (? IN BOOLEAN MODE)"));
+        let (_, values) = for_backend(DbBackend::Postgres).items("50%").build(PostgresQueryBuilder);
+        assert_eq!(values.0.len(), 3);
+    }
+
+    #[test]
+    fn test_counts() {
+        let counts = counts([
+            (Some("top".into()), 1),
+            (None, 5),
+            (Some("shoes".into()), 2),
+            (Some("bottom".into()), 1),
+        ]);
+        let counts: Vec<_> = counts.iter().map(|c| (c.value.as_str(), c.count)).collect();
+        assert_eq!(
+            counts,
+            vec![("shoes", 2), ("bottom", 1), ("top", 1)],
+            "without items lacking one"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use entity::class::{
+    category::Category,
+    size::{Fit, Size},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A garment whose size the user has judged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fitted {
+    pub brand: String,
+    pub category: Option<Category>,
+    pub size: Size,
+    pub fit: Fit,
+}
+
+/// What the user has learned about the sizes of a brand.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BrandSize {
+    pub brand: String,
+    pub category: Option<Category>,
+    /// the size that fit well most often
+    pub recommended: Option<Size>,
+    pub fits_well: Vec<Size>,
+    pub too_small: Vec<Size>,
+    pub too_large: Vec<Size>,
+}
+
+/// Learn per brand and category sizes from judged garments; brands are matched ignoring case.
+pub fn mappings(fitted: &[Fitted]) -> Vec<BrandSize> {
+    let mut groups: HashMap<_, Vec<&Fitted>> = HashMap::new();
+    for garment in fitted.iter().filter(|g| !g.brand.trim().is_empty()) {
+        let key = (garment.brand.trim().to_lowercase(), garment.category);
+        groups.entry(key).or_default().push(garment);
+    }
+    let mut mappings: Vec<_> = groups
+        .into_values()
+        .map(|garments| {
+            let sizes = |fit: Fit| {
+                let mut sizes: Vec<_> =
+                    garments.iter().filter(|g| g.fit == fit).map(|g| g.size).collect();
+                sizes.sort();
+                sizes
+            };
+            let fits_well = sizes(Fit::FitsWell);
+            let count = |size: &Size| fits_well.iter().filter(|s| *s == size).count();
+            // on ties the smaller size, as sizes are sorted and max_by_key keeps the last
+            let recommended = fits_well.iter().rev().max_by_key(|s| count(s)).copied();
+            let (mut fits_well, mut too_small, mut too_large) =
+                (fits_well.clone(), sizes(Fit::TooSmall), sizes(Fit::TooLarge));
+            for sizes in [&mut fits_well, &mut too_small, &mut too_large] {
+                sizes.dedup();
+            }
+            BrandSize {
+                brand: garments[0].brand.trim().to_string(),
+                category: garments[0].category,
+                recommended,
+                fits_well,
+                too_small,
+                too_large,
+            }
+        })
+        .collect();
+    mappings.sort_by_key(|m| (m.brand.to_lowercase(), m.category.map(|c| c.to_string())));
+    mappings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fitted(brand: &str, category: Category, size: &str, fit: Fit) -> Fitted {
+        Fitted { brand: brand.into(), category: Some(category), size: size.parse().unwrap(), fit }
+    }
+
+    #[test]
+    fn test_mappings() {
+        let fitted = [
+            fitted("Acme", Category::Top, "M", Fit::FitsWell),
+            fitted("acme ", Category::Top, "L", Fit::FitsWell),
+            fitted("ACME", Category::Top, "L", Fit::FitsWell),
+            fitted("Acme", Category::Top, "S", Fit::TooSmall),
+            fitted("Acme", Category::Shoes, "EU 42", Fit::TooSmall),
+            fitted("Acme", Category::Shoes, "EU 43", Fit::FitsWell),
+            fitted("Zed", Category::Top, "M", Fit::TooLarge),
+            fitted(" ", Category::Top, "M", Fit::FitsWell),
+        ];
+        let mappings = mappings(&fitted);
+        assert_eq!(mappings.len(), 3, "blank brands are not learned from");
+        let tops = mappings.iter().find(|m| m.category == Some(Category::Top)).unwrap();
+        assert_eq!(tops.brand, "Acme");
+        assert_eq!(tops.recommended, Some("L".parse().unwrap()));
+        assert_eq!(tops.fits_well, vec!["M".parse().unwrap(), "L".parse().unwrap()]);
+        assert_eq!(tops.too_small, vec!["S".parse().unwrap()]);
+        let shoes = mappings.iter().find(|m| m.category == Some(Category::Shoes)).unwrap();
+        assert_eq!(shoes.recommended.unwrap().to_string(), "EU 43");
+        let zed = mappings.last().unwrap();
+        assert_eq!((zed.brand.as_str(), zed.recommended), ("Zed", None));
+        assert_eq!(zed.too_large.len(), 1);
+    }
+
+    #[test]
+    fn test_recommend_smaller_on_tie() {
+        let fitted = [
+            fitted("Acme", Category::Top, "L", Fit::FitsWell),
+            fitted("Acme", Category::Top, "M", Fit::FitsWell),
+        ];
+        assert_eq!(mappings(&fitted)[0].recommended, Some("M".parse().unwrap()));
+    }
+}
@@ -0,0 +1,446 @@
+use chrono::NaiveDate;
+use entity::{
+    class::{
+        category::Category,
+        color::{Color, Rgb},
+        id::Id,
+        season::Season,
+        tag::Tag,
+    },
+    model::item,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::weather::Forecast;
+
+/// items worn within this many days are less likely to be suggested
+pub const RECENT_DAYS: i64 = 7;
+/// best candidates per slot that are combined into outfits
+pub const SLOT_CANDIDATES: usize = 6;
+/// suggestions returned at most at once
+pub const MAX_LIMIT: usize = 10;
+/// random noise added to scores, so that different seeds give different variations
+pub const JITTER: f64 = 0.05;
+/// outerwear is needed below this temperature, in degrees celsius
+pub const COAT_BELOW: f64 = 15.0;
+/// garments from this warmth on are too hot from `HOT_ABOVE` degrees celsius
+pub const HEAVY_WARMTH: i32 = 4;
+pub const HOT_ABOVE: f64 = 25.0;
+
+/// A garment that can be put in an outfit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Garment {
+    pub id: Id<item::Model>,
+    pub name: String,
+    pub category: Category,
+    /// most dominant color of its photo
+    pub color: Option<Rgb>,
+    pub tags: Vec<Tag>,
+    pub last_worn: Option<NaiveDate>,
+    /// 0 to 5, see [`item::Model::WARMTH`]
+    pub warmth: i32,
+    pub waterproof: bool,
+}
+impl Garment {
+    /// seasons the garment is tagged with, empty means all seasons
+    pub fn seasons(&self) -> Vec<Season> {
+        self.tags.iter().filter_map(|t| t.as_str().parse().ok()).collect()
+    }
+    pub fn suits(&self, criteria: &Criteria) -> bool {
+        let seasons = self.seasons();
+        let season = seasons.is_empty() || seasons.contains(&criteria.season);
+        let occasion = criteria.occasion.as_ref().is_none_or(|o| self.tags.contains(o));
+        let weather = criteria
+            .forecast
+            .as_ref()
+            .is_none_or(|f| f.feels_like() < HOT_ABOVE || self.warmth < HEAVY_WARMTH);
+        season && occasion && weather
+    }
+    /// 0 if worn today, 1 if not worn for `RECENT_DAYS` or never worn
+    pub fn freshness(&self, today: &NaiveDate) -> f64 {
+        match self.last_worn {
+            Some(worn) => ((*today - worn).num_days() as f64 / RECENT_DAYS as f64).clamp(0.0, 1.0),
+            None => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Criteria {
+    pub occasion: Option<Tag>,
+    pub season: Season,
+    pub today: NaiveDate,
+    pub seed: u64,
+    pub limit: usize,
+    /// forecast of `today` at home, if known
+    pub forecast: Option<Forecast>,
+}
+impl Criteria {
+    pub fn slots(&self) -> Vec<Category> {
+        let mut slots = vec![Category::Top, Category::Bottom, Category::Shoes];
+        let cold = match &self.forecast {
+            Some(forecast) => forecast.feels_like() < COAT_BELOW,
+            None => matches!(self.season, Season::Autumn | Season::Winter),
+        };
+        if cold {
+            slots.push(Category::Outerwear);
+        }
+        slots
+    }
+}
+
+/// total warmth of an outfit that feels right at `temperature` degrees celsius
+pub fn ideal_warmth(temperature: f64) -> f64 {
+    (3.0 + (24.0 - temperature) / 3.0).clamp(3.0, 16.0)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Suggestion {
+    /// in slot order
+    pub items: Vec<Id<item::Model>>,
+    /// 0 to 1000, higher is better
+    pub score: u32,
+    pub explanations: Vec<String>,
+}
+
+/// Relation of two colors on the color wheel.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Harmony {
+    Neutral,
+    Analogous,
+    Complementary,
+    Triadic,
+    Clashing,
+    Unknown,
+}
+impl Harmony {
+    pub fn of(x: &Rgb, y: &Rgb) -> Self {
+        const ACHROMATIC: f64 = 15.0;
+        let (lx, ly) = (x.lab(), y.lab());
+        let neutral =
+            |rgb: &Rgb, chroma: f64| Color::nearest(rgb).is_neutral() || chroma < ACHROMATIC;
+        if neutral(x, lx.chroma()) || neutral(y, ly.chroma()) {
+            return Self::Neutral;
+        }
+        let diff = (lx.hue() - ly.hue()).abs();
+        match diff.min(360.0 - diff) {
+            d if d < 30.0 => Self::Analogous,
+            d if d >= 150.0 => Self::Complementary,
+            d if (100.0..140.0).contains(&d) => Self::Triadic,
+            _ => Self::Clashing,
+        }
+    }
+    pub fn score(&self) -> f64 {
+        match self {
+            Self::Neutral => 1.0,
+            Self::Complementary => 0.9,
+            Self::Analogous => 0.8,
+            Self::Triadic => 0.6,
+            Self::Unknown => 0.5,
+            Self::Clashing => 0.2,
+        }
+    }
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Neutral => "combined with a neutral color",
+            Self::Complementary => "complementary colors",
+            Self::Analogous => "analogous colors",
+            Self::Triadic => "triadic colors",
+            Self::Unknown => "of unknown colors",
+            Self::Clashing => "clashing colors",
+        }
+    }
+}
+
+/// Propose up to `criteria.limit` outfits from `garments`, best first.
+///
+/// Every outfit fills each slot of [`Criteria::slots`] with one garment suited to the occasion
+/// and season. Outfits are ranked by color harmony and by how long ago their pieces were worn.
+/// The result only depends on the input, so the same seed always gives the same suggestions.
+pub fn suggest(garments: &[Garment], criteria: &Criteria) -> Vec<Suggestion> {
+    let slots = criteria.slots();
+    let mut candidates: Vec<Vec<&Garment>> = Vec::new();
+    for slot in &slots {
+        let mut fits: Vec<_> =
+            garments.iter().filter(|g| &g.category == slot && g.suits(criteria)).collect();
+        if fits.is_empty() {
+            return Vec::new();
+        }
+        fits.sort_by(|x, y| {
+            let (fx, fy) = (x.freshness(&criteria.today), y.freshness(&criteria.today));
+            fy.total_cmp(&fx).then_with(|| x.id.identifier().cmp(&y.id.identifier()))
+        });
+        fits.truncate(SLOT_CANDIDATES);
+        candidates.push(fits);
+    }
+
+    // unlike `StdRng`, its stream is fixed across releases, so a seed keeps its suggestions
+    let mut rng = ChaCha8Rng::seed_from_u64(criteria.seed);
+    let mut scored: Vec<(f64, Vec<&Garment>)> = combinations(&candidates)
+        .into_iter()
+        .map(|outfit| (score(&outfit, criteria) + rng.gen_range(0.0..JITTER), outfit))
+        .collect();
+    scored.sort_by(|(x, _), (y, _)| y.total_cmp(x));
+
+    let mut chosen: Vec<(f64, Vec<&Garment>)> = Vec::new();
+    for (score, outfit) in scored {
+        if chosen.len() >= criteria.limit {
+            break;
+        }
+        // share at most one piece with a better suggestion
+        let diverse = chosen
+            .iter()
+            .all(|(_, c)| outfit.iter().filter(|g| c.iter().any(|h| h.id == g.id)).count() <= 1);
+        if diverse {
+            chosen.push((score, outfit));
+        }
+    }
+    chosen
+        .into_iter()
+        .map(|(score, outfit)| Suggestion {
+            items: outfit.iter().map(|g| g.id.clone()).collect(),
+            score: (score / (1.0 + JITTER) * 1000.0).round() as u32,
+            explanations: explain(&outfit, criteria),
+        })
+        .collect()
+}
+
+fn combinations<'a>(candidates: &[Vec<&'a Garment>]) -> Vec<Vec<&'a Garment>> {
+    candidates.iter().fold(vec![Vec::new()], |outfits, slot| {
+        outfits
+            .iter()
+            .flat_map(|outfit| {
+                slot.iter().map(move |g| {
+                    let mut extended = outfit.clone();
+                    extended.push(*g);
+                    extended
+                })
+            })
+            .collect()
+    })
+}
+
+fn pairs<'a, 'b>(
+    outfit: &'b [&'a Garment],
+) -> impl Iterator<Item = (&'a Garment, &'a Garment)> + 'b {
+    outfit.iter().enumerate().flat_map(move |(i, x)| outfit[i + 1..].iter().map(move |y| (*x, *y)))
+}
+
+fn harmony(x: &Garment, y: &Garment) -> Harmony {
+    match (&x.color, &y.color) {
+        (Some(cx), Some(cy)) => Harmony::of(cx, cy),
+        _ => Harmony::Unknown,
+    }
+}
+
+/// 0 to 1, how well the outfit keeps warm and dry
+fn weather_fit(outfit: &[&Garment], forecast: &Forecast) -> f64 {
+    let warmth = outfit.iter().map(|g| g.warmth).sum::<i32>() as f64;
+    let warm = 1.0 - ((warmth - ideal_warmth(forecast.feels_like())).abs() / 6.0).min(1.0);
+    if forecast.is_rainy() {
+        let exposed: Vec<_> = outfit
+            .iter()
+            .filter(|g| matches!(g.category, Category::Outerwear | Category::Shoes))
+            .collect();
+        let dry = exposed.iter().filter(|g| g.waterproof).count() as f64;
+        0.5 * warm + 0.5 * dry / exposed.len().max(1) as f64
+    } else {
+        warm
+    }
+}
+
+fn score(outfit: &[&Garment], criteria: &Criteria) -> f64 {
+    let harmonies: Vec<_> = pairs(outfit).map(|(x, y)| harmony(x, y).score()).collect();
+    let harmony = harmonies.iter().sum::<f64>() / harmonies.len().max(1) as f64;
+    let freshness = outfit.iter().map(|g| g.freshness(&criteria.today)).sum::<f64>()
+        / outfit.len().max(1) as f64;
+    match &criteria.forecast {
+        Some(forecast) => 0.45 * harmony + 0.3 * freshness + 0.25 * weather_fit(outfit, forecast),
+        None => 0.6 * harmony + 0.4 * freshness,
+    }
+}
+
+fn explain(outfit: &[&Garment], criteria: &Criteria) -> Vec<String> {
+    let mut explanations = Vec::new();
+    if let Some(occasion) = &criteria.occasion {
+        explanations.push(format!("suited for {}", occasion));
+    }
+    explanations.push(format!("suited for {}", criteria.season));
+    if let Some(forecast) = &criteria.forecast {
+        explanations.push(format!("forecast is {}", forecast));
+        let warmth: i32 = outfit.iter().map(|g| g.warmth).sum();
+        let ideal = ideal_warmth(forecast.feels_like()).round() as i32;
+        match warmth - ideal {
+            d if d < -2 => explanations.push(format!("may be too light, warmth {}", warmth)),
+            d if d > 2 => explanations.push(format!("may be too warm, warmth {}", warmth)),
+            _ => explanations.push(format!("warmth {} suits the temperature", warmth)),
+        }
+        if forecast.is_rainy() {
+            for garment in outfit.iter().filter(|g| g.waterproof) {
+                explanations.push(format!("{} is waterproof", garment.name));
+            }
+        }
+    }
+    for (x, y) in pairs(outfit) {
+        let harmony = harmony(x, y);
+        if harmony != Harmony::Neutral {
+            explanations.push(format!("{} and {} are {}", x.name, y.name, harmony.describe()));
+        }
+    }
+    for garment in outfit {
+        match garment.last_worn {
+            None => explanations.push(format!("{} has not been worn yet", garment.name)),
+            Some(worn) => {
+                let days = (criteria.today - worn).num_days();
+                if days < RECENT_DAYS {
+                    explanations.push(format!("{} was worn only {} days ago", garment.name, days));
+                }
+            }
+        }
+    }
+    explanations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn garment(name: &str, category: Category, color: Rgb, tags: &[&str]) -> Garment {
+        Garment {
+            id: Id::create(),
+            name: name.into(),
+            category,
+            color: Some(color),
+            tags: tags.iter().map(|t| Tag::parse(t).unwrap()).collect(),
+            last_worn: None,
+            warmth: match category {
+                Category::Outerwear => 4,
+                _ => 1,
+            },
+            waterproof: false,
+        }
+    }
+    fn closet() -> Vec<Garment> {
+        vec![
+            garment("white shirt", Category::Top, Rgb(245, 245, 245), &["casual"]),
+            garment("red shirt", Category::Top, Rgb(190, 30, 45), &["casual"]),
+            garment("green polo", Category::Top, Rgb(40, 120, 60), &["casual", "summer"]),
+            garment("chinos", Category::Bottom, Rgb(170, 150, 100), &["casual"]),
+            garment("green pants", Category::Bottom, Rgb(60, 140, 70), &["casual"]),
+            garment("sneakers", Category::Shoes, Rgb(20, 20, 20), &["casual"]),
+            garment("loafers", Category::Shoes, Rgb(110, 70, 40), &["formal"]),
+            garment("pea coat", Category::Outerwear, Rgb(31, 42, 68), &["winter", "casual"]),
+        ]
+    }
+    fn criteria(season: Season, seed: u64) -> Criteria {
+        Criteria {
+            occasion: Some(Tag::parse("casual").unwrap()),
+            season,
+            today: NaiveDate::from_ymd_opt(2023, 6, 15).unwrap(),
+            seed,
+            limit: 3,
+            forecast: None,
+        }
+    }
+    fn forecast(temperature: f64, precipitation_probability: u8) -> Forecast {
+        Forecast {
+            date: NaiveDate::from_ymd_opt(2023, 6, 15).unwrap(),
+            temperature_max: temperature,
+            temperature_min: temperature,
+            precipitation_probability,
+            wind_speed_max: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_harmony() {
+        assert_eq!(Harmony::of(&Rgb(245, 245, 245), &Rgb(190, 30, 45)), Harmony::Neutral);
+        assert_eq!(Harmony::of(&Rgb(240, 210, 60), &Rgb(40, 90, 180)), Harmony::Complementary);
+        assert_eq!(Harmony::of(&Rgb(190, 30, 45), &Rgb(40, 120, 60)), Harmony::Triadic);
+        assert_eq!(Harmony::of(&Rgb(190, 30, 45), &Rgb(110, 60, 140)), Harmony::Clashing);
+        assert_eq!(Harmony::of(&Rgb(40, 120, 60), &Rgb(60, 140, 70)), Harmony::Analogous);
+    }
+
+    #[test]
+    fn test_slots_and_occasion() {
+        let closet = closet();
+        let summer = suggest(&closet, &criteria(Season::Summer, 0));
+        assert!(!summer.is_empty());
+        for suggestion in &summer {
+            let categories: Vec<_> = suggestion
+                .items
+                .iter()
+                .map(|id| closet.iter().find(|g| &g.id == id).unwrap().category)
+                .collect();
+            assert_eq!(categories, vec![Category::Top, Category::Bottom, Category::Shoes]);
+            let loafers = &closet[6].id;
+            assert!(!suggestion.items.contains(loafers), "formal shoes are not casual");
+        }
+
+        let winter = suggest(&closet, &criteria(Season::Winter, 0));
+        assert!(winter.iter().all(|s| s.items.len() == 4));
+        let polo = &closet[2].id;
+        assert!(winter.iter().all(|s| !s.items.contains(polo)), "polo is summer only");
+
+        let formal = Criteria {
+            occasion: Some(Tag::parse("formal").unwrap()),
+            ..criteria(Season::Summer, 0)
+        };
+        assert!(suggest(&closet, &formal).is_empty());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let closet = closet();
+        let criteria = criteria(Season::Summer, 42);
+        assert_eq!(suggest(&closet, &criteria), suggest(&closet, &criteria));
+        let scores: Vec<_> = suggest(&closet, &criteria).iter().map(|s| s.score).collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_recently_worn_avoided() {
+        let mut closet = closet();
+        let criteria = criteria(Season::Summer, 0);
+        closet[0].last_worn = Some(criteria.today);
+        closet[1].last_worn = Some(criteria.today);
+        let best = &suggest(&closet, &criteria)[0];
+        assert!(best.items.contains(&closet[2].id), "only the polo was not worn today");
+        assert!(best.explanations.iter().any(|e| e.contains("has not been worn yet")));
+    }
+
+    #[test]
+    fn test_weather() {
+        let mut closet = closet();
+        closet.push(Garment {
+            warmth: 5,
+            ..garment("down jacket", Category::Outerwear, Rgb(20, 20, 20), &["casual"])
+        });
+        closet.push(Garment {
+            warmth: 2,
+            waterproof: true,
+            ..garment("rain boots", Category::Shoes, Rgb(20, 20, 20), &["casual"])
+        });
+        let (down_jacket, rain_boots) = (closet[8].id.clone(), closet[9].id.clone());
+
+        let cold = Criteria { forecast: Some(forecast(0.0, 0)), ..criteria(Season::Summer, 0) };
+        assert_eq!(cold.slots().len(), 4, "cold weather needs outerwear even in summer");
+        let suggested = suggest(&closet, &cold);
+        assert!(suggested[0].items.contains(&down_jacket), "the warmest coat for freezing days");
+        assert!(suggested[0].explanations.iter().any(|e| e.starts_with("forecast is 0 to 0")));
+
+        let warm = Criteria { forecast: Some(forecast(20.0, 0)), ..criteria(Season::Winter, 0) };
+        assert_eq!(warm.slots().len(), 3, "mild weather needs no outerwear even in winter");
+
+        let hot = Criteria { forecast: Some(forecast(30.0, 0)), ..criteria(Season::Summer, 0) };
+        closet[0].warmth = HEAVY_WARMTH;
+        let suggested = suggest(&closet, &hot);
+        assert!(suggested.iter().all(|s| !s.items.contains(&closet[0].id)), "too warm");
+
+        let rainy = Criteria { forecast: Some(forecast(20.0, 90)), ..criteria(Season::Summer, 0) };
+        let best = &suggest(&closet, &rainy)[0];
+        assert!(best.items.contains(&rain_boots));
+        assert!(best.explanations.contains(&"rain boots is waterproof".to_string()));
+    }
+}
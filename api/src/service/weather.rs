@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use entity::class::location::Location;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::Config;
+
+/// precipitation probability from which it is considered a rainy day, in percent
+pub const RAINY: u8 = 50;
+/// wind speed from which it is considered a windy day, in km/h
+pub const WINDY: f64 = 30.0;
+
+/// Daily weather forecast at some location.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Forecast {
+    pub date: NaiveDate,
+    /// degrees celsius
+    pub temperature_max: f64,
+    /// degrees celsius
+    pub temperature_min: f64,
+    /// percent, 0 to 100
+    pub precipitation_probability: u8,
+    /// km/h
+    pub wind_speed_max: f64,
+}
+impl Forecast {
+    /// daytime temperature, a few degrees lower on windy days
+    pub fn feels_like(&self) -> f64 {
+        let daytime = (self.temperature_max * 2.0 + self.temperature_min) / 3.0;
+        if self.is_windy() {
+            daytime - 3.0
+        } else {
+            daytime
+        }
+    }
+    pub fn is_rainy(&self) -> bool {
+        self.precipitation_probability >= RAINY
+    }
+    pub fn is_windy(&self) -> bool {
+        self.wind_speed_max >= WINDY
+    }
+}
+impl std::fmt::Display for Forecast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.0} to {:.0} degrees, {}% chance of rain",
+            self.temperature_min, self.temperature_max, self.precipitation_probability
+        )?;
+        if self.is_windy() {
+            write!(f, ", windy")?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of daily forecasts.
+#[async_trait::async_trait]
+pub trait WeatherProvider: Send + Sync {
+    async fn forecast(&self, location: &Location, date: NaiveDate) -> anyhow::Result<Forecast>;
+}
+
+/// Build the provider selected by the configuration.
+pub fn from_config(config: &Config) -> anyhow::Result<Arc<dyn WeatherProvider>> {
+    match &config.weather_provider()[..] {
+        OpenMeteo::NAME => Ok(Arc::new(OpenMeteo::new(config.weather_url()))),
+        FixtureWeather::NAME => match config.weather_fixture() {
+            Some(path) => Ok(Arc::new(
+                FixtureWeather::from_path(&path)
+                    .with_context(|| format!("invalid {}", Config::WEATHER_FIXTURE))?,
+            )),
+            None => Ok(Arc::new(FixtureWeather::builtin())),
+        },
+        unknown => anyhow::bail!("unknown {}: {}", Config::WEATHER_PROVIDER, unknown),
+    }
+}
+
+/// Forecasts from the free [Open-Meteo](https://open-meteo.com) API.
+#[derive(Debug, Clone)]
+pub struct OpenMeteo {
+    client: reqwest::Client,
+    url: String,
+}
+impl OpenMeteo {
+    pub const NAME: &str = "open_meteo";
+    const DAILY: &str =
+        "temperature_2m_max,temperature_2m_min,precipitation_probability_max,wind_speed_10m_max";
+
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+#[async_trait::async_trait]
+impl WeatherProvider for OpenMeteo {
+    async fn forecast(&self, location: &Location, date: NaiveDate) -> anyhow::Result<Forecast> {
+        let (latitude, longitude) =
+            (location.latitude().to_string(), location.longitude().to_string());
+        let date = date.to_string();
+        let query = [
+            ("latitude", &latitude[..]),
+            ("longitude", &longitude[..]),
+            ("daily", Self::DAILY),
+            ("timezone", "auto"),
+            ("start_date", &date[..]),
+            ("end_date", &date[..]),
+        ];
+        let response = self.client.get(&self.url).query(&query).send().await?.error_for_status()?;
+        let forecast: OpenMeteoForecast = response.json().await?;
+        forecast.daily.forecasts()?.into_iter().next().context("empty forecast")
+    }
+}
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+struct OpenMeteoForecast {
+    daily: OpenMeteoDaily,
+}
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+struct OpenMeteoDaily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+    wind_speed_10m_max: Vec<Option<f64>>,
+}
+impl OpenMeteoDaily {
+    fn forecasts(&self) -> anyhow::Result<Vec<Forecast>> {
+        let at = |values: &[Option<f64>], i: usize, name: &str| {
+            values.get(i).copied().flatten().with_context(|| format!("missing {}", name))
+        };
+        (0..self.time.len())
+            .map(|i| {
+                Ok(Forecast {
+                    date: self.time[i],
+                    temperature_max: at(&self.temperature_2m_max, i, "temperature_2m_max")?,
+                    temperature_min: at(&self.temperature_2m_min, i, "temperature_2m_min")?,
+                    // not available for every past date, so taken as no rain
+                    precipitation_probability: (self.precipitation_probability_max.get(i))
+                        .copied()
+                        .flatten()
+                        .unwrap_or_default()
+                        .clamp(0.0, 100.0) as u8,
+                    wind_speed_max: at(&self.wind_speed_10m_max, i, "wind_speed_10m_max")?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Canned forecasts for tests and offline development, regardless of location.
+/// Dates without a forecast get the one of the closest date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureWeather {
+    forecasts: Vec<Forecast>,
+}
+impl FixtureWeather {
+    pub const NAME: &str = "fixture";
+
+    pub fn new(forecasts: Vec<Forecast>) -> Self {
+        Self { forecasts }
+    }
+    pub fn builtin() -> Self {
+        let fixture = include_str!("../../fixtures/weather.json");
+        Self::new(serde_json::from_str(fixture).expect("builtin fixture is valid"))
+    }
+    pub fn from_path(path: &str) -> anyhow::Result<Self> {
+        let fixture = std::fs::read_to_string(path).with_context(|| format!("read {}", path))?;
+        Ok(Self::new(serde_json::from_str(&fixture).with_context(|| format!("parse {}", path))?))
+    }
+}
+#[async_trait::async_trait]
+impl WeatherProvider for FixtureWeather {
+    async fn forecast(&self, _location: &Location, date: NaiveDate) -> anyhow::Result<Forecast> {
+        let closest = self.forecasts.iter().min_by_key(|f| (f.date - date).num_days().abs());
+        let forecast = closest.context("no fixture forecast")?;
+        Ok(Forecast { date, ..forecast.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_meteo_response() {
+        let response = serde_json::json!({
+            "latitude": 35.7,
+            "longitude": 139.75,
+            "daily_units": { "time": "iso8601", "temperature_2m_max": "°C" },
+            "daily": {
+                "time": ["2023-06-15", "2023-06-16"],
+                "temperature_2m_max": [29.5, 22.0],
+                "temperature_2m_min": [21.0, 17.5],
+                "precipitation_probability_max": [null, 80],
+                "wind_speed_10m_max": [12.0, 31.2]
+            }
+        });
+        let forecast: OpenMeteoForecast = serde_json::from_value(response).unwrap();
+        let forecasts = forecast.daily.forecasts().unwrap();
+        assert_eq!(forecasts.len(), 2);
+        assert_eq!(forecasts[0].date, NaiveDate::from_ymd_opt(2023, 6, 15).unwrap());
+        assert_eq!(forecasts[0].precipitation_probability, 0);
+        assert!(!forecasts[0].is_rainy() && !forecasts[0].is_windy());
+        assert!(forecasts[1].is_rainy() && forecasts[1].is_windy());
+        assert_eq!(forecasts[1].to_string(), "18 to 22 degrees, 80% chance of rain, windy");
+
+        let missing = serde_json::json!({ "daily": {
+            "time": ["2023-06-15"],
+            "temperature_2m_max": [null],
+            "temperature_2m_min": [21.0],
+            "precipitation_probability_max": [10],
+            "wind_speed_10m_max": [12.0]
+        }});
+        let forecast: OpenMeteoForecast = serde_json::from_value(missing).unwrap();
+        assert!(forecast.daily.forecasts().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_closest() {
+        let fixture = FixtureWeather::builtin();
+        let tokyo = Location::parse("35.6812,139.7671").unwrap();
+        let date = NaiveDate::from_ymd_opt(2023, 6, 16).unwrap();
+        let rainy = fixture.forecast(&tokyo, date).await.unwrap();
+        assert_eq!(rainy.precipitation_probability, 80);
+        let date = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let cold = fixture.forecast(&tokyo, date).await.unwrap();
+        assert_eq!((cold.date, cold.temperature_max), (date, 3.0));
+        assert!(FixtureWeather::new(Vec::new()).forecast(&tokyo, date).await.is_err());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = |provider: &str, fixture: Option<&str>| Config {
+            weather_provider: Some(provider.into()),
+            weather_fixture: fixture.map(Into::into),
+            ..Default::default()
+        };
+        assert!(from_config(&config(FixtureWeather::NAME, None)).is_ok());
+        assert!(from_config(&config(OpenMeteo::NAME, None)).is_ok());
+        let unknown = from_config(&config("almanac", None)).err().unwrap();
+        assert_eq!(unknown.to_string(), "unknown WEATHER_PROVIDER: almanac");
+        let unreadable = from_config(&config(FixtureWeather::NAME, Some("/nonexistent.json")));
+        let unreadable = format!("{:#}", unreadable.err().unwrap());
+        assert!(unreadable.starts_with("invalid WEATHER_FIXTURE: read /nonexistent.json"));
+    }
+}
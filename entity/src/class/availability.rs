@@ -0,0 +1,118 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Where a garment is in its wear and care cycle.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    #[default]
+    Clean,
+    Worn,
+    InLaundry,
+    AtDryCleaner,
+    LentOut,
+    InRepair,
+    Stored,
+}
+impl Availability {
+    pub const ALL: [Availability; 7] = [
+        Self::Clean,
+        Self::Worn,
+        Self::InLaundry,
+        Self::AtDryCleaner,
+        Self::LentOut,
+        Self::InRepair,
+        Self::Stored,
+    ];
+
+    /// states reachable from this state in one transition
+    pub fn next(&self) -> &'static [Availability] {
+        match self {
+            Self::Clean => &[Self::Worn, Self::LentOut, Self::InRepair, Self::Stored],
+            Self::Worn => &[Self::Clean, Self::InLaundry, Self::AtDryCleaner, Self::InRepair],
+            Self::InLaundry => &[Self::Clean],
+            Self::AtDryCleaner => &[Self::Clean],
+            Self::LentOut => &[Self::Clean, Self::Worn],
+            Self::InRepair => &[Self::Clean],
+            Self::Stored => &[Self::Clean],
+        }
+    }
+    pub fn can_transition(&self, to: &Availability) -> bool {
+        self.next().contains(to)
+    }
+    pub fn transition(&self, to: Availability) -> Result<Availability, EntityError> {
+        if self.can_transition(&to) {
+            Ok(to)
+        } else {
+            Err(ValidateError::ForbiddenTransition { from: self.to_string(), to: to.to_string() })?
+        }
+    }
+
+    /// whether the garment can be worn right now
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Clean)
+    }
+}
+impl std::fmt::Display for Availability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Availability {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedAvailability { unrecognized_availability: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(Availability);
+super::impl_into_active_value!(Availability);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(Availability::AtDryCleaner.to_string(), "at_dry_cleaner");
+        assert_eq!("lent_out".parse::<Availability>().unwrap(), Availability::LentOut);
+        assert!("lost".parse::<Availability>().is_err());
+        for availability in Availability::ALL {
+            assert_eq!(availability.to_string().parse::<Availability>().unwrap(), availability);
+        }
+    }
+
+    #[test]
+    fn test_transition() {
+        use Availability::*;
+        assert_eq!(Clean.transition(Worn).unwrap(), Worn);
+        assert_eq!(Worn.transition(InLaundry).unwrap(), InLaundry);
+        assert_eq!(InLaundry.transition(Clean).unwrap(), Clean);
+        assert!(Clean.transition(Clean).is_err());
+        assert!(Clean.transition(InLaundry).is_err());
+        assert!(Stored.transition(Worn).is_err());
+        assert!(InLaundry.transition(LentOut).is_err());
+    }
+
+    #[test]
+    fn test_graph_returns_to_clean() {
+        for availability in Availability::ALL {
+            let mut visited = vec![availability];
+            let mut frontier = vec![availability];
+            while let Some(state) = frontier.pop() {
+                for next in state.next() {
+                    if !visited.contains(next) {
+                        visited.push(*next);
+                        frontier.push(*next);
+                    }
+                }
+            }
+            assert!(visited.contains(&Availability::Clean), "{} is a dead end", availability);
+            assert!(!availability.can_transition(&availability));
+        }
+    }
+}
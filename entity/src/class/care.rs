@@ -0,0 +1,239 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Washing process, from the bars under the wash tub.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Process {
+    Normal,
+    Mild,
+    VeryMild,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Wash {
+    /// maximum temperature in degrees celsius
+    Machine {
+        temperature: u8,
+        process: Process,
+    },
+    Hand,
+    DoNotWash,
+}
+impl Wash {
+    /// temperatures of the wash tub symbol
+    pub const TEMPERATURES: [u8; 6] = [30, 40, 50, 60, 70, 95];
+    /// hand wash is at most this warm
+    pub const HAND_TEMPERATURE: u8 = 40;
+
+    pub fn machine(temperature: u8, process: Process) -> Option<Self> {
+        let max = match process {
+            Process::Normal => 95,
+            Process::Mild => 60,
+            Process::VeryMild => 40,
+        };
+        (Self::TEMPERATURES.contains(&temperature) && temperature <= max)
+            .then_some(Self::Machine { temperature, process })
+    }
+}
+
+/// Ordered from the most to the least permissive, as for the remaining symbols.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Bleach {
+    Any,
+    NonChlorine,
+    DoNotBleach,
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TumbleDry {
+    Normal,
+    Low,
+    DoNotTumbleDry,
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Iron {
+    High,
+    Medium,
+    Low,
+    DoNotIron,
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Professional {
+    /// dry cleaning in tetrachloroethene
+    DryCleanP,
+    /// dry cleaning in hydrocarbon solvents
+    DryCleanF,
+    WetClean,
+    DoNotDryClean,
+}
+
+/// ISO 3758 care label, represented as its symbols separated by commas in label order,
+/// e.g. `wash_40_mild,non_chlorine_bleach,tumble_dry_low,iron_medium,dry_clean_p`.
+/// Symbols missing from the label are unknown.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Care {
+    pub wash: Option<Wash>,
+    pub bleach: Option<Bleach>,
+    pub dry: Option<TumbleDry>,
+    pub iron: Option<Iron>,
+    pub professional: Option<Professional>,
+}
+impl Care {
+    pub fn parse(care: &str) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateCare { invalid_care: care.into() };
+        fn set<T>(slot: &mut Option<T>, symbol: T) -> Option<()> {
+            slot.replace(symbol).is_none().then_some(())
+        }
+        let mut parsed = Self::default();
+        for symbol in care.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let ok = match symbol {
+                "hand_wash" => set(&mut parsed.wash, Wash::Hand),
+                "do_not_wash" => set(&mut parsed.wash, Wash::DoNotWash),
+                "bleach" => set(&mut parsed.bleach, Bleach::Any),
+                "non_chlorine_bleach" => set(&mut parsed.bleach, Bleach::NonChlorine),
+                "do_not_bleach" => set(&mut parsed.bleach, Bleach::DoNotBleach),
+                "tumble_dry" => set(&mut parsed.dry, TumbleDry::Normal),
+                "tumble_dry_low" => set(&mut parsed.dry, TumbleDry::Low),
+                "do_not_tumble_dry" => set(&mut parsed.dry, TumbleDry::DoNotTumbleDry),
+                "iron_high" => set(&mut parsed.iron, Iron::High),
+                "iron_medium" => set(&mut parsed.iron, Iron::Medium),
+                "iron_low" => set(&mut parsed.iron, Iron::Low),
+                "do_not_iron" => set(&mut parsed.iron, Iron::DoNotIron),
+                "dry_clean_p" => set(&mut parsed.professional, Professional::DryCleanP),
+                "dry_clean_f" => set(&mut parsed.professional, Professional::DryCleanF),
+                "wet_clean" => set(&mut parsed.professional, Professional::WetClean),
+                "do_not_dry_clean" => set(&mut parsed.professional, Professional::DoNotDryClean),
+                wash => wash.strip_prefix("wash_").and_then(|wash| {
+                    let (temperature, process) = match wash.split_once('_') {
+                        Some((t, "mild")) => (t, Process::Mild),
+                        Some((t, "very_mild")) => (t, Process::VeryMild),
+                        Some(_) => return None,
+                        None => (wash, Process::Normal),
+                    };
+                    let machine = Wash::machine(temperature.parse().ok()?, process)?;
+                    set(&mut parsed.wash, machine)
+                }),
+            };
+            ok.ok_or_else(invalid)?;
+        }
+        Ok(parsed)
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        let wash = self.wash.map(|wash| match wash {
+            Wash::Machine { temperature, process: Process::Normal } => {
+                format!("wash_{}", temperature)
+            }
+            Wash::Machine { temperature, process: Process::Mild } => {
+                format!("wash_{}_mild", temperature)
+            }
+            Wash::Machine { temperature, process: Process::VeryMild } => {
+                format!("wash_{}_very_mild", temperature)
+            }
+            Wash::Hand => "hand_wash".into(),
+            Wash::DoNotWash => "do_not_wash".into(),
+        });
+        let bleach = self.bleach.map(|bleach| match bleach {
+            Bleach::Any => "bleach",
+            Bleach::NonChlorine => "non_chlorine_bleach",
+            Bleach::DoNotBleach => "do_not_bleach",
+        });
+        let dry = self.dry.map(|dry| match dry {
+            TumbleDry::Normal => "tumble_dry",
+            TumbleDry::Low => "tumble_dry_low",
+            TumbleDry::DoNotTumbleDry => "do_not_tumble_dry",
+        });
+        let iron = self.iron.map(|iron| match iron {
+            Iron::High => "iron_high",
+            Iron::Medium => "iron_medium",
+            Iron::Low => "iron_low",
+            Iron::DoNotIron => "do_not_iron",
+        });
+        let professional = self.professional.map(|professional| match professional {
+            Professional::DryCleanP => "dry_clean_p",
+            Professional::DryCleanF => "dry_clean_f",
+            Professional::WetClean => "wet_clean",
+            Professional::DoNotDryClean => "do_not_dry_clean",
+        });
+        let rest = [bleach, dry, iron, professional].into_iter().flatten().map(String::from);
+        wash.into_iter().chain(rest).collect()
+    }
+}
+impl std::fmt::Display for Care {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbols().join(","))
+    }
+}
+impl std::str::FromStr for Care {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Care);
+super::impl_into_active_value!(Care);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ok() {
+        let label = "wash_40_mild,non_chlorine_bleach,tumble_dry_low,iron_medium,dry_clean_p";
+        let care = Care::parse(label).unwrap();
+        assert_eq!(care.wash, Some(Wash::Machine { temperature: 40, process: Process::Mild }));
+        assert_eq!(care.bleach, Some(Bleach::NonChlorine));
+        assert_eq!(care.dry, Some(TumbleDry::Low));
+        assert_eq!(care.iron, Some(Iron::Medium));
+        assert_eq!(care.professional, Some(Professional::DryCleanP));
+        assert_eq!(care.to_string(), label);
+
+        // symbols in any order, missing ones unknown
+        let care = Care::parse(" do_not_iron , hand_wash ").unwrap();
+        assert_eq!(care.to_string(), "hand_wash,do_not_iron");
+        assert_eq!(care.bleach, None);
+        assert_eq!(Care::parse("").unwrap(), Care::default());
+        assert_eq!(Care::parse("wash_95").unwrap().to_string(), "wash_95");
+        assert!(Care::parse("wash_30_very_mild").is_ok());
+    }
+
+    #[test]
+    fn test_validate_err() {
+        assert!(Care::parse("wash_45").is_err(), "not a temperature of the symbol");
+        assert!(Care::parse("wash_95_mild").is_err(), "mild process is at most 60");
+        assert!(Care::parse("wash_60_very_mild").is_err(), "very mild process is at most 40");
+        assert!(Care::parse("wash_40_hot").is_err());
+        assert!(Care::parse("wash_40,hand_wash").is_err(), "one wash symbol");
+        assert!(Care::parse("iron_low,iron_low").is_err());
+        assert!(Care::parse("starch").is_err());
+    }
+
+    #[test]
+    fn test_order() {
+        assert!(Bleach::Any < Bleach::DoNotBleach);
+        assert!(TumbleDry::Low < TumbleDry::DoNotTumbleDry);
+        assert!(Process::Normal < Process::VeryMild);
+    }
+}
@@ -0,0 +1,49 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Kind of garment, which decides the slot it fills in an outfit.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Top,
+    Bottom,
+    Outerwear,
+    Shoes,
+    Accessory,
+}
+impl Category {
+    pub const ALL: [Category; 5] =
+        [Self::Top, Self::Bottom, Self::Outerwear, Self::Shoes, Self::Accessory];
+}
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Category {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedCategory { unrecognized_category: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Category);
+super::impl_into_active_value!(Category);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(Category::Outerwear.to_string(), "outerwear");
+        assert_eq!("shoes".parse::<Category>().unwrap(), Category::Shoes);
+        assert!("hat".parse::<Category>().is_err());
+        for category in Category::ALL {
+            assert_eq!(category.to_string().parse::<Category>().unwrap(), category);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Named color vocabulary that garments are tagged with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Color {
+    Black,
+    White,
+    Gray,
+    Navy,
+    Blue,
+    LightBlue,
+    Red,
+    Burgundy,
+    Pink,
+    Orange,
+    Yellow,
+    Beige,
+    Brown,
+    Khaki,
+    Green,
+    Olive,
+    Purple,
+}
+impl Color {
+    pub const ALL: [Color; 17] = [
+        Self::Black,
+        Self::White,
+        Self::Gray,
+        Self::Navy,
+        Self::Blue,
+        Self::LightBlue,
+        Self::Red,
+        Self::Burgundy,
+        Self::Pink,
+        Self::Orange,
+        Self::Yellow,
+        Self::Beige,
+        Self::Brown,
+        Self::Khaki,
+        Self::Green,
+        Self::Olive,
+        Self::Purple,
+    ];
+
+    /// representative sRGB value of the named color
+    pub fn reference(&self) -> Rgb {
+        match self {
+            Self::Black => Rgb(20, 20, 20),
+            Self::White => Rgb(245, 245, 245),
+            Self::Gray => Rgb(128, 128, 128),
+            Self::Navy => Rgb(31, 42, 68),
+            Self::Blue => Rgb(40, 90, 180),
+            Self::LightBlue => Rgb(150, 190, 230),
+            Self::Red => Rgb(190, 30, 45),
+            Self::Burgundy => Rgb(110, 20, 40),
+            Self::Pink => Rgb(240, 160, 180),
+            Self::Orange => Rgb(240, 130, 40),
+            Self::Yellow => Rgb(240, 210, 60),
+            Self::Beige => Rgb(220, 200, 160),
+            Self::Brown => Rgb(110, 70, 40),
+            Self::Khaki => Rgb(170, 150, 100),
+            Self::Green => Rgb(40, 120, 60),
+            Self::Olive => Rgb(110, 110, 50),
+            Self::Purple => Rgb(110, 60, 140),
+        }
+    }
+
+    /// the named color perceptually closest to `rgb`
+    pub fn nearest(rgb: &Rgb) -> Self {
+        let lab = rgb.lab();
+        let distance = |c: &Color| lab.delta_e(&c.reference().lab());
+        Self::ALL
+            .into_iter()
+            .min_by(|x, y| distance(x).total_cmp(&distance(y)))
+            .expect("vocabulary is not empty")
+    }
+
+    /// neutral colors go with anything
+    pub fn is_neutral(&self) -> bool {
+        matches!(
+            self,
+            Self::Black | Self::White | Self::Gray | Self::Navy | Self::Beige | Self::Khaki
+        )
+    }
+}
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Color {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedColor { unrecognized_color: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Color);
+super::impl_into_active_value!(Color);
+
+/// sRGB color, represented as `#rrggbb`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgb(pub u8, pub u8, pub u8);
+impl Rgb {
+    pub fn parse(rgb: &str) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateRgb { invalid_rgb: rgb.into() };
+        let hex = rgb.strip_prefix('#').filter(|h| h.len() == 6).ok_or_else(invalid)?;
+        let channel = |i: usize| {
+            hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).ok_or_else(invalid)
+        };
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// convert into CIELAB (D65 white point)
+    pub fn lab(&self) -> Lab {
+        fn linear(c: u8) -> f64 {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        fn f(t: f64) -> f64 {
+            const DELTA: f64 = 6.0 / 29.0;
+            if t > DELTA.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA.powi(2)) + 4.0 / 29.0
+            }
+        }
+        let (r, g, b) = (linear(self.0), linear(self.1), linear(self.2));
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        Lab { l: 116.0 * fy - 16.0, a: 500.0 * (fx - fy), b: 200.0 * (fy - fz) }
+    }
+}
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+impl std::str::FromStr for Rgb {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Rgb);
+super::impl_into_active_value!(Rgb);
+
+/// color in CIELAB space, where euclidean distance approximates perceived difference
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+impl Lab {
+    /// CIE76 color difference
+    pub fn delta_e(&self, other: &Lab) -> f64 {
+        ((self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+    pub fn chroma(&self) -> f64 {
+        self.a.hypot(self.b)
+    }
+    /// hue angle in degrees, 0 to 360
+    pub fn hue(&self) -> f64 {
+        self.b.atan2(self.a).to_degrees().rem_euclid(360.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_str() {
+        assert_eq!(Color::LightBlue.to_string(), "light_blue");
+        assert_eq!("navy".parse::<Color>().unwrap(), Color::Navy);
+        assert!("ultramarine".parse::<Color>().is_err());
+        for color in Color::ALL {
+            assert_eq!(color.to_string().parse::<Color>().unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn test_rgb_str() {
+        assert_eq!(Rgb::parse("#1f2a44").unwrap(), Rgb(31, 42, 68));
+        assert_eq!(Rgb::parse("#FFFFFF").unwrap(), Rgb(255, 255, 255));
+        assert_eq!(Rgb(31, 42, 68).to_string(), "#1f2a44");
+        assert!(Rgb::parse("1f2a44").is_err());
+        assert!(Rgb::parse("#1f2a4").is_err());
+        assert!(Rgb::parse("#1f2a4g").is_err());
+        assert!(Rgb::parse("#1f2a44ff").is_err());
+        assert!(Rgb::parse("#ｆｆｆ").is_err());
+    }
+
+    #[test]
+    fn test_lab() {
+        let white = Rgb(255, 255, 255).lab();
+        assert!((white.l - 100.0).abs() < 0.05 && white.a.abs() < 0.05 && white.b.abs() < 0.05);
+        let black = Rgb(0, 0, 0).lab();
+        assert!(black.l.abs() < 0.01);
+        let red = Rgb(255, 0, 0).lab();
+        assert!((red.l - 53.24).abs() < 0.1 && (red.a - 80.09).abs() < 0.1);
+        assert!((white.delta_e(&black) - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_nearest() {
+        assert_eq!(Color::nearest(&Rgb(0, 0, 0)), Color::Black);
+        assert_eq!(Color::nearest(&Rgb(255, 255, 255)), Color::White);
+        assert_eq!(Color::nearest(&Rgb(25, 35, 70)), Color::Navy);
+        assert_eq!(Color::nearest(&Rgb(200, 20, 30)), Color::Red);
+        for color in Color::ALL {
+            assert_eq!(Color::nearest(&color.reference()), color);
+        }
+    }
+}
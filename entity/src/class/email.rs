@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{EntityError, ValidateError};
@@ -5,8 +6,9 @@ use crate::error::{EntityError, ValidateError};
 pub const LOCAL_PART_REGEX: &str = r"[a-zA-Z0-9_+-]+(\.[a-zA-Z0-9_+-]+)*";
 pub const DOMAIN_REGEX: &str = r"([a-zA-Z0-9][a-zA-Z0-9-]*\.)+[a-zA-Z]{2,}";
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(try_from = "String", into = "String")]
+#[schemars(extend("format" = "email"))]
 pub struct Email {
     local_part: String,
     domain: String,
@@ -56,6 +56,12 @@ impl<T> From<Id<T>> for sea_orm::Value {
     }
 }
 
+impl<T> sea_orm::IntoActiveValue<Id<T>> for Id<T> {
+    fn into_active_value(self) -> sea_orm::ActiveValue<Id<T>> {
+        sea_orm::ActiveValue::Set(self)
+    }
+}
+
 impl<T> sea_orm::TryFromU64 for Id<T> {
     fn try_from_u64(_: u64) -> Result<Self, sea_orm::DbErr> {
         Err(sea_orm::DbErr::Custom(format!("{} cannot be converted from u64", stringify!(Id<T>))))
@@ -67,11 +73,21 @@ impl<T> sea_orm::TryGetable for Id<T> {
         res: &sea_orm::QueryResult,
         index: I,
     ) -> Result<Self, sea_orm::TryGetError> {
-        let val: Uuid = res.try_get_by(index).map_err(sea_orm::TryGetError::DbErr)?;
+        let val = res.try_get_by(index).map_err(sea_orm::TryGetError::DbErr).and_then(
+            |opt: Option<Uuid>| {
+                opt.ok_or_else(|| sea_orm::TryGetError::Null(format!("{:?}", index)))
+            },
+        )?;
         Ok(Id::<T>::new(val))
     }
 }
 
+impl<T> sea_orm::sea_query::Nullable for Id<T> {
+    fn null() -> sea_orm::Value {
+        sea_orm::Value::Uuid(None)
+    }
+}
+
 impl<T> sea_orm::sea_query::ValueType for Id<T> {
     fn try_from(v: sea_orm::Value) -> Result<Self, sea_orm::sea_query::ValueTypeErr> {
         match v {
@@ -92,3 +108,18 @@ impl<T> sea_orm::sea_query::ValueType for Id<T> {
         sea_orm::sea_query::ArrayType::Uuid
     }
 }
+
+/// documented as the uuid it serializes to, regardless of what it identifies
+impl<T> schemars::JsonSchema for Id<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Id".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        <Uuid as schemars::JsonSchema>::json_schema(generator)
+    }
+}
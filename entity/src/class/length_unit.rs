@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Unit body measurements are entered and shown in; they are stored in millimetres.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    #[default]
+    Cm,
+    Inch,
+}
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 2] = [Self::Cm, Self::Inch];
+
+    pub fn millimetres_per_unit(&self) -> f64 {
+        match self {
+            Self::Cm => 10.0,
+            Self::Inch => 25.4,
+        }
+    }
+    /// the length in millimetres, which must be positive
+    pub fn to_millimetres(&self, length: f64) -> Result<i32, EntityError> {
+        let millimetres = (length * self.millimetres_per_unit()).round();
+        if length.is_finite() && millimetres > 0.0 && millimetres < i32::MAX as f64 {
+            Ok(millimetres as i32)
+        } else {
+            let (field, invalid_value) = ("length".into(), format!("{} {}", length, self));
+            Err(ValidateError::CannotValidateField { field, invalid_value })?
+        }
+    }
+    /// the length in this unit, to one decimal place
+    pub fn from_millimetres(&self, millimetres: i32) -> f64 {
+        (millimetres as f64 / self.millimetres_per_unit() * 10.0).round() / 10.0
+    }
+}
+impl std::fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for LengthUnit {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedLengthUnit { unrecognized_length_unit: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(LengthUnit);
+super::impl_into_active_value!(LengthUnit);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(LengthUnit::Inch.to_string(), "inch");
+        assert!("feet".parse::<LengthUnit>().is_err());
+        for unit in LengthUnit::ALL {
+            assert_eq!(unit.to_string().parse::<LengthUnit>().unwrap(), unit);
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(LengthUnit::Cm.to_millimetres(80.5).unwrap(), 805);
+        assert_eq!(LengthUnit::Inch.to_millimetres(32.0).unwrap(), 813);
+        assert_eq!(LengthUnit::Inch.from_millimetres(813), 32.0);
+        assert_eq!(LengthUnit::Cm.from_millimetres(813), 81.3);
+        assert!(LengthUnit::Cm.to_millimetres(0.0).is_err());
+        assert!(LengthUnit::Cm.to_millimetres(-3.0).is_err());
+        assert!(LengthUnit::Cm.to_millimetres(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// BCP 47 language tag of language, script and region, such as `en-US` or `zh-Hant-TW`.
+/// Parsed case-insensitively with `-` or `_`, and kept in canonical case.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schemars(extend("examples" = ["en-US"]))]
+pub struct Locale {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+}
+impl Default for Locale {
+    fn default() -> Self {
+        Self { language: "en".into(), script: None, region: Some("US".into()) }
+    }
+}
+impl Locale {
+    pub fn parse(locale: &str) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateLocale { invalid_locale: locale.into() };
+        let alphabetic = |s: &str, len: std::ops::RangeInclusive<usize>| {
+            len.contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphabetic())
+        };
+        let mut subtags = locale.trim().split(['-', '_']).peekable();
+        let language = subtags.next().filter(|l| alphabetic(l, 2..=3)).ok_or_else(invalid)?;
+        let script = subtags.next_if(|s| alphabetic(s, 4..=4)).map(|s| {
+            let (initial, rest) = s.split_at(1);
+            initial.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+        });
+        let region = subtags
+            .next_if(|r| {
+                alphabetic(r, 2..=2) || (r.len() == 3 && r.chars().all(|c| c.is_ascii_digit()))
+            })
+            .map(|r| r.to_ascii_uppercase());
+        if subtags.next().is_some() {
+            Err(invalid())?
+        }
+        Ok(Self { language: language.to_ascii_lowercase(), script, region })
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+}
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.language)?;
+        for subtag in [&self.script, &self.region].into_iter().flatten() {
+            write!(f, "-{}", subtag)?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for Locale {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Locale);
+super::impl_into_active_value!(Locale);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        let locale = |s: &str| s.parse::<Locale>().unwrap().to_string();
+        assert_eq!(locale("ja"), "ja");
+        assert_eq!(locale("en_gb"), "en-GB");
+        assert_eq!(locale("ZH-hant-tw"), "zh-Hant-TW");
+        assert_eq!(locale("es-419"), "es-419");
+        assert_eq!(Locale::default().to_string(), "en-US");
+        let parsed: Locale = "zh-Hant-TW".parse().unwrap();
+        assert_eq!(
+            (parsed.language(), parsed.script(), parsed.region()),
+            ("zh", Some("Hant"), Some("TW"))
+        );
+    }
+
+    #[test]
+    fn test_str_err() {
+        assert!("".parse::<Locale>().is_err());
+        assert!("e".parse::<Locale>().is_err());
+        assert!("english".parse::<Locale>().is_err());
+        assert!("en-USA".parse::<Locale>().is_err());
+        assert!("en-US-x".parse::<Locale>().is_err());
+        assert!("e1-US".parse::<Locale>().is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Geographic coordinate, represented as `latitude,longitude` in degrees.
+/// Stored with micro degree precision (about 10 cm).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Location {
+    latitude: i32,
+    longitude: i32,
+}
+impl Location {
+    const MICRO: f64 = 1_000_000.0;
+
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateLocation {
+            invalid_location: format!("{},{}", latitude, longitude),
+        };
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            Err(invalid())?
+        }
+        let (latitude, longitude) =
+            ((latitude * Self::MICRO).round() as i32, (longitude * Self::MICRO).round() as i32);
+        Ok(Self { latitude, longitude })
+    }
+    pub fn parse(location: &str) -> Result<Self, EntityError> {
+        let invalid =
+            || ValidateError::CannotValidateLocation { invalid_location: location.into() };
+        let (latitude, longitude) = location.split_once(',').ok_or_else(invalid)?;
+        let degree = |s: &str| s.trim().parse::<f64>().ok().filter(|d| d.is_finite());
+        match (degree(latitude), degree(longitude)) {
+            (Some(latitude), Some(longitude)) => Self::new(latitude, longitude),
+            _ => Err(invalid())?,
+        }
+    }
+
+    pub fn latitude(&self) -> f64 {
+        self.latitude as f64 / Self::MICRO
+    }
+    pub fn longitude(&self) -> f64 {
+        self.longitude as f64 / Self::MICRO
+    }
+}
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.6},{:.6}", self.latitude(), self.longitude())
+    }
+}
+impl std::str::FromStr for Location {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Location);
+super::impl_into_active_value!(Location);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ok() {
+        let tokyo = Location::parse("35.6812,139.7671").unwrap();
+        assert_eq!(tokyo.latitude(), 35.6812);
+        assert_eq!(tokyo.longitude(), 139.7671);
+        assert_eq!(tokyo.to_string(), "35.681200,139.767100");
+        assert_eq!(Location::parse(" -33.8688 , 151.2093 ").unwrap().latitude(), -33.8688);
+        assert!(Location::parse("90,180").is_ok());
+        assert!(Location::parse("-90,-180").is_ok());
+    }
+
+    #[test]
+    fn test_validate_err() {
+        assert!(Location::parse("").is_err());
+        assert!(Location::parse("35.6812").is_err());
+        assert!(Location::parse("35.6812;139.7671").is_err());
+        assert!(Location::parse("91,0").is_err());
+        assert!(Location::parse("0,180.5").is_err());
+        assert!(Location::parse("north,east").is_err());
+        assert!(Location::parse("NaN,0").is_err());
+    }
+}
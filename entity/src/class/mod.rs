@@ -1,8 +1,25 @@
+pub mod availability;
+pub mod care;
+pub mod category;
+pub mod color;
 pub mod email;
 pub mod id;
+pub mod length_unit;
+pub mod locale;
+pub mod location;
+pub mod money;
 pub mod password;
+pub mod retire_reason;
+pub mod role;
+pub mod season;
+pub mod size;
 pub mod status;
+pub mod storage_kind;
+pub mod tag;
+pub mod time_zone;
+pub mod unit_system;
 pub mod username;
+pub mod week_start;
 
 macro_rules! impl_convert_string_value {
     ($ty: ty) => {
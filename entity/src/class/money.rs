@@ -0,0 +1,220 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// ISO 4217 currency.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    Jpy,
+    Usd,
+    Eur,
+    Gbp,
+    Cny,
+    Krw,
+    Aud,
+    Cad,
+    Chf,
+}
+impl Currency {
+    /// digits of the minor unit, e.g. cents
+    pub fn exponent(&self) -> u32 {
+        match self {
+            Self::Jpy | Self::Krw => 0,
+            _ => 2,
+        }
+    }
+}
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Currency {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedCurrency { unrecognized_currency: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Currency);
+super::impl_into_active_value!(Currency);
+
+/// Amount of a currency, represented as `1234.50 USD`.
+/// Kept as an integer of the minor unit so that arithmetic is exact.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+impl Money {
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+    /// money stored as an amount column and a currency column, if both are set
+    pub fn from_parts(minor: Option<i64>, currency: Option<Currency>) -> Option<Self> {
+        Some(Self::new(minor?, currency?))
+    }
+    pub fn parse(money: &str) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateMoney { invalid_money: money.into() };
+        let (amount, currency) = money.trim().split_once(' ').ok_or_else(invalid)?;
+        let currency: Currency = currency.trim().parse()?;
+        let (negative, amount) = match amount.strip_prefix('-') {
+            Some(amount) => (true, amount),
+            None => (false, amount),
+        };
+        let (major, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        let exponent = currency.exponent() as usize;
+        if !digits(major)
+            || (amount.contains('.') && !digits(fraction))
+            || fraction.len() > exponent
+        {
+            Err(invalid())?
+        }
+        let fraction = format!("{:0<width$}", fraction, width = exponent);
+        let minor = format!("{}{}", major, fraction).parse::<i64>().map_err(|_| invalid())?;
+        Ok(Self::new(if negative { -minor } else { minor }, currency))
+    }
+
+    /// amount in the minor unit
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+    /// the money if it can be a price, which unlike a balance is never negative
+    pub fn validate_price(self) -> Result<Self, EntityError> {
+        if self.is_negative() {
+            Err(ValidateError::NegativeMoney { negative_money: self.to_string() })?
+        }
+        Ok(self)
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, EntityError> {
+        if self.currency != other.currency {
+            Err(ValidateError::CurrencyMismatch {
+                left: self.currency.to_string(),
+                right: other.currency.to_string(),
+            })?
+        }
+        let minor = self.minor.checked_add(other.minor).ok_or_else(|| {
+            ValidateError::CannotValidateMoney { invalid_money: format!("{} + {}", self, other) }
+        })?;
+        Ok(Self::new(minor, self.currency))
+    }
+
+    pub fn checked_mul(&self, times: i64) -> Result<Money, EntityError> {
+        let minor = self.minor.checked_mul(times).ok_or_else(|| {
+            ValidateError::CannotValidateMoney { invalid_money: format!("{} * {}", self, times) }
+        })?;
+        Ok(Self::new(minor, self.currency))
+    }
+
+    /// sum for each currency, ordered by currency
+    pub fn totals<'a>(
+        amounts: impl IntoIterator<Item = &'a Money>,
+    ) -> Result<Vec<Money>, EntityError> {
+        let mut totals = std::collections::BTreeMap::new();
+        for amount in amounts {
+            let total =
+                totals.entry(amount.currency).or_insert_with(|| Money::zero(amount.currency));
+            *total = total.checked_add(amount)?;
+        }
+        Ok(totals.into_values().collect())
+    }
+}
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sign, minor) =
+            if self.minor < 0 { ("-", self.minor.unsigned_abs()) } else { ("", self.minor as u64) };
+        match self.currency.exponent() {
+            0 => write!(f, "{}{} {}", sign, minor, self.currency),
+            exponent => {
+                let unit = 10u64.pow(exponent);
+                let (major, fraction) = (minor / unit, minor % unit);
+                let width = exponent as usize;
+                write!(f, "{}{}.{:0width$} {}", sign, major, fraction, self.currency, width = width)
+            }
+        }
+    }
+}
+impl std::str::FromStr for Money {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Money);
+super::impl_into_active_value!(Money);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_str() {
+        assert_eq!(Currency::Usd.to_string(), "USD");
+        assert_eq!("JPY".parse::<Currency>().unwrap(), Currency::Jpy);
+        assert!("usd".parse::<Currency>().is_err());
+        assert!("XXX".parse::<Currency>().is_err());
+    }
+
+    #[test]
+    fn test_money_str() {
+        assert_eq!(Money::parse("1234.5 USD").unwrap(), Money::new(123450, Currency::Usd));
+        assert_eq!(Money::parse("0.05 EUR").unwrap(), Money::new(5, Currency::Eur));
+        assert_eq!(Money::parse("12 USD").unwrap(), Money::new(1200, Currency::Usd));
+        assert_eq!(Money::parse("-3.10 GBP").unwrap(), Money::new(-310, Currency::Gbp));
+        assert_eq!(Money::parse("4980 JPY").unwrap(), Money::new(4980, Currency::Jpy));
+        assert_eq!(Money::new(123450, Currency::Usd).to_string(), "1234.50 USD");
+        assert_eq!(Money::new(-5, Currency::Eur).to_string(), "-0.05 EUR");
+        assert_eq!(Money::new(4980, Currency::Jpy).to_string(), "4980 JPY");
+        for money in ["1234.50 USD", "-0.05 EUR", "4980 JPY"] {
+            assert_eq!(Money::parse(money).unwrap().to_string(), money);
+        }
+    }
+
+    #[test]
+    fn test_money_err() {
+        assert!(Money::parse("12.345 USD").is_err(), "more digits than cents");
+        assert!(Money::parse("12.5 JPY").is_err(), "yen has no minor unit");
+        assert!(Money::parse("12 usd").is_err());
+        assert!(Money::parse("12USD").is_err());
+        assert!(Money::parse(".5 USD").is_err());
+        assert!(Money::parse("5. USD").is_err());
+        assert!(Money::parse("1e3 USD").is_err());
+        assert!(Money::parse("+5 USD").is_err());
+        assert!(Money::parse("99999999999999999999 JPY").is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let usd = |s| Money::parse(s).unwrap();
+        // exact where floating point would drift
+        let tenths = vec![usd("0.10 USD"); 10];
+        assert_eq!(Money::totals(&tenths).unwrap(), vec![usd("1.00 USD")]);
+        assert!(usd("1 USD").checked_add(&Money::parse("1 EUR").unwrap()).is_err());
+        assert!(Money::new(i64::MAX, Currency::Jpy)
+            .checked_add(&Money::new(1, Currency::Jpy))
+            .is_err());
+        assert_eq!(usd("0.10 USD").checked_mul(3).unwrap(), usd("0.30 USD"));
+        assert!(Money::new(i64::MAX, Currency::Jpy).checked_mul(2).is_err());
+        let mixed = [usd("1.50 USD"), Money::parse("300 JPY").unwrap(), usd("2.25 USD")];
+        let totals = Money::totals(&mixed).unwrap();
+        assert_eq!(totals, vec![Money::parse("300 JPY").unwrap(), usd("3.75 USD")]);
+    }
+}
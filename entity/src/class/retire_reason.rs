@@ -0,0 +1,48 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Why a garment left the closet.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RetireReason {
+    Donated,
+    Sold,
+    Discarded,
+    Lost,
+}
+impl RetireReason {
+    pub const ALL: [RetireReason; 4] = [Self::Donated, Self::Sold, Self::Discarded, Self::Lost];
+}
+impl std::fmt::Display for RetireReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for RetireReason {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedRetireReason { unrecognized_retire_reason: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(RetireReason);
+super::impl_into_active_value!(RetireReason);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(RetireReason::Donated.to_string(), "donated");
+        assert_eq!("sold".parse::<RetireReason>().unwrap(), RetireReason::Sold);
+        assert!("burned".parse::<RetireReason>().is_err());
+        for reason in RetireReason::ALL {
+            assert_eq!(reason.to_string().parse::<RetireReason>().unwrap(), reason);
+        }
+    }
+}
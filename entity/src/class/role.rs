@@ -0,0 +1,61 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Permission of a member in a closet, ordered from the weakest.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// can see items and outfits
+    Viewer,
+    /// can also add, change and wear items and outfits
+    Editor,
+    /// can also manage the closet and its members
+    Owner,
+}
+impl Role {
+    pub const ALL: [Role; 3] = [Self::Viewer, Self::Editor, Self::Owner];
+
+    /// whether the role grants everything `required` does
+    pub fn satisfies(&self, required: &Role) -> bool {
+        self >= required
+    }
+}
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Role {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedRole { unrecognized_role: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Role);
+super::impl_into_active_value!(Role);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        for role in Role::ALL {
+            assert_eq!(role.to_string().parse::<Role>().unwrap(), role);
+        }
+        assert!("admin".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_satisfies() {
+        assert!(Role::Owner.satisfies(&Role::Editor));
+        assert!(Role::Editor.satisfies(&Role::Editor));
+        assert!(!Role::Viewer.satisfies(&Role::Editor));
+    }
+}
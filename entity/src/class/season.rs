@@ -0,0 +1,101 @@
+use chrono::{Datelike, NaiveDate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Half of the globe, whose seasons are opposite to those of the other half.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum Hemisphere {
+    #[default]
+    Northern,
+    Southern,
+}
+impl Hemisphere {
+    /// of the latitude in degrees, the equator counting as northern
+    pub fn of(latitude: f64) -> Self {
+        if latitude < 0.0 {
+            Self::Southern
+        } else {
+            Self::Northern
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+impl Season {
+    pub const ALL: [Season; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+
+    /// meteorological season of the hemisphere
+    pub fn of(date: &NaiveDate, hemisphere: Hemisphere) -> Self {
+        let northern = match date.month() {
+            3..=5 => Self::Spring,
+            6..=8 => Self::Summer,
+            9..=11 => Self::Autumn,
+            _ => Self::Winter,
+        };
+        match hemisphere {
+            Hemisphere::Northern => northern,
+            Hemisphere::Southern => northern.opposite(),
+        }
+    }
+    fn opposite(&self) -> Self {
+        match self {
+            Self::Spring => Self::Autumn,
+            Self::Summer => Self::Winter,
+            Self::Autumn => Self::Spring,
+            Self::Winter => Self::Summer,
+        }
+    }
+}
+impl std::fmt::Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Season {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedSeason { unrecognized_season: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Season);
+super::impl_into_active_value!(Season);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(Season::Autumn.to_string(), "autumn");
+        assert_eq!("winter".parse::<Season>().unwrap(), Season::Winter);
+        assert!("fall".parse::<Season>().is_err());
+    }
+
+    #[test]
+    fn test_of() {
+        let date = |m| NaiveDate::from_ymd_opt(2023, m, 15).unwrap();
+        let northern = |m| Season::of(&date(m), Hemisphere::Northern);
+        assert_eq!(northern(1), Season::Winter);
+        assert_eq!(northern(4), Season::Spring);
+        assert_eq!(northern(8), Season::Summer);
+        assert_eq!(northern(10), Season::Autumn);
+        assert_eq!(northern(12), Season::Winter);
+        let southern = |m| Season::of(&date(m), Hemisphere::of(-33.87));
+        assert_eq!(southern(1), Season::Summer);
+        assert_eq!(southern(4), Season::Autumn);
+        assert_eq!(southern(8), Season::Winter);
+        assert_eq!(southern(10), Season::Spring);
+        assert_eq!(Hemisphere::of(0.0), Hemisphere::Northern);
+    }
+}
@@ -0,0 +1,205 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Regional numeric sizing system.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Region {
+    Eu,
+    Us,
+    Uk,
+    Jp,
+}
+impl Region {
+    pub const ALL: [Region; 4] = [Self::Eu, Self::Us, Self::Uk, Self::Jp];
+}
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+
+/// Letter size, from the smallest.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Letter {
+    Xxs,
+    Xs,
+    S,
+    M,
+    L,
+    Xl,
+    Xxl,
+    Xxxl,
+}
+impl Letter {
+    pub const ALL: [Letter; 8] =
+        [Self::Xxs, Self::Xs, Self::S, Self::M, Self::L, Self::Xl, Self::Xxl, Self::Xxxl];
+}
+impl std::fmt::Display for Letter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+
+/// Size label of a garment, normalized as `M` or `EU 42` or `US 9.5`.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(try_from = "String", into = "String")]
+pub enum Size {
+    Letter(Letter),
+    /// in half steps, as shoe sizes go
+    Numeric {
+        region: Region,
+        halves: u16,
+    },
+}
+impl Size {
+    /// numeric sizes are below this in every region
+    pub const LIMIT: f64 = 100.0;
+
+    /// parse labels such as `m`, `2XL`, `Medium`, `eu42`, `US 9.5` or `JP 26.5`
+    pub fn parse(size: &str) -> Result<Self, EntityError> {
+        let invalid = || ValidateError::CannotValidateSize { invalid_size: size.into() };
+        let normalized = size.trim().to_uppercase();
+        let letter = match normalized.as_str() {
+            "SMALL" => Some(Letter::S),
+            "MEDIUM" => Some(Letter::M),
+            "LARGE" => Some(Letter::L),
+            "2XS" => Some(Letter::Xxs),
+            "2XL" => Some(Letter::Xxl),
+            "3XL" => Some(Letter::Xxxl),
+            s => serde_json::from_value(serde_json::json!(s)).ok(),
+        };
+        if let Some(letter) = letter {
+            return Ok(Self::Letter(letter));
+        }
+        let region = Region::ALL
+            .into_iter()
+            .find(|r| normalized.starts_with(&r.to_string()))
+            .ok_or_else(invalid)?;
+        let number = normalized[region.to_string().len()..].trim();
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        let halves = value * 2.0;
+        let plain = number.chars().all(|c| c.is_ascii_digit() || c == '.');
+        if !(plain && value > 0.0 && value < Self::LIMIT && halves.fract() == 0.0) {
+            Err(invalid())?
+        }
+        Ok(Self::Numeric { region, halves: halves as u16 })
+    }
+
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            Self::Letter(_) => None,
+            Self::Numeric { region, .. } => Some(*region),
+        }
+    }
+    /// the number of a numeric size
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            Self::Letter(_) => None,
+            Self::Numeric { halves, .. } => Some(*halves as f64 / 2.0),
+        }
+    }
+}
+impl std::fmt::Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Letter(letter) => write!(f, "{}", letter),
+            Self::Numeric { region, halves } if halves % 2 == 0 => {
+                write!(f, "{} {}", region, halves / 2)
+            }
+            Self::Numeric { region, halves } => write!(f, "{} {}.5", region, halves / 2),
+        }
+    }
+}
+impl std::str::FromStr for Size {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Size);
+super::impl_into_active_value!(Size);
+
+/// How a garment of some size turned out to fit its wearer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Fit {
+    TooSmall,
+    FitsWell,
+    TooLarge,
+}
+impl Fit {
+    pub const ALL: [Fit; 3] = [Self::TooSmall, Self::FitsWell, Self::TooLarge];
+}
+impl std::fmt::Display for Fit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for Fit {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s))
+            .map_err(|_| ValidateError::UnrecognizedFit { unrecognized_fit: s.into() })?)
+    }
+}
+
+super::impl_convert_string_value!(Fit);
+super::impl_into_active_value!(Fit);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letter() {
+        assert_eq!(Size::parse("m").unwrap(), Size::Letter(Letter::M));
+        assert_eq!(Size::parse(" Medium ").unwrap(), Size::Letter(Letter::M));
+        assert_eq!(Size::parse("2xl").unwrap(), Size::Letter(Letter::Xxl));
+        assert_eq!(Size::parse("XXL").unwrap().to_string(), "XXL");
+        assert!(Size::parse("XXXXL").is_err());
+        assert!(Size::Letter(Letter::S) < Size::Letter(Letter::Xl));
+        for letter in Letter::ALL {
+            let size = Size::Letter(letter);
+            assert_eq!(size.to_string().parse::<Size>().unwrap(), size);
+        }
+    }
+
+    #[test]
+    fn test_numeric() {
+        let eu42 = Size::Numeric { region: Region::Eu, halves: 84 };
+        assert_eq!(Size::parse("EU 42").unwrap(), eu42);
+        assert_eq!(Size::parse("eu42").unwrap(), eu42);
+        assert_eq!(Size::parse("EU 42.0").unwrap().to_string(), "EU 42");
+        assert_eq!(Size::parse("us 9.5").unwrap().to_string(), "US 9.5");
+        assert_eq!(Size::parse("JP 26.5").unwrap().value(), Some(26.5));
+        assert_eq!(Size::parse("UK 10").unwrap().region(), Some(Region::Uk));
+        assert!(Size::parse("42").is_err(), "region is required");
+        assert!(Size::parse("EU 42.3").is_err());
+        assert!(Size::parse("EU -1").is_err());
+        assert!(Size::parse("EU 0").is_err());
+        assert!(Size::parse("US 120").is_err());
+        assert!(Size::parse("US NaN").is_err());
+        assert!(Size::parse("FR 38").is_err());
+        assert!(Size::parse("US 9").unwrap() < Size::parse("US 9.5").unwrap());
+    }
+
+    #[test]
+    fn test_fit_str() {
+        assert_eq!(Fit::FitsWell.to_string(), "fits_well");
+        assert!("snug".parse::<Fit>().is_err());
+        for fit in Fit::ALL {
+            assert_eq!(fit.to_string().parse::<Fit>().unwrap(), fit);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// Kind of a physical storage location, from the largest.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    Room,
+    Wardrobe,
+    Box,
+}
+impl StorageKind {
+    pub const ALL: [StorageKind; 3] = [Self::Room, Self::Wardrobe, Self::Box];
+
+    /// whether items here are at hand for daily wear, unless configured otherwise
+    pub fn is_active(&self) -> bool {
+        !matches!(self, Self::Box)
+    }
+    /// whether a location of this kind can be placed in `parent`
+    pub fn fits_in(&self, parent: &StorageKind) -> bool {
+        parent < self
+    }
+}
+impl std::fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for StorageKind {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedStorageKind { unrecognized_storage_kind: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(StorageKind);
+super::impl_into_active_value!(StorageKind);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(StorageKind::Wardrobe.to_string(), "wardrobe");
+        assert_eq!("box".parse::<StorageKind>().unwrap(), StorageKind::Box);
+        assert!("drawer".parse::<StorageKind>().is_err());
+    }
+
+    #[test]
+    fn test_fits_in() {
+        assert!(StorageKind::Box.fits_in(&StorageKind::Wardrobe));
+        assert!(StorageKind::Wardrobe.fits_in(&StorageKind::Room));
+        assert!(!StorageKind::Room.fits_in(&StorageKind::Box));
+        assert!(!StorageKind::Box.fits_in(&StorageKind::Box));
+    }
+}
@@ -0,0 +1,65 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+pub const REGEX: &str = r"^[a-z0-9][a-z0-9_-]{0,31}$";
+
+/// Free-form label of a garment such as an occasion (`casual`) or a season (`winter`).
+/// Tags are case insensitive and stored in lower case.
+#[derive(
+    Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tag(String);
+impl Tag {
+    pub fn parse(tag: &str) -> Result<Self, EntityError> {
+        let normalized = tag.trim().to_lowercase();
+        let re = regex::Regex::new(REGEX).expect("invalid regex");
+        if re.is_match(&normalized) {
+            Ok(Self(normalized))
+        } else {
+            Err(ValidateError::CannotValidateTag { invalid_tag: tag.into() })?
+        }
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl std::str::FromStr for Tag {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+super::impl_convert_string_value!(Tag);
+super::impl_into_active_value!(Tag);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ok() {
+        assert_eq!(Tag::parse("casual").unwrap().as_str(), "casual");
+        assert_eq!(Tag::parse(" Winter ").unwrap().as_str(), "winter");
+        assert!(Tag::parse("black-tie").is_ok());
+        assert!(Tag::parse("work_from_home").is_ok());
+        assert!(Tag::parse("2023").is_ok());
+    }
+
+    #[test]
+    fn test_validate_err() {
+        assert!(Tag::parse("").is_err());
+        assert!(Tag::parse("-dash").is_err());
+        assert!(Tag::parse("two words").is_err());
+        assert!(Tag::parse("semi;colon").is_err());
+        assert!(Tag::parse(&"x".repeat(33)).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Offset, TimeZone as _, Utc};
+use chrono_tz::Tz;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// IANA time zone such as `Asia/Tokyo`, in which dates are shown to the user.
+/// Timestamps are stored in UTC regardless.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schemars(extend("examples" = ["Asia/Tokyo"]))]
+pub struct TimeZone(Tz);
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self(Tz::UTC)
+    }
+}
+impl TimeZone {
+    pub fn tz(&self) -> Tz {
+        self.0
+    }
+    pub fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.0)
+    }
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+    /// local date of the instant
+    pub fn date_of<T: chrono::TimeZone>(&self, at: &DateTime<T>) -> NaiveDate {
+        at.with_timezone(&self.0).date_naive()
+    }
+    /// instant the local day starts, midnight unless the clocks skip it
+    pub fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        match midnight.and_local_timezone(self.0).earliest() {
+            Some(start) => start.with_timezone(&Utc),
+            // the day starts as the clocks are set forward, which is midnight by the offset before
+            None => {
+                let before = self.0.offset_from_utc_datetime(&(midnight - Days::new(1))).fix();
+                (midnight - before).and_utc()
+            }
+        }
+    }
+}
+impl std::fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.name())
+    }
+}
+impl std::str::FromStr for TimeZone {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tz = s.trim().parse::<Tz>().map_err(|_| ValidateError::UnrecognizedTimeZone {
+            unrecognized_time_zone: s.into(),
+        })?;
+        Ok(Self(tz))
+    }
+}
+
+super::impl_convert_string_value!(TimeZone);
+super::impl_into_active_value!(TimeZone);
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone as _;
+
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        let tokyo: TimeZone = "Asia/Tokyo".parse().unwrap();
+        assert_eq!(tokyo.to_string(), "Asia/Tokyo");
+        assert_eq!(TimeZone::default().to_string(), "UTC");
+        assert!("Mars/Olympus_Mons".parse::<TimeZone>().is_err());
+        assert!("+09:00".parse::<TimeZone>().is_err());
+    }
+
+    #[test]
+    fn test_date_of() {
+        let tokyo: TimeZone = "Asia/Tokyo".parse().unwrap();
+        let new_york: TimeZone = "America/New_York".parse().unwrap();
+        let at = Utc.with_ymd_and_hms(2023, 6, 30, 20, 0, 0).unwrap();
+        assert_eq!(tokyo.date_of(&at), NaiveDate::from_ymd_opt(2023, 7, 1).unwrap());
+        assert_eq!(new_york.date_of(&at), NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+    }
+
+    #[test]
+    fn test_start_of() {
+        let new_york: TimeZone = "America/New_York".parse().unwrap();
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let start = new_york.start_of(ymd(2023, 3, 1));
+        assert_eq!(start, Utc.with_ymd_and_hms(2023, 3, 1, 5, 0, 0).unwrap());
+        let start = new_york.start_of(ymd(2023, 4, 1));
+        assert_eq!(
+            start,
+            Utc.with_ymd_and_hms(2023, 4, 1, 4, 0, 0).unwrap(),
+            "daylight saving time"
+        );
+        // the clocks went from midnight to one in Sao Paulo that day
+        let sao_paulo: TimeZone = "America/Sao_Paulo".parse().unwrap();
+        let start = sao_paulo.start_of(ymd(2018, 11, 4));
+        assert_eq!(start, Utc.with_ymd_and_hms(2018, 11, 4, 3, 0, 0).unwrap());
+        assert_eq!(sao_paulo.date_of(&start), ymd(2018, 11, 4));
+        assert_eq!(sao_paulo.date_of(&(start - chrono::Duration::seconds(1))), ymd(2018, 11, 3));
+    }
+}
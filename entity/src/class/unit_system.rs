@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::length_unit::LengthUnit;
+use crate::error::{EntityError, ValidateError};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+impl UnitSystem {
+    pub const ALL: [UnitSystem; 2] = [Self::Metric, Self::Imperial];
+
+    /// unit body measurements are shown in unless asked otherwise
+    pub fn length_unit(&self) -> LengthUnit {
+        match self {
+            Self::Metric => LengthUnit::Cm,
+            Self::Imperial => LengthUnit::Inch,
+        }
+    }
+}
+impl std::fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for UnitSystem {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedUnitSystem { unrecognized_unit_system: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(UnitSystem);
+super::impl_into_active_value!(UnitSystem);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(UnitSystem::Imperial.to_string(), "imperial");
+        assert!("us".parse::<UnitSystem>().is_err());
+        for system in UnitSystem::ALL {
+            assert_eq!(system.to_string().parse::<UnitSystem>().unwrap(), system);
+        }
+        assert_eq!(UnitSystem::Imperial.length_unit(), LengthUnit::Inch);
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EntityError, ValidateError};
+
+/// First day of the week in calendars.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Saturday,
+    Sunday,
+}
+impl WeekStart {
+    pub const ALL: [WeekStart; 3] = [Self::Monday, Self::Saturday, Self::Sunday];
+
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Saturday => Weekday::Sat,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+    /// first day of the week containing the date
+    pub fn first_of_week(&self, date: NaiveDate) -> NaiveDate {
+        let back = date.weekday().days_since(self.weekday());
+        date.checked_sub_days(Days::new(back.into())).unwrap_or(date)
+    }
+}
+impl std::fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::json!(self).as_str().ok_or(std::fmt::Error)?)
+    }
+}
+impl std::str::FromStr for WeekStart {
+    type Err = EntityError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_value(serde_json::json!(s)).map_err(|_| {
+            ValidateError::UnrecognizedWeekStart { unrecognized_week_start: s.into() }
+        })?)
+    }
+}
+
+super::impl_convert_string_value!(WeekStart);
+super::impl_into_active_value!(WeekStart);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        assert_eq!(WeekStart::Sunday.to_string(), "sunday");
+        assert!("tuesday".parse::<WeekStart>().is_err());
+        for start in WeekStart::ALL {
+            assert_eq!(start.to_string().parse::<WeekStart>().unwrap(), start);
+        }
+    }
+
+    #[test]
+    fn test_first_of_week() {
+        let date = |d| NaiveDate::from_ymd_opt(2023, 6, d).unwrap();
+        // 2023-06-14 is a Wednesday
+        assert_eq!(WeekStart::Monday.first_of_week(date(14)), date(12));
+        assert_eq!(WeekStart::Sunday.first_of_week(date(14)), date(11));
+        assert_eq!(WeekStart::Saturday.first_of_week(date(14)), date(10));
+        assert_eq!(WeekStart::Monday.first_of_week(date(12)), date(12));
+    }
+}
@@ -1,12 +1,18 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
+#[derive(Debug, thiserror::Error, Serialize, Deserialize, JsonSchema)]
 pub enum EntityError {
     #[error("{0}")]
     ValidateError(#[source] ValidateError),
 
     #[error("fail to hash password")]
     CannotHashPassword,
+
+    #[error("{0}")]
+    InvalidFields(FieldErrors),
 }
 impl From<ValidateError> for EntityError {
     fn from(inner: ValidateError) -> Self {
@@ -18,6 +24,7 @@ impl From<EntityError> for sea_orm::TryGetError {
         match e {
             EntityError::ValidateError(e) => Self::DbErr(sea_orm::DbErr::Type(e.to_string())),
             EntityError::CannotHashPassword => Self::DbErr(sea_orm::DbErr::Custom(e.to_string())),
+            EntityError::InvalidFields(_) => Self::DbErr(sea_orm::DbErr::Type(e.to_string())),
         }
     }
 }
@@ -27,7 +34,45 @@ impl From<EntityError> for sea_orm::sea_query::ValueTypeErr {
     }
 }
 
-#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
+/// Every error of a request per field, collected instead of stopping at the first one.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldErrors(pub BTreeMap<String, Vec<String>>);
+impl FieldErrors {
+    /// the error of a single field
+    pub fn of(field: &str, error: impl std::fmt::Display) -> Self {
+        let mut errors = Self::default();
+        errors.push(field, error);
+        errors
+    }
+    pub fn push(&mut self, field: &str, error: impl std::fmt::Display) {
+        self.0.entry(field.to_string()).or_default().push(error.to_string());
+    }
+    /// the value if valid, otherwise the error is recorded for the field
+    pub fn check<T, E: std::fmt::Display>(
+        &mut self,
+        field: &str,
+        result: Result<T, E>,
+    ) -> Option<T> {
+        result.map_err(|e| self.push(field, e)).ok()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+impl std::fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors = self.0.iter().flat_map(|(field, e)| e.iter().map(move |e| (field, e)));
+        let errors: Vec<_> = errors.map(|(field, e)| format!("{}: {}", field, e)).collect();
+        write!(f, "{}", errors.join(", "))
+    }
+}
+impl From<FieldErrors> for EntityError {
+    fn from(inner: FieldErrors) -> Self {
+        Self::InvalidFields(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, Serialize, Deserialize, JsonSchema)]
 pub enum ValidateError {
     #[error("token {} is invalid", invalid_token)]
     CannotValidateToken68 { invalid_token: String },
@@ -39,6 +84,100 @@ pub enum ValidateError {
     ContainUnavailableCharacterPassword,
     #[error("unrecognized status")]
     UnrecognizedStatus,
+    #[error("color {} is unrecognized", unrecognized_color)]
+    UnrecognizedColor { unrecognized_color: String },
+    #[error("rgb {} is invalid", invalid_rgb)]
+    CannotValidateRgb { invalid_rgb: String },
+    #[error("availability {} is unrecognized", unrecognized_availability)]
+    UnrecognizedAvailability { unrecognized_availability: String },
+    #[error("cannot transition from {} to {}", from, to)]
+    ForbiddenTransition { from: String, to: String },
+    #[error("category {} is unrecognized", unrecognized_category)]
+    UnrecognizedCategory { unrecognized_category: String },
+    #[error("season {} is unrecognized", unrecognized_season)]
+    UnrecognizedSeason { unrecognized_season: String },
+    #[error("tag {} is invalid", invalid_tag)]
+    CannotValidateTag { invalid_tag: String },
+    #[error("location {} is invalid", invalid_location)]
+    CannotValidateLocation { invalid_location: String },
+    #[error("warmth {} is out of range", invalid_warmth)]
+    CannotValidateWarmth { invalid_warmth: i32 },
+    #[error("period from {} to {} is invalid", starts_on, ends_on)]
+    CannotValidatePeriod { starts_on: String, ends_on: String },
+    #[error("role {} is unrecognized", unrecognized_role)]
+    UnrecognizedRole { unrecognized_role: String },
+    #[error("{} is required", missing_field)]
+    MissingField { missing_field: String },
+    #[error("{} {} is invalid", field, invalid_value)]
+    CannotValidateField { field: String, invalid_value: String },
+    #[error("currency {} is unrecognized", unrecognized_currency)]
+    UnrecognizedCurrency { unrecognized_currency: String },
+    #[error("money {} is invalid", invalid_money)]
+    CannotValidateMoney { invalid_money: String },
+    #[error("money {} is negative", negative_money)]
+    NegativeMoney { negative_money: String },
+    #[error("cannot combine {} with {}", left, right)]
+    CurrencyMismatch { left: String, right: String },
+    #[error("retire reason {} is unrecognized", unrecognized_retire_reason)]
+    UnrecognizedRetireReason { unrecognized_retire_reason: String },
+    #[error("care label {} is invalid", invalid_care)]
+    CannotValidateCare { invalid_care: String },
+    #[error("storage kind {} is unrecognized", unrecognized_storage_kind)]
+    UnrecognizedStorageKind { unrecognized_storage_kind: String },
+    #[error("size {} is invalid", invalid_size)]
+    CannotValidateSize { invalid_size: String },
+    #[error("fit {} is unrecognized", unrecognized_fit)]
+    UnrecognizedFit { unrecognized_fit: String },
+    #[error("length unit {} is unrecognized", unrecognized_length_unit)]
+    UnrecognizedLengthUnit { unrecognized_length_unit: String },
+    #[error("time zone {} is unrecognized", unrecognized_time_zone)]
+    UnrecognizedTimeZone { unrecognized_time_zone: String },
+    #[error("locale {} is invalid", invalid_locale)]
+    CannotValidateLocale { invalid_locale: String },
+    #[error("unit system {} is unrecognized", unrecognized_unit_system)]
+    UnrecognizedUnitSystem { unrecognized_unit_system: String },
+    #[error("week start {} is unrecognized", unrecognized_week_start)]
+    UnrecognizedWeekStart { unrecognized_week_start: String },
     #[error("cannot convert to string")]
     CannotConvertToString,
 }
+impl ValidateError {
+    /// request field the invalid value is usually given in, none for errors of no single field
+    pub fn field(&self) -> Option<&str> {
+        let field = match self {
+            Self::CannotValidateToken68 { .. }
+            | Self::UnrecognizedStatus
+            | Self::ForbiddenTransition { .. }
+            | Self::CurrencyMismatch { .. }
+            | Self::CannotConvertToString => return None,
+            Self::CannotValidateEmail { .. } => "email",
+            Self::CannotValidateUsername { .. } => "username",
+            Self::ContainUnavailableCharacterPassword => "password",
+            Self::UnrecognizedColor { .. } => "color",
+            Self::CannotValidateRgb { .. } => "rgb",
+            Self::UnrecognizedAvailability { .. } => "availability",
+            Self::UnrecognizedCategory { .. } => "category",
+            Self::UnrecognizedSeason { .. } => "season",
+            Self::CannotValidateTag { .. } => "tag",
+            Self::CannotValidateLocation { .. } => "location",
+            Self::CannotValidateWarmth { .. } => "warmth",
+            Self::CannotValidatePeriod { .. } => "ends_on",
+            Self::UnrecognizedRole { .. } => "role",
+            Self::MissingField { missing_field } => missing_field,
+            Self::CannotValidateField { field, .. } => field,
+            Self::UnrecognizedCurrency { .. } => "currency",
+            Self::CannotValidateMoney { .. } | Self::NegativeMoney { .. } => "money",
+            Self::UnrecognizedRetireReason { .. } => "reason",
+            Self::CannotValidateCare { .. } => "care",
+            Self::UnrecognizedStorageKind { .. } => "kind",
+            Self::CannotValidateSize { .. } => "size",
+            Self::UnrecognizedFit { .. } => "fit",
+            Self::UnrecognizedLengthUnit { .. } => "unit",
+            Self::UnrecognizedTimeZone { .. } => "time_zone",
+            Self::CannotValidateLocale { .. } => "locale",
+            Self::UnrecognizedUnitSystem { .. } => "unit_system",
+            Self::UnrecognizedWeekStart { .. } => "week_start",
+        };
+        Some(field)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::user;
+
+/// secret that grants read access to the calendar feed of a user
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "CalendarToken")]
+#[sea_orm(table_name = "calendar_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    #[sea_orm(unique)]
+    pub user_id: Id<user::Model>,
+
+    #[sea_orm(unique)]
+    pub token: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::UserId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if insert {
+            self.created_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{closet_invitation, closet_member, item, outfit};
+
+/// Collection of items and outfits shared by its members, which go with it when it is deleted.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Closet")]
+#[sea_orm(table_name = "closets")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+
+    pub name: String,
+    /// default closet of the user who created it
+    pub personal: bool,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "closet_member::Entity")]
+    Member,
+    #[sea_orm(has_many = "closet_invitation::Entity")]
+    Invitation,
+    #[sea_orm(has_many = "item::Entity")]
+    Item,
+    #[sea_orm(has_many = "outfit::Entity")]
+    Outfit,
+}
+impl Related<closet_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Member.def()
+    }
+}
+impl Related<closet_invitation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Invitation.def()
+    }
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+impl Related<outfit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Outfit.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::class::{id::Id, role::Role};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{closet, user};
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ClosetMember")]
+#[sea_orm(table_name = "closet_members")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub closet_id: Id<closet::Model>,
+    pub user_id: Id<user::Model>,
+
+    pub role: Role,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "closet::Entity",
+        from = "Column::ClosetId",
+        to = "closet::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Closet,
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::UserId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+impl Related<closet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Closet.def()
+    }
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if insert {
+            self.created_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{item, outfit, user};
+
+/// Garment or outfit a user marked as a favorite; exactly one of them is set.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Favorite")]
+#[sea_orm(table_name = "favorites")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub user_id: Id<user::Model>,
+    pub item_id: Option<Id<item::Model>>,
+    pub outfit_id: Option<Id<outfit::Model>>,
+
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::UserId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Item,
+    #[sea_orm(
+        belongs_to = "outfit::Entity",
+        from = "Column::OutfitId",
+        to = "outfit::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Outfit,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if insert {
+            self.created_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(self)
+    }
+}
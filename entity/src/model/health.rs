@@ -1,9 +1,11 @@
+use schemars::JsonSchema;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::class::status::Status;
 
-#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Health")]
 #[sea_orm(table_name = "health")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
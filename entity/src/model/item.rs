@@ -0,0 +1,186 @@
+use crate::class::{
+    availability::Availability,
+    care::Care,
+    category::Category,
+    id::Id,
+    money::{Currency, Money},
+    retire_reason::RetireReason,
+    size::{Fit, Size},
+};
+use crate::error::{EntityError, ValidateError};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{closet, storage, user};
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Item")]
+#[sea_orm(table_name = "items")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    /// user who added the item
+    pub owner_id: Id<user::Model>,
+    pub closet_id: Id<closet::Model>,
+    pub storage_id: Option<Id<storage::Model>>,
+
+    pub name: String,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub notes: Option<String>,
+    /// 0 (barely any insulation) to 5 (heavy winter wear)
+    pub warmth: i32,
+    pub waterproof: bool,
+    /// ISO 3758 care label
+    pub care: Option<Care>,
+    pub size: Option<Size>,
+    /// how the size fits its wearer
+    pub fit: Option<Fit>,
+    pub fit_note: Option<String>,
+    pub availability: Availability,
+    /// in the minor unit of the currency, e.g. cents
+    pub purchase_price_amount: Option<i64>,
+    pub purchase_price_currency: Option<Currency>,
+    pub purchased_on: Option<Date>,
+    /// retired items are kept for statistics but hidden from the active closet
+    pub retired_on: Option<Date>,
+    pub retire_reason: Option<RetireReason>,
+    /// only for sold items, in the minor unit of the currency
+    pub sale_price_amount: Option<i64>,
+    pub sale_price_currency: Option<Currency>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveIntoActiveModel, Serialize, Deserialize)]
+pub struct InsertItem {
+    pub owner_id: Id<user::Model>,
+    pub closet_id: Id<closet::Model>,
+    pub name: String,
+    pub category: Option<Category>,
+    pub brand: Option<String>,
+    pub notes: Option<String>,
+    pub warmth: i32,
+    pub waterproof: bool,
+}
+
+impl Model {
+    pub const WARMTH: std::ops::RangeInclusive<i32> = 0..=5;
+    pub const DEFAULT_WARMTH: i32 = 1;
+
+    pub fn validate_warmth(warmth: i32) -> Result<i32, EntityError> {
+        if Self::WARMTH.contains(&warmth) {
+            Ok(warmth)
+        } else {
+            Err(ValidateError::CannotValidateWarmth { invalid_warmth: warmth })?
+        }
+    }
+    pub fn is_retired(&self) -> bool {
+        self.retired_on.is_some()
+    }
+    pub fn purchase_price(&self) -> Option<Money> {
+        Money::from_parts(self.purchase_price_amount, self.purchase_price_currency)
+    }
+    pub fn sale_price(&self) -> Option<Money> {
+        Money::from_parts(self.sale_price_amount, self.sale_price_currency)
+    }
+}
+impl ActiveModel {
+    pub fn set_purchase_price(&mut self, price: Option<Money>) {
+        self.purchase_price_amount = ActiveValue::Set(price.map(|p| p.minor()));
+        self.purchase_price_currency = ActiveValue::Set(price.map(|p| p.currency()));
+    }
+    pub fn set_sale_price(&mut self, price: Option<Money>) {
+        self.sale_price_amount = ActiveValue::Set(price.map(|p| p.minor()));
+        self.sale_price_currency = ActiveValue::Set(price.map(|p| p.currency()));
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "closet::Entity",
+        from = "Column::ClosetId",
+        to = "closet::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Closet,
+    #[sea_orm(has_many = "super::item_image::Entity")]
+    Image,
+    #[sea_orm(has_many = "super::item_color::Entity")]
+    Color,
+    #[sea_orm(has_many = "super::item_transition::Entity")]
+    Transition,
+    #[sea_orm(has_many = "super::item_tag::Entity")]
+    Tag,
+    #[sea_orm(has_many = "super::item_wear::Entity")]
+    Wear,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Owner.def()
+    }
+}
+impl Related<closet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Closet.def()
+    }
+}
+impl Related<super::item_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Image.def()
+    }
+}
+impl Related<super::item_color::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Color.def()
+    }
+}
+impl Related<super::item_transition::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transition.def()
+    }
+}
+impl Related<super::item_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tag.def()
+    }
+}
+impl Related<super::item_wear::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Wear.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        self.availability = match self.availability {
+            ActiveValue::NotSet => ActiveValue::Set(Availability::default()),
+            availability => availability,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
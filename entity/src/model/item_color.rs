@@ -0,0 +1,71 @@
+use crate::class::{
+    color::{Color, Rgb},
+    id::Id,
+};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::item;
+
+/// one swatch of the dominant color palette extracted from an item photo
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ItemColor")]
+#[sea_orm(table_name = "item_colors")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub item_id: Id<item::Model>,
+
+    pub color: Color,
+    pub rgb: Rgb,
+    /// share of the photo covered by this swatch, in per mille
+    pub ratio: i32,
+    /// CIE L*a*b* of `rgb` in hundredths, to match palettes near a color in queries
+    pub lab_l: i32,
+    pub lab_a: i32,
+    pub lab_b: i32,
+}
+impl Model {
+    /// `lab_l`, `lab_a` and `lab_b` of the color
+    pub fn lab(rgb: &Rgb) -> [i32; 3] {
+        let lab = rgb.lab();
+        [lab.l, lab.a, lab.b].map(|v| (v * 100.0).round() as i32)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Item,
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if let ActiveValue::Set(rgb) = &self.rgb {
+            let [l, a, b] = Model::lab(rgb);
+            self.lab_l = ActiveValue::Set(l);
+            self.lab_a = ActiveValue::Set(a);
+            self.lab_b = ActiveValue::Set(b);
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::item;
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ItemImage")]
+#[sea_orm(table_name = "item_images")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub item_id: Id<item::Model>,
+
+    pub content_type: String,
+    #[sea_orm(column_type = "Binary(BlobSize::Long)")]
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    /// 64 bit difference hash of the photo, stored as signed.
+    /// Photos uploaded before hashes were introduced have none and are not compared.
+    pub perceptual_hash: Option<i64>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Item,
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if insert {
+            self.created_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(self)
+    }
+}
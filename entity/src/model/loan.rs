@@ -0,0 +1,111 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{item, user};
+
+/// Garment lent to a registered user, or to someone known only by a contact.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Loan")]
+#[sea_orm(table_name = "loans")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub item_id: Id<item::Model>,
+    pub lender_id: Id<user::Model>,
+    pub borrower_id: Option<Id<user::Model>>,
+
+    /// name, phone or email of a borrower without an account
+    pub contact: Option<String>,
+    pub due_on: Option<Date>,
+    /// set once the lender confirms the garment is back
+    pub returned_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+impl Model {
+    pub fn is_open(&self) -> bool {
+        self.returned_at.is_none()
+    }
+    pub fn is_overdue(&self, today: &Date) -> bool {
+        self.is_open() && self.due_on.is_some_and(|due| &due < today)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Item,
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::LenderId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Lender,
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::BorrowerId",
+        to = "user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Borrower,
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overdue() {
+        let date = |d| chrono::NaiveDate::from_ymd_opt(2023, 6, d).unwrap();
+        let loan = Model {
+            id: Id::create(),
+            item_id: Id::create(),
+            lender_id: Id::create(),
+            borrower_id: None,
+            contact: Some("sister".into()),
+            due_on: Some(date(10)),
+            returned_at: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        };
+        assert!(!loan.is_overdue(&date(10)));
+        assert!(loan.is_overdue(&date(11)));
+        let returned = Model { returned_at: Some(Default::default()), ..loan.clone() };
+        assert!(!returned.is_overdue(&date(11)));
+        assert!(!Model { due_on: None, ..loan }.is_overdue(&date(11)));
+    }
+}
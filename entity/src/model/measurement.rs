@@ -0,0 +1,66 @@
+use crate::class::{id::Id, length_unit::LengthUnit};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::user;
+
+/// Body measurements of a user, in millimetres.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Measurement")]
+#[sea_orm(table_name = "measurements")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    #[sea_orm(unique)]
+    pub user_id: Id<user::Model>,
+
+    /// unit the user enters and reads measurements in
+    pub unit: LengthUnit,
+    pub height: Option<i32>,
+    pub chest: Option<i32>,
+    pub waist: Option<i32>,
+    pub hips: Option<i32>,
+    pub inseam: Option<i32>,
+    /// foot length, for shoes
+    pub foot: Option<i32>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::UserId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
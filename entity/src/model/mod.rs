@@ -1,2 +1,26 @@
+pub mod calendar_token;
+pub mod closet;
+pub mod closet_invitation;
+pub mod closet_member;
+pub mod favorite;
 pub mod health;
+pub mod item;
+pub mod item_color;
+pub mod item_image;
+pub mod item_tag;
+pub mod item_transition;
+pub mod item_wear;
+pub mod loan;
+pub mod measurement;
+pub mod outfit;
+pub mod outfit_item;
+pub mod outfit_wear;
+pub mod outfit_wear_tag;
+pub mod packing_item;
+pub mod planned_outfit;
+pub mod share_link;
+pub mod storage;
+pub mod trip;
+pub mod trip_activity;
 pub mod user;
+pub mod wish;
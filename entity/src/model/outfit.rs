@@ -0,0 +1,86 @@
+use crate::class::{id::Id, tag::Tag};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{closet, item, outfit_item, user};
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Outfit")]
+#[sea_orm(table_name = "outfits")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    /// user who added the outfit
+    pub owner_id: Id<user::Model>,
+    pub closet_id: Id<closet::Model>,
+
+    pub name: String,
+    pub occasion: Option<Tag>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "closet::Entity",
+        from = "Column::ClosetId",
+        to = "closet::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Closet,
+    #[sea_orm(has_many = "outfit_item::Entity")]
+    OutfitItem,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Owner.def()
+    }
+}
+impl Related<closet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Closet.def()
+    }
+}
+impl Related<outfit_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitItem.def()
+    }
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        outfit_item::Relation::Item.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(outfit_item::Relation::Outfit.def().rev())
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
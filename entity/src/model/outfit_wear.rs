@@ -0,0 +1,89 @@
+use crate::class::id::Id;
+use crate::error::{EntityError, ValidateError};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{outfit, outfit_wear_tag, user};
+
+/// The day a user wore an outfit, and how it felt.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "OutfitWear")]
+#[sea_orm(table_name = "outfit_wears")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub outfit_id: Id<outfit::Model>,
+    /// user who wore it
+    pub user_id: Id<user::Model>,
+
+    pub worn_on: Date,
+    /// 1 (never again) to 5 (loved it), if rated
+    pub rating: Option<i32>,
+    pub note: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+impl Model {
+    pub const RATING: std::ops::RangeInclusive<i32> = 1..=5;
+
+    pub fn validate_rating(rating: i32) -> Result<i32, EntityError> {
+        if Self::RATING.contains(&rating) {
+            Ok(rating)
+        } else {
+            let (field, invalid_value) = ("rating".into(), rating.to_string());
+            Err(ValidateError::CannotValidateField { field, invalid_value })?
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "outfit::Entity",
+        from = "Column::OutfitId",
+        to = "outfit::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Outfit,
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::UserId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(has_many = "outfit_wear_tag::Entity")]
+    Tag,
+}
+impl Related<outfit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Outfit.def()
+    }
+}
+impl Related<outfit_wear_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tag.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
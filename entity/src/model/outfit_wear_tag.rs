@@ -0,0 +1,48 @@
+use crate::class::{id::Id, tag::Tag};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::outfit_wear;
+
+/// feedback on a worn outfit, such as `too-warm` or `compliments`
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "OutfitWearTag")]
+#[sea_orm(table_name = "outfit_wear_tags")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub outfit_wear_id: Id<outfit_wear::Model>,
+
+    pub tag: Tag,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "outfit_wear::Entity",
+        from = "Column::OutfitWearId",
+        to = "outfit_wear::Column::Id",
+        on_delete = "Cascade"
+    )]
+    OutfitWear,
+}
+impl Related<outfit_wear::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitWear.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        Ok(self)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{item, trip};
+
+/// an item on the packing list of a trip
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "PackingItem")]
+#[sea_orm(table_name = "packing_items")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub trip_id: Id<trip::Model>,
+    pub item_id: Id<item::Model>,
+
+    /// how many days of the trip the item is planned to be worn
+    pub days: i32,
+    /// why the item was picked
+    pub reason: String,
+    /// checked off as packed
+    pub packed: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "trip::Entity",
+        from = "Column::TripId",
+        to = "trip::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Trip,
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Item,
+}
+impl Related<trip::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Trip.def()
+    }
+}
+impl Related<item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        self.packed = match self.packed {
+            ActiveValue::NotSet => ActiveValue::Set(false),
+            packed => packed,
+        };
+        Ok(self)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::class::id::Id;
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{outfit, user};
+
+/// the outfit a user plans to wear on a day, at most one per day
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "PlannedOutfit")]
+#[sea_orm(table_name = "planned_outfits")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub owner_id: Id<user::Model>,
+    pub outfit_id: Id<outfit::Model>,
+
+    pub planned_on: Date,
+    pub note: Option<String>,
+    /// whether the day is over and its items were recorded in the wear log
+    pub logged: bool,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "outfit::Entity",
+        from = "Column::OutfitId",
+        to = "outfit::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Outfit,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Owner.def()
+    }
+}
+impl Related<outfit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Outfit.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        self.logged = match self.logged {
+            ActiveValue::NotSet => ActiveValue::Set(false),
+            logged => logged,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
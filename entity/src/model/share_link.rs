@@ -0,0 +1,121 @@
+use crate::class::{category::Category, id::Id, tag::Tag};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{closet, outfit, user};
+
+/// Secret link that shows an outfit, or the items of a closet, to anyone who has it.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ShareLink")]
+#[sea_orm(table_name = "share_links")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    /// user who shared
+    pub owner_id: Id<user::Model>,
+    pub closet_id: Id<closet::Model>,
+    /// the shared outfit, otherwise items of the closet are shared
+    pub outfit_id: Option<Id<outfit::Model>>,
+
+    #[sea_orm(unique)]
+    pub token: String,
+    /// only items of this category, if the closet is shared
+    pub category: Option<Category>,
+    /// only items with this tag, if the closet is shared
+    pub tag: Option<Tag>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    pub views: i32,
+    pub last_viewed_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+impl Model {
+    pub fn is_active(&self, now: &DateTimeWithTimeZone) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| now < &expires_at)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "closet::Entity",
+        from = "Column::ClosetId",
+        to = "closet::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Closet,
+    #[sea_orm(
+        belongs_to = "outfit::Entity",
+        from = "Column::OutfitId",
+        to = "outfit::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Outfit,
+}
+impl Related<outfit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Outfit.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_active() {
+        let now: DateTimeWithTimeZone =
+            chrono::DateTime::parse_from_rfc3339("2023-07-01T12:00:00+09:00").unwrap();
+        let link = Model {
+            id: Id::create(),
+            owner_id: Id::create(),
+            closet_id: Id::create(),
+            outfit_id: None,
+            token: "secret".into(),
+            category: None,
+            tag: None,
+            expires_at: None,
+            revoked_at: None,
+            views: 0,
+            last_viewed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(link.is_active(&now));
+        let expired = Model { expires_at: Some(now), ..link.clone() };
+        assert!(!expired.is_active(&now));
+        assert!(expired.is_active(&(now - chrono::Duration::seconds(1))));
+        let revoked = Model { revoked_at: Some(now), ..link };
+        assert!(!revoked.is_active(&now));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::class::id::Id;
+use crate::error::{EntityError, ValidateError};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{packing_item, trip_activity, user};
+
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Trip")]
+#[sea_orm(table_name = "trips")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub owner_id: Id<user::Model>,
+
+    pub name: String,
+    pub destination: String,
+    /// first day of the trip, inclusive
+    pub starts_on: Date,
+    /// last day of the trip, inclusive
+    pub ends_on: Date,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+impl Model {
+    pub const MAX_DAYS: i64 = 366;
+
+    /// number of days from `starts_on` to `ends_on`, both inclusive
+    pub fn validate_period(starts_on: &Date, ends_on: &Date) -> Result<i64, EntityError> {
+        let days = (*ends_on - *starts_on).num_days() + 1;
+        if (1..=Self::MAX_DAYS).contains(&days) {
+            Ok(days)
+        } else {
+            Err(ValidateError::CannotValidatePeriod {
+                starts_on: starts_on.to_string(),
+                ends_on: ends_on.to_string(),
+            })?
+        }
+    }
+    pub fn days(&self) -> i64 {
+        (self.ends_on - self.starts_on).num_days() + 1
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(has_many = "trip_activity::Entity")]
+    Activity,
+    #[sea_orm(has_many = "packing_item::Entity")]
+    PackingItem,
+}
+impl Related<user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Owner.def()
+    }
+}
+impl Related<trip_activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+impl Related<packing_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PackingItem.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
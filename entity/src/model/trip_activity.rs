@@ -0,0 +1,48 @@
+use crate::class::{id::Id, tag::Tag};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::trip;
+
+/// something planned during a trip, matched against item tags when packing
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "TripActivity")]
+#[sea_orm(table_name = "trip_activities")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub trip_id: Id<trip::Model>,
+
+    pub activity: Tag,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "trip::Entity",
+        from = "Column::TripId",
+        to = "trip::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Trip,
+}
+impl Related<trip::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Trip.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        Ok(self)
+    }
+}
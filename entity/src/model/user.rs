@@ -1,8 +1,14 @@
-use crate::class::{email::Email, id::Id, password::Password, username::Username};
+use crate::class::{
+    email::Email, id::Id, locale::Locale, location::Location, password::Password,
+    season::Hemisphere, time_zone::TimeZone, unit_system::UnitSystem, username::Username,
+    week_start::WeekStart,
+};
+use schemars::JsonSchema;
 use sea_orm::{entity::prelude::*, ActiveValue};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "User")]
 #[sea_orm(table_name = "users")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -12,9 +18,17 @@ pub struct Model {
     #[sea_orm(unique)]
     pub email: Email,
     #[serde(skip_serializing_if = "Password::is_unauthenticated", default)]
+    #[schemars(skip)]
     pub password: Password,
 
     pub display_name: String,
+    /// where the user lives, used for weather forecasts
+    pub home: Option<Location>,
+    /// user-facing dates such as today are in this zone
+    pub time_zone: TimeZone,
+    pub locale: Locale,
+    pub unit_system: UnitSystem,
+    pub week_start: WeekStart,
     pub confirmed: bool,
     pub is_active: bool,
     pub created_at: DateTimeWithTimeZone,
@@ -28,6 +42,16 @@ impl Model {
         self.password = Password::Unauthenticated;
         self
     }
+
+    /// today in the time zone of the user
+    pub fn today(&self) -> chrono::NaiveDate {
+        self.time_zone.today()
+    }
+
+    /// hemisphere of the home, northern if the home is not set
+    pub fn hemisphere(&self) -> Hemisphere {
+        self.home.map(|home| Hemisphere::of(home.latitude())).unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, DeriveIntoActiveModel, Serialize, Deserialize)]
@@ -64,8 +88,26 @@ impl ActiveModelBehavior for ActiveModel {
             ActiveValue::NotSet => ActiveValue::Set(false),
             confirmed => confirmed,
         };
+        if insert {
+            self.time_zone = match self.time_zone {
+                ActiveValue::NotSet => ActiveValue::Set(TimeZone::default()),
+                time_zone => time_zone,
+            };
+            self.locale = match self.locale {
+                ActiveValue::NotSet => ActiveValue::Set(Locale::default()),
+                locale => locale,
+            };
+            self.unit_system = match self.unit_system {
+                ActiveValue::NotSet => ActiveValue::Set(UnitSystem::default()),
+                unit_system => unit_system,
+            };
+            self.week_start = match self.week_start {
+                ActiveValue::NotSet => ActiveValue::Set(WeekStart::default()),
+                week_start => week_start,
+            };
+        }
         if self.is_changed() {
-            let timestamp = chrono::Local::now().into();
+            let timestamp = chrono::Utc::now().into();
             self.updated_at = ActiveValue::Set(timestamp);
             if insert {
                 self.created_at = ActiveValue::Set(timestamp);
@@ -0,0 +1,102 @@
+use crate::class::{
+    category::Category,
+    id::Id,
+    money::{Currency, Money},
+};
+use crate::error::{EntityError, ValidateError};
+use schemars::JsonSchema;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+use super::{item, user};
+
+/// Something the user wants to buy, until it is promoted into an item.
+#[derive(Debug, Clone, Eq, PartialEq, DeriveEntityModel, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Wish")]
+#[sea_orm(table_name = "wishes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Id<Model>,
+    pub owner_id: Id<user::Model>,
+    /// the item bought for this wish
+    pub item_id: Option<Id<item::Model>>,
+
+    pub name: String,
+    /// shop page
+    pub link: Option<String>,
+    /// in the minor unit of the currency, e.g. cents
+    pub target_price_amount: Option<i64>,
+    pub target_price_currency: Option<Currency>,
+    /// 1 (someday) to 5 (as soon as possible)
+    pub priority: i32,
+    /// category of the gap in the closet this fills
+    pub category: Option<Category>,
+    /// why the closet needs it
+    pub gap: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+impl Model {
+    pub const PRIORITY: std::ops::RangeInclusive<i32> = 1..=5;
+    pub const DEFAULT_PRIORITY: i32 = 3;
+
+    pub fn validate_priority(priority: i32) -> Result<i32, EntityError> {
+        if Self::PRIORITY.contains(&priority) {
+            Ok(priority)
+        } else {
+            let (field, invalid_value) = ("priority".into(), priority.to_string());
+            Err(ValidateError::CannotValidateField { field, invalid_value })?
+        }
+    }
+    pub fn is_purchased(&self) -> bool {
+        self.item_id.is_some()
+    }
+    pub fn target_price(&self) -> Option<Money> {
+        Money::from_parts(self.target_price_amount, self.target_price_currency)
+    }
+}
+impl ActiveModel {
+    pub fn set_target_price(&mut self, price: Option<Money>) {
+        self.target_price_amount = ActiveValue::Set(price.map(|p| p.minor()));
+        self.target_price_currency = ActiveValue::Set(price.map(|p| p.currency()));
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "user::Entity",
+        from = "Column::OwnerId",
+        to = "user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "item::Entity",
+        from = "Column::ItemId",
+        to = "item::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Item,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.id = match self.id {
+            ActiveValue::NotSet => ActiveValue::Set(Id::<Model>::create()),
+            id => id,
+        };
+        if self.is_changed() {
+            let timestamp = chrono::Utc::now().into();
+            self.updated_at = ActiveValue::Set(timestamp);
+            if insert {
+                self.created_at = ActiveValue::Set(timestamp);
+            }
+        }
+        Ok(self)
+    }
+}
@@ -1,12 +1,54 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20230601_000002_create_item_table;
+mod m20230602_000003_create_item_transition_table;
+mod m20230603_000004_create_outfit_table;
+mod m20230603_000015_alter_users_add_home;
+mod m20230603_000016_alter_items_add_warmth;
+mod m20230604_000005_create_trip_table;
+mod m20230605_000006_create_calendar_table;
+mod m20230606_000007_create_closet_table;
+mod m20230607_000008_create_loan_table;
+mod m20230608_000009_create_wish_table;
+mod m20230608_000017_alter_items_add_retirement;
+mod m20230608_000018_alter_items_add_care;
+mod m20230609_000010_create_storage_table;
+mod m20230610_000011_create_measurement_table;
+mod m20230611_000012_create_search_index;
+mod m20230611_000019_alter_item_images_add_perceptual_hash;
+mod m20230612_000013_create_share_link_table;
+mod m20230613_000014_create_outfit_wear_table;
+mod m20230613_000020_alter_users_add_preferences;
+mod m20230614_000021_alter_item_colors_add_lab;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20230601_000002_create_item_table::Migration),
+            Box::new(m20230602_000003_create_item_transition_table::Migration),
+            Box::new(m20230603_000004_create_outfit_table::Migration),
+            Box::new(m20230603_000015_alter_users_add_home::Migration),
+            Box::new(m20230603_000016_alter_items_add_warmth::Migration),
+            Box::new(m20230604_000005_create_trip_table::Migration),
+            Box::new(m20230605_000006_create_calendar_table::Migration),
+            Box::new(m20230606_000007_create_closet_table::Migration),
+            Box::new(m20230607_000008_create_loan_table::Migration),
+            Box::new(m20230608_000009_create_wish_table::Migration),
+            Box::new(m20230608_000017_alter_items_add_retirement::Migration),
+            Box::new(m20230608_000018_alter_items_add_care::Migration),
+            Box::new(m20230609_000010_create_storage_table::Migration),
+            Box::new(m20230610_000011_create_measurement_table::Migration),
+            Box::new(m20230611_000012_create_search_index::Migration),
+            Box::new(m20230611_000019_alter_item_images_add_perceptual_hash::Migration),
+            Box::new(m20230612_000013_create_share_link_table::Migration),
+            Box::new(m20230613_000014_create_outfit_wear_table::Migration),
+            Box::new(m20230613_000020_alter_users_add_preferences::Migration),
+            Box::new(m20230614_000021_alter_item_colors_add_lab::Migration),
+        ]
     }
 }
@@ -14,7 +14,33 @@ pub struct Migration;
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         let schema = Schema::new(manager.get_database_backend());
-        manager.create_table(schema.create_table_from_entity(user::Entity)).await?;
+        // columns as of this migration, the ones added later are altered in by their migrations
+        manager
+            .create_table(
+                Table::create()
+                    .table(user::Entity)
+                    .col(ColumnDef::new(user::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(user::Column::Username).string().not_null().unique_key())
+                    .col(ColumnDef::new(user::Column::Email).string().not_null().unique_key())
+                    .col(ColumnDef::new(user::Column::Password).string().not_null())
+                    .col(ColumnDef::new(user::Column::DisplayName).string().not_null())
+                    .col(ColumnDef::new(user::Column::Confirmed).boolean().not_null())
+                    .col(ColumnDef::new(user::Column::IsActive).boolean().not_null())
+                    .col(
+                        ColumnDef::new(user::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(user::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(user::Column::LastLogin).timestamp_with_time_zone())
+                    .col(ColumnDef::new(user::Column::LastLogout).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
         manager.create_table(schema.create_table_from_entity(health::Entity)).await?;
 
         let insert_ok =
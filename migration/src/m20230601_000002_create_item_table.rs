@@ -0,0 +1,86 @@
+use entity::model::{item, item_color, item_image, user};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration, the ones added later are altered in by their migrations
+        manager
+            .create_table(
+                Table::create()
+                    .table(item::Entity)
+                    .col(ColumnDef::new(item::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(item::Column::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(item::Column::Name).string().not_null())
+                    .col(ColumnDef::new(item::Column::Brand).string())
+                    .col(ColumnDef::new(item::Column::Notes).string())
+                    .col(
+                        ColumnDef::new(item::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(item::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(item::Entity, item::Column::OwnerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(item_image::Entity)
+                    .col(ColumnDef::new(item_image::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(item_image::Column::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(item_image::Column::ContentType).string().not_null())
+                    .col(ColumnDef::new(item_image::Column::Data).blob(BlobSize::Long).not_null())
+                    .col(
+                        ColumnDef::new(item_image::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(item_image::Entity, item_image::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(item_color::Entity)
+                    .col(ColumnDef::new(item_color::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(item_color::Column::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(item_color::Column::Color).string().not_null())
+                    .col(ColumnDef::new(item_color::Column::Rgb).string().not_null())
+                    .col(ColumnDef::new(item_color::Column::Ratio).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(item_color::Entity, item_color::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(item_color::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(item_image::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(item::Entity).to_owned()).await
+    }
+}
@@ -0,0 +1,65 @@
+use entity::{
+    class::availability::Availability,
+    model::{item, item_transition},
+};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // existing items start in the default state, as new ones do
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item::Entity)
+                    .add_column(
+                        ColumnDef::new(item::Column::Availability)
+                            .string()
+                            .not_null()
+                            .default(Availability::default().to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(item_transition::Entity)
+                    .col(
+                        ColumnDef::new(item_transition::Column::Id).uuid().not_null().primary_key(),
+                    )
+                    .col(ColumnDef::new(item_transition::Column::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(item_transition::Column::From).string().not_null())
+                    .col(ColumnDef::new(item_transition::Column::To).string().not_null())
+                    .col(
+                        ColumnDef::new(item_transition::Column::TransitionedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(item_transition::Entity, item_transition::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(item_transition::Entity).to_owned()).await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item::Entity)
+                    .drop_column(item::Column::Availability)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,64 @@
+use entity::model::{item, item_tag, item_wear, outfit, outfit_item, user};
+use sea_orm_migration::{prelude::*, sea_orm::Schema};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(manager.get_database_backend());
+        // existing items are not categorized
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item::Entity)
+                    .add_column(ColumnDef::new(item::Column::Category).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager.create_table(schema.create_table_from_entity(item_tag::Entity)).await?;
+        manager.create_table(schema.create_table_from_entity(item_wear::Entity)).await?;
+        // columns as of this migration, the ones added later are altered in by their migrations
+        manager
+            .create_table(
+                Table::create()
+                    .table(outfit::Entity)
+                    .col(ColumnDef::new(outfit::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(outfit::Column::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(outfit::Column::Name).string().not_null())
+                    .col(ColumnDef::new(outfit::Column::Occasion).string())
+                    .col(
+                        ColumnDef::new(outfit::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(outfit::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(outfit::Entity, outfit::Column::OwnerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager.create_table(schema.create_table_from_entity(outfit_item::Entity)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(outfit_item::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(outfit::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(item_wear::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(item_tag::Entity).to_owned()).await?;
+        manager
+            .alter_table(
+                Table::alter().table(item::Entity).drop_column(item::Column::Category).to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,28 @@
+use entity::model::user;
+use sea_orm_migration::prelude::*;
+
+/// Homes of the existing users are unknown until they set one.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(user::Entity)
+                    .add_column(ColumnDef::new(user::Column::Home).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter().table(user::Entity).drop_column(user::Column::Home).to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,34 @@
+use entity::model::item;
+use sea_orm_migration::prelude::*;
+
+/// Existing items get the warmth new items default to, and are not waterproof.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            ColumnDef::new(item::Column::Warmth)
+                .integer()
+                .not_null()
+                .default(item::Model::DEFAULT_WARMTH)
+                .to_owned(),
+            ColumnDef::new(item::Column::Waterproof).boolean().not_null().default(false).to_owned(),
+        ];
+        // one column per statement, as sqlite cannot add several at once
+        for mut column in columns {
+            let alter = Table::alter().table(item::Entity).add_column(&mut column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [item::Column::Warmth, item::Column::Waterproof] {
+            let alter = Table::alter().table(item::Entity).drop_column(column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use entity::model::{item, packing_item, trip, trip_activity, user};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(trip::Entity)
+                    .col(ColumnDef::new(trip::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(trip::Column::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(trip::Column::Name).string().not_null())
+                    .col(ColumnDef::new(trip::Column::Destination).string().not_null())
+                    .col(ColumnDef::new(trip::Column::StartsOn).date().not_null())
+                    .col(ColumnDef::new(trip::Column::EndsOn).date().not_null())
+                    .col(
+                        ColumnDef::new(trip::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(trip::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(trip::Entity, trip::Column::OwnerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(trip_activity::Entity)
+                    .col(ColumnDef::new(trip_activity::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(trip_activity::Column::TripId).uuid().not_null())
+                    .col(ColumnDef::new(trip_activity::Column::Activity).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(trip_activity::Entity, trip_activity::Column::TripId)
+                            .to(trip::Entity, trip::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(packing_item::Entity)
+                    .col(ColumnDef::new(packing_item::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(packing_item::Column::TripId).uuid().not_null())
+                    .col(ColumnDef::new(packing_item::Column::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(packing_item::Column::Days).integer().not_null())
+                    .col(ColumnDef::new(packing_item::Column::Reason).string().not_null())
+                    .col(ColumnDef::new(packing_item::Column::Packed).boolean().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(packing_item::Entity, packing_item::Column::TripId)
+                            .to(trip::Entity, trip::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(packing_item::Entity, packing_item::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(packing_item::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(trip_activity::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(trip::Entity).to_owned()).await
+    }
+}
@@ -0,0 +1,94 @@
+use entity::model::{calendar_token, outfit, planned_outfit, user};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(planned_outfit::Entity)
+                    .col(ColumnDef::new(planned_outfit::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(planned_outfit::Column::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(planned_outfit::Column::OutfitId).uuid().not_null())
+                    .col(ColumnDef::new(planned_outfit::Column::PlannedOn).date().not_null())
+                    .col(ColumnDef::new(planned_outfit::Column::Note).string())
+                    .col(ColumnDef::new(planned_outfit::Column::Logged).boolean().not_null())
+                    .col(
+                        ColumnDef::new(planned_outfit::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(planned_outfit::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(planned_outfit::Entity, planned_outfit::Column::OwnerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(planned_outfit::Entity, planned_outfit::Column::OutfitId)
+                            .to(outfit::Entity, outfit::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-planned_outfits-owner_id-planned_on")
+                    .table(planned_outfit::Entity)
+                    .col(planned_outfit::Column::OwnerId)
+                    .col(planned_outfit::Column::PlannedOn)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(calendar_token::Entity)
+                    .col(ColumnDef::new(calendar_token::Column::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(calendar_token::Column::UserId)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(calendar_token::Column::Token)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(calendar_token::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(calendar_token::Entity, calendar_token::Column::UserId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(calendar_token::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(planned_outfit::Entity).to_owned()).await
+    }
+}
@@ -0,0 +1,304 @@
+use entity::{
+    class::{id::Id, role::Role},
+    model::{closet, closet_invitation, closet_member, item, outfit, user},
+};
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{
+        ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, QueryFilter, QuerySelect, Statement,
+    },
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration, the closets of the items and outfits are altered in below
+        manager
+            .create_table(
+                Table::create()
+                    .table(closet::Entity)
+                    .col(ColumnDef::new(closet::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(closet::Column::Name).string().not_null())
+                    .col(ColumnDef::new(closet::Column::Personal).boolean().not_null())
+                    .col(
+                        ColumnDef::new(closet::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(closet::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(closet_member::Entity)
+                    .col(ColumnDef::new(closet_member::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(closet_member::Column::ClosetId).uuid().not_null())
+                    .col(ColumnDef::new(closet_member::Column::UserId).uuid().not_null())
+                    .col(ColumnDef::new(closet_member::Column::Role).string().not_null())
+                    .col(
+                        ColumnDef::new(closet_member::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(closet_member::Entity, closet_member::Column::ClosetId)
+                            .to(closet::Entity, closet::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(closet_member::Entity, closet_member::Column::UserId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-closet_members-closet_id-user_id")
+                    .table(closet_member::Entity)
+                    .col(closet_member::Column::ClosetId)
+                    .col(closet_member::Column::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(closet_invitation::Entity)
+                    .col(
+                        ColumnDef::new(closet_invitation::Column::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(closet_invitation::Column::ClosetId).uuid().not_null())
+                    .col(ColumnDef::new(closet_invitation::Column::InviterId).uuid().not_null())
+                    .col(ColumnDef::new(closet_invitation::Column::InviteeId).uuid().not_null())
+                    .col(ColumnDef::new(closet_invitation::Column::Role).string().not_null())
+                    .col(
+                        ColumnDef::new(closet_invitation::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(closet_invitation::Entity, closet_invitation::Column::ClosetId)
+                            .to(closet::Entity, closet::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(closet_invitation::Entity, closet_invitation::Column::InviterId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(closet_invitation::Entity, closet_invitation::Column::InviteeId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // nullable until the existing items and outfits are moved to the personal closets
+        for (table, column) in closet_columns() {
+            let alter =
+                Table::alter().table(table).add_column(ColumnDef::new(column).uuid()).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        personal_closets(manager).await?;
+        for (table, column) in closet_columns() {
+            if manager.get_database_backend() == DbBackend::Sqlite {
+                rebuild_sqlite(manager, &table, nullable(&column), constrained(&column)).await?;
+                continue;
+            }
+            let alter = Table::alter()
+                .table(table.clone())
+                .modify_column(ColumnDef::new(column.clone()).uuid().not_null())
+                .to_owned();
+            manager.alter_table(alter).await?;
+            let foreign_key = ForeignKey::create()
+                .name(&foreign_key_name(&table))
+                .from(table, column)
+                .to(closet::Entity, closet::Column::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .to_owned();
+            manager.create_foreign_key(foreign_key).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column) in closet_columns() {
+            if manager.get_database_backend() == DbBackend::Sqlite {
+                rebuild_sqlite(manager, &table, constrained(&column), nullable(&column)).await?;
+            } else {
+                let foreign_key = ForeignKey::drop()
+                    .name(&foreign_key_name(&table))
+                    .table(table.clone())
+                    .to_owned();
+                manager.drop_foreign_key(foreign_key).await?;
+            }
+            manager.alter_table(Table::alter().table(table).drop_column(column).to_owned()).await?;
+        }
+        manager.drop_table(Table::drop().table(closet_invitation::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(closet_member::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(closet::Entity).to_owned()).await
+    }
+}
+
+/// `closet_id` of the tables created before closets
+fn closet_columns() -> [(TableRef, DynIden); 2] {
+    [
+        (item::Entity.into_table_ref(), item::Column::ClosetId.into_iden()),
+        (outfit::Entity.into_table_ref(), outfit::Column::ClosetId.into_iden()),
+    ]
+}
+
+fn table_name(table: &TableRef) -> String {
+    match table {
+        TableRef::Table(table) => table.to_string(),
+        _ => unreachable!("closet_columns"),
+    }
+}
+
+fn foreign_key_name(table: &TableRef) -> String {
+    format!("fk-{}-closet_id", table_name(table))
+}
+
+fn quoted(iden: &dyn Iden) -> String {
+    format!("\"{}\"", iden.to_string())
+}
+
+/// Definition of the sqlite `closet_id`, nullable as added
+fn nullable(column: &DynIden) -> String {
+    format!("{} text(36)", quoted(column.as_ref()))
+}
+
+/// Definition of the sqlite `closet_id`, not null and referring to the closets
+fn constrained(column: &DynIden) -> String {
+    format!(
+        "{} NOT NULL REFERENCES {} ({}) ON DELETE CASCADE",
+        nullable(column),
+        quoted(&closet::Entity),
+        quoted(&closet::Column::Id)
+    )
+}
+
+/// Replace the definition `from` of a column of the table by `to`, the way sqlite changes a
+/// column: create the table anew, copy the rows, drop the former table and rename the new one.
+/// The rest of the definition is taken from the schema, as later migrations add their columns.
+async fn rebuild_sqlite(
+    manager: &SchemaManager<'_>,
+    table: &TableRef,
+    from: String,
+    to: String,
+) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let name = table_name(table);
+    let schema = Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [name.clone().into()],
+    );
+    let row = db.query_one(schema).await?;
+    let sql: String = row.ok_or(DbErr::RecordNotFound(name.clone()))?.try_get("", "sql")?;
+    if !sql.contains(&from) {
+        Err(DbErr::Migration(format!("no {} in {}", from, sql)))?
+    }
+    let rebuilt = format!("{}_rebuilt", name);
+    let create =
+        sql.replacen(&from, &to, 1).replacen(&format!("\"{name}\""), &format!("\"{rebuilt}\""), 1);
+    // the pool of sqlite holds a single connection, so the pragmas apply to the statements
+    // between them; with foreign keys on, dropping the table would delete what refers to it
+    for statement in [
+        "PRAGMA foreign_keys = OFF".to_owned(),
+        create,
+        format!("INSERT INTO \"{rebuilt}\" SELECT * FROM \"{name}\""),
+        format!("DROP TABLE \"{name}\""),
+        format!("ALTER TABLE \"{rebuilt}\" RENAME TO \"{name}\""),
+        "PRAGMA foreign_keys = ON".to_owned(),
+    ] {
+        db.execute_unprepared(&statement).await?;
+    }
+    Ok(())
+}
+
+/// Give every existing user a personal closet they own, holding the items and outfits they added.
+async fn personal_closets(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let users: Vec<(Id<user::Model>, String)> = user::Entity::find()
+        .select_only()
+        .column(user::Column::Id)
+        .column(user::Column::DisplayName)
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (user_id, display_name) in users {
+        let closet_id = Id::<closet::Model>::create();
+        let now = chrono::Utc::now().fixed_offset();
+        let insert_closet = Query::insert()
+            .into_table(closet::Entity)
+            .columns([
+                closet::Column::Id,
+                closet::Column::Name,
+                closet::Column::Personal,
+                closet::Column::CreatedAt,
+                closet::Column::UpdatedAt,
+            ])
+            .values_panic([
+                closet_id.clone().into(),
+                format!("{}'s closet", display_name).into(),
+                true.into(),
+                now.into(),
+                now.into(),
+            ])
+            .to_owned();
+        manager.exec_stmt(insert_closet).await?;
+        let insert_member = Query::insert()
+            .into_table(closet_member::Entity)
+            .columns([
+                closet_member::Column::Id,
+                closet_member::Column::ClosetId,
+                closet_member::Column::UserId,
+                closet_member::Column::Role,
+                closet_member::Column::CreatedAt,
+            ])
+            .values_panic([
+                Id::<closet_member::Model>::create().into(),
+                closet_id.clone().into(),
+                user_id.clone().into(),
+                Role::Owner.into(),
+                now.into(),
+            ])
+            .to_owned();
+        manager.exec_stmt(insert_member).await?;
+        item::Entity::update_many()
+            .col_expr(item::Column::ClosetId, Expr::value(closet_id.clone()))
+            .filter(item::Column::OwnerId.eq(user_id.clone()))
+            .exec(db)
+            .await?;
+        outfit::Entity::update_many()
+            .col_expr(outfit::Column::ClosetId, Expr::value(closet_id))
+            .filter(outfit::Column::OwnerId.eq(user_id))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
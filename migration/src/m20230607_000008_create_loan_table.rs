@@ -0,0 +1,58 @@
+use entity::model::{item, loan, user};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(loan::Entity)
+                    .col(ColumnDef::new(loan::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(loan::Column::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(loan::Column::LenderId).uuid().not_null())
+                    .col(ColumnDef::new(loan::Column::BorrowerId).uuid())
+                    .col(ColumnDef::new(loan::Column::Contact).string())
+                    .col(ColumnDef::new(loan::Column::DueOn).date())
+                    .col(ColumnDef::new(loan::Column::ReturnedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(loan::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(loan::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(loan::Entity, loan::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(loan::Entity, loan::Column::LenderId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(loan::Entity, loan::Column::BorrowerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(loan::Entity).to_owned()).await
+    }
+}
@@ -0,0 +1,76 @@
+use entity::model::{item, user, wish};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // purchases of the existing items are unknown
+        let columns = [
+            ColumnDef::new(item::Column::PurchasePriceAmount).big_integer().to_owned(),
+            ColumnDef::new(item::Column::PurchasePriceCurrency).string().to_owned(),
+            ColumnDef::new(item::Column::PurchasedOn).date().to_owned(),
+        ];
+        // one column per statement, as sqlite cannot add several at once
+        for mut column in columns {
+            let alter = Table::alter().table(item::Entity).add_column(&mut column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(wish::Entity)
+                    .col(ColumnDef::new(wish::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(wish::Column::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(wish::Column::ItemId).uuid())
+                    .col(ColumnDef::new(wish::Column::Name).string().not_null())
+                    .col(ColumnDef::new(wish::Column::Link).string())
+                    .col(ColumnDef::new(wish::Column::TargetPriceAmount).big_integer())
+                    .col(ColumnDef::new(wish::Column::TargetPriceCurrency).string())
+                    .col(ColumnDef::new(wish::Column::Priority).integer().not_null())
+                    .col(ColumnDef::new(wish::Column::Category).string())
+                    .col(ColumnDef::new(wish::Column::Gap).string())
+                    .col(
+                        ColumnDef::new(wish::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(wish::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(wish::Entity, wish::Column::OwnerId)
+                            .to(user::Entity, user::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(wish::Entity, wish::Column::ItemId)
+                            .to(item::Entity, item::Column::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(wish::Entity).to_owned()).await?;
+        let columns = [
+            item::Column::PurchasePriceAmount,
+            item::Column::PurchasePriceCurrency,
+            item::Column::PurchasedOn,
+        ];
+        for column in columns {
+            let alter = Table::alter().table(item::Entity).drop_column(column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use entity::model::item;
+use sea_orm_migration::prelude::*;
+
+/// Existing items are active.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            ColumnDef::new(item::Column::RetiredOn).date().to_owned(),
+            ColumnDef::new(item::Column::RetireReason).string().to_owned(),
+            ColumnDef::new(item::Column::SalePriceAmount).big_integer().to_owned(),
+            ColumnDef::new(item::Column::SalePriceCurrency).string().to_owned(),
+        ];
+        // one column per statement, as sqlite cannot add several at once
+        for mut column in columns {
+            let alter = Table::alter().table(item::Entity).add_column(&mut column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            item::Column::RetiredOn,
+            item::Column::RetireReason,
+            item::Column::SalePriceAmount,
+            item::Column::SalePriceCurrency,
+        ];
+        for column in columns {
+            let alter = Table::alter().table(item::Entity).drop_column(column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+}
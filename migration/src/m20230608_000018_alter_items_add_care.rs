@@ -0,0 +1,28 @@
+use entity::model::item;
+use sea_orm_migration::prelude::*;
+
+/// Care labels of the existing items are unknown.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item::Entity)
+                    .add_column(ColumnDef::new(item::Column::Care).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter().table(item::Entity).drop_column(item::Column::Care).to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,65 @@
+use entity::model::{closet, item, storage};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // columns as of this migration
+        manager
+            .create_table(
+                Table::create()
+                    .table(storage::Entity)
+                    .col(ColumnDef::new(storage::Column::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(storage::Column::ClosetId).uuid().not_null())
+                    .col(ColumnDef::new(storage::Column::ParentId).uuid())
+                    .col(ColumnDef::new(storage::Column::Kind).string().not_null())
+                    .col(ColumnDef::new(storage::Column::Name).string().not_null())
+                    .col(ColumnDef::new(storage::Column::Label).string())
+                    .col(ColumnDef::new(storage::Column::Active).boolean().not_null())
+                    .col(
+                        ColumnDef::new(storage::Column::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(storage::Column::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(storage::Entity, storage::Column::ClosetId)
+                            .to(closet::Entity, closet::Column::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(storage::Entity, storage::Column::ParentId)
+                            .to(storage::Entity, storage::Column::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // existing items are not stored anywhere yet
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item::Entity)
+                    .add_column(ColumnDef::new(item::Column::StorageId).uuid())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter().table(item::Entity).drop_column(item::Column::StorageId).to_owned(),
+            )
+            .await?;
+        manager.drop_table(Table::drop().table(storage::Entity).to_owned()).await
+    }
+}
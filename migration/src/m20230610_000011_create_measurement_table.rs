@@ -0,0 +1,34 @@
+use entity::model::{item, measurement};
+use sea_orm_migration::{prelude::*, sea_orm::Schema};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// size labels of items, unknown for the existing ones
+const ITEM_COLUMNS: [item::Column; 3] =
+    [item::Column::Size, item::Column::Fit, item::Column::FitNote];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(manager.get_database_backend());
+        // one column per statement, as sqlite cannot add several at once
+        for column in ITEM_COLUMNS {
+            let alter = Table::alter()
+                .table(item::Entity)
+                .add_column(ColumnDef::new(column).string())
+                .to_owned();
+            manager.alter_table(alter).await?;
+        }
+        manager.create_table(schema.create_table_from_entity(measurement::Entity)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(measurement::Entity).to_owned()).await?;
+        for column in ITEM_COLUMNS {
+            let alter = Table::alter().table(item::Entity).drop_column(column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+}
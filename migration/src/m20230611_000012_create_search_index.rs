@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// searchable text columns of each table
+const INDEXED: [(&str, &[&str]); 2] =
+    [("items", &["name", "brand", "notes"]), ("outfits", &["name"])];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for (table, columns) in INDEXED {
+            let statements = match manager.get_database_backend() {
+                DbBackend::MySql => vec![format!(
+                    "CREATE FULLTEXT INDEX ft_{table} ON {table} ({})",
+                    columns.join(", ")
+                )],
+                // kept in sync by triggers, as external content tables need an integer rowid
+                DbBackend::Sqlite => {
+                    let (list, new) = (columns.join(", "), prefixed("new", columns).join(", "));
+                    let set: Vec<_> = columns.iter().map(|c| format!("{c} = new.{c}")).collect();
+                    vec![
+                        format!(
+                            "CREATE VIRTUAL TABLE {table}_fts USING fts5(id UNINDEXED, {list})"
+                        ),
+                        format!(
+                            "INSERT INTO {table}_fts (id, {list}) SELECT id, {list} FROM {table}"
+                        ),
+                        format!(
+                            "CREATE TRIGGER {table}_fts_insert AFTER INSERT ON {table} BEGIN \
+                             INSERT INTO {table}_fts (id, {list}) VALUES (new.id, {new}); END"
+                        ),
+                        format!(
+                            "CREATE TRIGGER {table}_fts_update AFTER UPDATE OF {list} ON {table} \
+                             BEGIN UPDATE {table}_fts SET {} WHERE id = old.id; END",
+                            set.join(", ")
+                        ),
+                        format!(
+                            "CREATE TRIGGER {table}_fts_delete AFTER DELETE ON {table} BEGIN \
+                             DELETE FROM {table}_fts WHERE id = old.id; END"
+                        ),
+                    ]
+                }
+                // searched with LIKE
+                DbBackend::Postgres => vec![],
+            };
+            for statement in statements {
+                db.execute_unprepared(&statement).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for (table, _) in INDEXED {
+            let statements = match manager.get_database_backend() {
+                DbBackend::MySql => vec![format!("DROP INDEX ft_{table} ON {table}")],
+                DbBackend::Sqlite => vec![
+                    format!("DROP TRIGGER {table}_fts_insert"),
+                    format!("DROP TRIGGER {table}_fts_update"),
+                    format!("DROP TRIGGER {table}_fts_delete"),
+                    format!("DROP TABLE {table}_fts"),
+                ],
+                DbBackend::Postgres => vec![],
+            };
+            for statement in statements {
+                db.execute_unprepared(&statement).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn prefixed(prefix: &str, columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|c| format!("{prefix}.{c}")).collect()
+}
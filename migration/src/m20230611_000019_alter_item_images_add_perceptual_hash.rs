@@ -0,0 +1,31 @@
+use entity::model::item_image;
+use sea_orm_migration::prelude::*;
+
+/// Photos already uploaded get no hash, decoding them here would need the hashing of the api.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item_image::Entity)
+                    .add_column(ColumnDef::new(item_image::Column::PerceptualHash).big_integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(item_image::Entity)
+                    .drop_column(item_image::Column::PerceptualHash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
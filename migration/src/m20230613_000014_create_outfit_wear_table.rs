@@ -0,0 +1,21 @@
+use entity::model::{favorite, outfit_wear, outfit_wear_tag};
+use sea_orm_migration::{prelude::*, sea_orm::Schema};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(manager.get_database_backend());
+        manager.create_table(schema.create_table_from_entity(outfit_wear::Entity)).await?;
+        manager.create_table(schema.create_table_from_entity(outfit_wear_tag::Entity)).await?;
+        manager.create_table(schema.create_table_from_entity(favorite::Entity)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(favorite::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(outfit_wear_tag::Entity).to_owned()).await?;
+        manager.drop_table(Table::drop().table(outfit_wear::Entity).to_owned()).await
+    }
+}
@@ -0,0 +1,47 @@
+use entity::{
+    class::{locale::Locale, time_zone::TimeZone, unit_system::UnitSystem, week_start::WeekStart},
+    model::user,
+};
+use sea_orm_migration::prelude::*;
+
+/// Preferences of the existing users are the defaults new users get.
+///
+/// Timestamps written before this migration stay as they are: they were written with the offset of
+/// the server and denote the same instants, and MySQL `TIMESTAMP` columns store them in UTC anyway.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let preferences = [
+            (user::Column::TimeZone, TimeZone::default().to_string()),
+            (user::Column::Locale, Locale::default().to_string()),
+            (user::Column::UnitSystem, UnitSystem::default().to_string()),
+            (user::Column::WeekStart, WeekStart::default().to_string()),
+        ];
+        // one column per statement, as sqlite cannot add several at once
+        for (column, default) in preferences {
+            let alter = Table::alter()
+                .table(user::Entity)
+                .add_column(ColumnDef::new(column).string().not_null().default(default))
+                .to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let preferences = [
+            user::Column::TimeZone,
+            user::Column::Locale,
+            user::Column::UnitSystem,
+            user::Column::WeekStart,
+        ];
+        for column in preferences {
+            let drop = Table::alter().table(user::Entity).drop_column(column).to_owned();
+            manager.alter_table(drop).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use entity::{
+    class::{color::Rgb, id::Id},
+    model::item_color,
+};
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect},
+};
+
+/// Existing swatches get the L*a*b* of their rgb.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // one column per statement, as sqlite cannot add several at once
+        for column in lab_columns() {
+            let mut column = ColumnDef::new(column).integer().not_null().default(0).to_owned();
+            let alter = Table::alter().table(item_color::Entity).add_column(&mut column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        let db = manager.get_connection();
+        let swatches: Vec<(Id<item_color::Model>, Rgb)> = item_color::Entity::find()
+            .select_only()
+            .column(item_color::Column::Id)
+            .column(item_color::Column::Rgb)
+            .into_tuple()
+            .all(db)
+            .await?;
+        for (id, rgb) in swatches {
+            let mut update = item_color::Entity::update_many();
+            for (column, value) in lab_columns().into_iter().zip(item_color::Model::lab(&rgb)) {
+                update = update.col_expr(column, Expr::value(value));
+            }
+            update.filter(item_color::Column::Id.eq(id)).exec(db).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in lab_columns() {
+            let alter = Table::alter().table(item_color::Entity).drop_column(column).to_owned();
+            manager.alter_table(alter).await?;
+        }
+        Ok(())
+    }
+}
+
+fn lab_columns() -> [item_color::Column; 3] {
+    [item_color::Column::LabL, item_color::Column::LabA, item_color::Column::LabB]
+}